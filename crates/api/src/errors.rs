@@ -1,11 +1,14 @@
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response, Json},
 };
 use thiserror::Error;
 
 use crate::models::{ErrorResponse, ErrorDetail};
 
+/// `Retry-After` of 503 answers, maintenance has no known end
+const RETRY_AFTER_SECS: u64 = 60;
+
 #[derive(Error, Debug)]
 pub enum ApiError {
     #[error("Server not found: {server}")]
@@ -17,12 +20,24 @@ pub enum ApiError {
     #[error("Not found")]
     NotFound,
 
+    #[error("Unauthorized")]
+    Unauthorized,
+
     #[error("Internal error: {0}")]
     InternalError(String),
 
     #[error("Invalid path: {0}")]
     InvalidPath(String),
 
+    #[error("Invalid query: {0}")]
+    InvalidQuery(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Unavailable: {0}")]
+    Unavailable(String),
+
     #[error("Cache error: {0}")]
     CacheError(#[from] lighty_cache::CacheError),
 
@@ -32,14 +47,17 @@ pub enum ApiError {
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        let (status, error_response) = match self {
+        let retry_after = matches!(self, ApiError::Unavailable(_));
+        let (status, mut error_response) = match self {
             ApiError::ServerNotFound { server, available } => (
                 StatusCode::NOT_FOUND,
                 ErrorResponse {
                     error: ErrorDetail {
                         code: "SERVER_NOT_FOUND".to_string(),
                         message: format!("Server '{}' not found", server),
-                        available_servers: Some(available),
+                        // Empty with `available_servers = "none"` or no similar name
+                        available_servers: (!available.is_empty()).then_some(available),
+                        request_id: None,
                     },
                 },
             ),
@@ -50,6 +68,18 @@ impl IntoResponse for ApiError {
                         code: "NOT_FOUND".to_string(),
                         message: "Resource not found".to_string(),
                         available_servers: None,
+                        request_id: None,
+                    },
+                },
+            ),
+            ApiError::Unauthorized => (
+                StatusCode::UNAUTHORIZED,
+                ErrorResponse {
+                    error: ErrorDetail {
+                        code: "UNAUTHORIZED".to_string(),
+                        message: "Missing or invalid admin token".to_string(),
+                        available_servers: None,
+                        request_id: None,
                     },
                 },
             ),
@@ -60,6 +90,7 @@ impl IntoResponse for ApiError {
                         code: "INTERNAL_ERROR".to_string(),
                         message: msg,
                         available_servers: None,
+                        request_id: None,
                     },
                 },
             ),
@@ -70,6 +101,40 @@ impl IntoResponse for ApiError {
                         code: "INVALID_PATH".to_string(),
                         message: msg,
                         available_servers: None,
+                        request_id: None,
+                    },
+                },
+            ),
+            ApiError::InvalidQuery(msg) => (
+                StatusCode::BAD_REQUEST,
+                ErrorResponse {
+                    error: ErrorDetail {
+                        code: "INVALID_QUERY".to_string(),
+                        message: msg,
+                        available_servers: None,
+                        request_id: None,
+                    },
+                },
+            ),
+            ApiError::Conflict(msg) => (
+                StatusCode::CONFLICT,
+                ErrorResponse {
+                    error: ErrorDetail {
+                        code: "CONFLICT".to_string(),
+                        message: msg,
+                        available_servers: None,
+                        request_id: None,
+                    },
+                },
+            ),
+            ApiError::Unavailable(msg) => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                ErrorResponse {
+                    error: ErrorDetail {
+                        code: "UNAVAILABLE".to_string(),
+                        message: msg,
+                        available_servers: None,
+                        request_id: None,
                     },
                 },
             ),
@@ -80,6 +145,7 @@ impl IntoResponse for ApiError {
                         code: "CACHE_ERROR".to_string(),
                         message: err.to_string(),
                         available_servers: None,
+                        request_id: None,
                     },
                 },
             ),
@@ -90,11 +156,17 @@ impl IntoResponse for ApiError {
                         code: "IO_ERROR".to_string(),
                         message: err.to_string(),
                         available_servers: None,
+                        request_id: None,
                     },
                 },
             ),
         };
 
-        (status, Json(error_response)).into_response()
+        error_response.error.request_id = crate::handlers::request_id::current_request_id();
+        let mut response = (status, Json(error_response)).into_response();
+        if retry_after {
+            response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(RETRY_AFTER_SECS));
+        }
+        response
     }
 }
@@ -0,0 +1,270 @@
+use super::models::AppState;
+use crate::errors::ApiError;
+use crate::models::{
+    CorruptFile, FileCacheStats, MaintenanceRequest, StatsResponse, TasksResponse, VerifyQuery, VerifyReport,
+};
+use axum::{
+    extract::{Path as AxumPath, Query, Request, State},
+    http::header,
+    middleware::Next,
+    response::{Json, Response},
+};
+use lighty_cache::{CacheError, FreezeStatus, MaintenanceStatus, PurgeSummary, ReuploadSummary};
+use lighty_filesystem::FileSystem;
+use lighty_models::ManifestEntry;
+use lighty_storage::StorageBackend;
+use std::collections::HashSet;
+use subtle::ConstantTimeEq;
+
+/// Read buffer used when re-hashing sampled files
+const VERIFY_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Guards /admin/* routes with the `server.admin_token` bearer token
+/// Admin endpoints answer 404 while no token is configured
+pub async fn require_admin_token(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let admin_token = state.cache.config.read().await.server.admin_token.clone();
+    if admin_token.is_empty() {
+        return Err(ApiError::NotFound);
+    }
+
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    // Constant-time, so response timing doesn't reveal how much of the token matched
+    let authorized = provided.is_some_and(|provided| bool::from(provided.as_bytes().ct_eq(admin_token.as_bytes())));
+    if !authorized {
+        return Err(ApiError::Unauthorized);
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Re-uploads every file of a server to remote storage, even if unchanged
+pub async fn reupload_server(
+    State(state): State<AppState>,
+    AxumPath(server_name): AxumPath<String>,
+) -> Result<Json<ReuploadSummary>, ApiError> {
+    match state.cache.reupload_server(&server_name).await {
+        Ok(summary) => Ok(Json(summary)),
+        Err(CacheError::ServerNotFound(_)) => Err(ApiError::ServerNotFound {
+            server: server_name,
+            available: state.cache.get_all_servers().await,
+        }),
+        Err(e @ CacheError::InMaintenance(_)) => Err(ApiError::Unavailable(e.to_string())),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Purges a server from the edge cache without waiting for a file change
+/// Purge failures are reported in the summary rather than as an error status
+pub async fn purge_server(
+    State(state): State<AppState>,
+    AxumPath(server_name): AxumPath<String>,
+) -> Result<Json<PurgeSummary>, ApiError> {
+    match state.cache.purge_server(&server_name).await {
+        Ok(summary) => Ok(Json(summary)),
+        Err(CacheError::ServerNotFound(_)) => Err(ApiError::ServerNotFound {
+            server: server_name,
+            available: state.cache.get_all_servers().await,
+        }),
+        Err(e @ CacheError::PurgeNotConfigured) => Err(ApiError::Conflict(e.to_string())),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Pins a server to its published metadata while new files are staged
+/// Idempotent: freezing a frozen server reports its pending changes
+pub async fn freeze_server(
+    State(state): State<AppState>,
+    AxumPath(server_name): AxumPath<String>,
+) -> Result<Json<FreezeStatus>, ApiError> {
+    match state.cache.freeze(&server_name).await {
+        Ok(status) => Ok(Json(status)),
+        Err(CacheError::ServerNotFound(_)) => Err(ApiError::ServerNotFound {
+            server: server_name,
+            available: state.cache.get_all_servers().await,
+        }),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Unfreezes a server and publishes the staged files at once
+pub async fn promote_server(
+    State(state): State<AppState>,
+    AxumPath(server_name): AxumPath<String>,
+) -> Result<Json<FreezeStatus>, ApiError> {
+    match state.cache.unfreeze_and_promote(&server_name).await {
+        Ok(status) => Ok(Json(status)),
+        Err(CacheError::ServerNotFound(_)) => Err(ApiError::ServerNotFound {
+            server: server_name,
+            available: state.cache.get_all_servers().await,
+        }),
+        Err(e @ CacheError::NotFrozen(_)) => Err(ApiError::Conflict(e.to_string())),
+        Err(e @ (CacheError::InMaintenance(_) | CacheError::PromoteFailed(_))) => Err(ApiError::Unavailable(e.to_string())),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Checks that every file of the cached metadata is on disk (or in remote storage) with its size, re-hashing `?sample=N` of them
+/// Drift is reported in the body, the status stays 200
+pub async fn verify_server(
+    State(state): State<AppState>,
+    AxumPath(server_name): AxumPath<String>,
+    Query(query): Query<VerifyQuery>,
+) -> Result<Json<VerifyReport>, ApiError> {
+    let Some(server_config) = state.cache.get_server_config(&server_name).await else {
+        return Err(state.server_not_found(server_name).await);
+    };
+    let version = state.cache.get(&server_name).await.ok_or(ApiError::NotFound)?;
+    let entries = version.manifest();
+    let server_dir = FileSystem::build_server_path(&state.base_path, &server_config.name);
+    // Published remotely without a local copy (`keep_local_backup = false`): the stored objects are checked instead
+    let remote = state.remote_storage.as_deref().filter(|_| !state.keep_local_backup);
+    let origin = remote.map(|storage| storage.get_url(""));
+
+    // Evenly spread so a sample covers every category, not just the first one
+    let sample = query.sample.min(entries.len());
+    let sampled: HashSet<usize> = (0..sample).map(|i| i * entries.len() / sample).collect();
+
+    let mut report = VerifyReport {
+        server: server_name,
+        checked: entries.len(),
+        hashed: 0,
+        missing: Vec::new(),
+        corrupt: Vec::new(),
+    };
+
+    for (index, entry) in entries.into_iter().enumerate() {
+        let remote_key = origin.as_deref().and_then(|origin| entry.url.strip_prefix(origin));
+        if let (Some(storage), Some(remote_key)) = (remote, remote_key) {
+            let remote_key = remote_key.to_string();
+            verify_remote(storage, &remote_key, entry, sampled.contains(&index), &mut report).await;
+            continue;
+        }
+
+        let file_path = server_dir.join(server_config.local_path(&entry.path));
+        let size = match tokio::fs::metadata(&file_path).await {
+            Ok(metadata) if metadata.is_file() => metadata.len(),
+            Ok(_) => {
+                report.missing.push(entry.path);
+                continue;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                report.missing.push(entry.path);
+                continue;
+            }
+            Err(e) => {
+                report.corrupt.push(CorruptFile { path: entry.path, reason: e.to_string() });
+                continue;
+            }
+        };
+
+        if size != entry.size {
+            let reason = format!("size {} on disk, {} in metadata", size, entry.size);
+            report.corrupt.push(CorruptFile { path: entry.path, reason });
+            continue;
+        }
+
+        if sampled.contains(&index) {
+            report.hashed += 1;
+            match lighty_utils::compute_sha1_with_size(&file_path, VERIFY_BUFFER_SIZE).await {
+                Ok((sha1, _)) if sha1 != entry.sha1 => {
+                    let reason = format!("sha1 {} on disk, {} in metadata", sha1, entry.sha1);
+                    report.corrupt.push(CorruptFile { path: entry.path, reason });
+                }
+                Ok(_) => {}
+                Err(e) => report.corrupt.push(CorruptFile { path: entry.path, reason: e.to_string() }),
+            }
+        }
+    }
+
+    if !report.missing.is_empty() || !report.corrupt.is_empty() {
+        tracing::warn!(
+            "Verify of '{}': {} missing, {} corrupt out of {} files",
+            report.server, report.missing.len(), report.corrupt.len(), report.checked
+        );
+    }
+
+    Ok(Json(report))
+}
+
+/// Checks a file published to remote storage: its size, and with `hash` the SHA1 recorded at upload
+/// Objects uploaded by other tools have no recorded SHA1 and are only size-checked
+async fn verify_remote(
+    storage: &dyn StorageBackend,
+    remote_key: &str,
+    entry: ManifestEntry,
+    hash: bool,
+    report: &mut VerifyReport,
+) {
+    let remote = match storage.head_file(remote_key).await {
+        Ok(Some(remote)) => remote,
+        Ok(None) => {
+            report.missing.push(entry.path);
+            return;
+        }
+        Err(e) => {
+            report.corrupt.push(CorruptFile { path: entry.path, reason: e.to_string() });
+            return;
+        }
+    };
+
+    if remote.size != entry.size {
+        let reason = format!("size {} in remote storage, {} in metadata", remote.size, entry.size);
+        report.corrupt.push(CorruptFile { path: entry.path, reason });
+        return;
+    }
+
+    if let Some(sha1) = remote.sha1.filter(|_| hash) {
+        report.hashed += 1;
+        if !sha1.eq_ignore_ascii_case(&entry.sha1) {
+            let reason = format!("sha1 {} in remote storage, {} in metadata", sha1, entry.sha1);
+            report.corrupt.push(CorruptFile { path: entry.path, reason });
+        }
+    }
+}
+
+/// Reports RAM cache usage and per-server scan durations
+pub async fn get_stats(State(state): State<AppState>) -> Json<StatsResponse> {
+    let (entries, size_kb) = state.cache.get_cache_stats().await;
+    Json(StatsResponse {
+        file_cache: FileCacheStats { entries, size_kb },
+        scans: state.cache.get_scan_durations().await,
+    })
+}
+
+/// Lists the cache's background tasks (rescan loop, RAM cache loads)
+pub async fn get_tasks(State(state): State<AppState>) -> Json<TasksResponse> {
+    let tasks = state.cache.background_tasks();
+    Json(TasksResponse {
+        running: tasks.iter().filter(|task| task.running).count(),
+        tasks,
+    })
+}
+
+/// Reports whether maintenance mode is on, with its reason
+pub async fn get_maintenance(State(state): State<AppState>) -> Json<MaintenanceStatus> {
+    Json(state.cache.get_maintenance_status())
+}
+
+/// Turns maintenance mode on or off until the next restart or `maintenance_mode` change in the config
+pub async fn set_maintenance(
+    State(state): State<AppState>,
+    Json(request): Json<MaintenanceRequest>,
+) -> Json<MaintenanceStatus> {
+    if request.enabled {
+        let reason = request
+            .reason
+            .unwrap_or_else(|| "Enabled through /admin/maintenance".to_string());
+        Json(state.cache.enter_maintenance(reason))
+    } else {
+        Json(state.cache.exit_maintenance())
+    }
+}
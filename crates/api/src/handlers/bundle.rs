@@ -0,0 +1,94 @@
+use super::models::AppState;
+use crate::errors::ApiError;
+use async_zip::base::write::ZipFileWriter;
+use async_zip::{Compression, ZipEntryBuilder};
+use axum::{
+    body::Body,
+    extract::{Path as AxumPath, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use futures::AsyncWriteExt;
+use lighty_filesystem::FileSystem;
+use lighty_models::ManifestEntry;
+use std::path::PathBuf;
+use tokio::io::{AsyncReadExt, DuplexStream};
+use tokio_util::io::ReaderStream;
+
+/// Bytes buffered between the zip writer and the response body
+const PIPE_CAPACITY: usize = 256 * 1024;
+
+/// Streams every file of a server as one zip, laid out like the client install
+/// Built on the fly through a bounded pipe, so memory stays flat whatever the pack size
+pub async fn get_server_bundle(
+    State(state): State<AppState>,
+    AxumPath(server_name): AxumPath<String>,
+) -> Result<Response, ApiError> {
+    let server_config = match state.cache.get_server_config(&server_name).await {
+        Some(config) if config.enabled => config,
+        _ => {
+            return Err(state.server_not_found(server_name).await);
+        }
+    };
+    if !server_config.enable_bundle {
+        return Err(ApiError::NotFound);
+    }
+
+    let version = state.cache.get(&server_name).await.ok_or_else(|| ApiError::NotFound)?;
+    let entries = version.manifest();
+    let server_dir = FileSystem::build_server_path(&state.base_path, &server_config.name);
+
+    // Read from the server's own folders (`extra_dir`), zipped under the manifest paths
+    let files = entries
+        .into_iter()
+        .map(|entry| (server_dir.join(server_config.local_path(&entry.path)), entry))
+        .collect();
+
+    let (writer, reader) = tokio::io::duplex(PIPE_CAPACITY);
+    tokio::spawn(async move {
+        if let Err(e) = write_bundle(writer, files).await {
+            // The client gets a truncated archive, which fails its zip check
+            tracing::warn!("Bundle for '{}' aborted: {}", server_name, e);
+        }
+    });
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}.zip\"", server_config.name),
+            ),
+        ],
+        Body::from_stream(ReaderStream::new(reader)),
+    )
+        .into_response())
+}
+
+/// Writes the manifest files into a zip, each under its category-prefixed path
+async fn write_bundle(
+    writer: DuplexStream,
+    files: Vec<(PathBuf, ManifestEntry)>,
+) -> Result<(), async_zip::error::ZipError> {
+    let mut zip = ZipFileWriter::with_tokio(writer);
+    let mut buffer = vec![0u8; 64 * 1024];
+
+    for (file_path, entry) in files {
+        let mut file = tokio::fs::File::open(file_path).await?;
+        let builder = ZipEntryBuilder::new(entry.path.into(), Compression::Deflate);
+        let mut entry_writer = zip.write_entry_stream(builder).await?;
+
+        loop {
+            let read = file.read(&mut buffer).await?;
+            if read == 0 {
+                break;
+            }
+            entry_writer.write_all(&buffer[..read]).await?;
+        }
+        entry_writer.close().await?;
+    }
+
+    zip.close().await?;
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::response::Response;
+use lighty_config::ServerSettings;
+
+/// `Cache-Control` values of file and metadata responses, built from `server.*_max_age_secs`
+/// None (max-age 0) sends no header
+#[derive(Debug, Clone)]
+pub struct CacheControl {
+    /// Files served by path: their content changes with the modpack
+    pub(super) files: Option<HeaderValue>,
+    /// Files served by hash: a hash always names the same bytes
+    pub(super) hashed_files: Option<HeaderValue>,
+    /// `/{server}.json`, manifest and tree
+    pub(super) metadata: Option<HeaderValue>,
+}
+
+impl CacheControl {
+    pub fn new(server: &ServerSettings) -> Self {
+        Self {
+            files: policy(server.file_max_age_secs, false),
+            hashed_files: policy(server.hashed_file_max_age_secs, true),
+            metadata: policy(server.metadata_max_age_secs, false),
+        }
+    }
+}
+
+/// `public, max-age=N` (plus `immutable`), or None for 0
+/// Not every response carries a validator (metadata, remote files), so 0 leaves caching to the client instead of `no-cache`
+fn policy(max_age_secs: u64, immutable: bool) -> Option<HeaderValue> {
+    let value = match (max_age_secs, immutable) {
+        (0, _) => return None,
+        (max_age, false) => format!("public, max-age={}", max_age),
+        (max_age, true) => format!("public, max-age={}, immutable", max_age),
+    };
+    Some(HeaderValue::from_str(&value).expect("Cache-Control value is ASCII"))
+}
+
+/// Sets `Cache-Control` on successful and `304` responses; errors and redirects are left uncached by policy
+pub(super) fn apply(response: &mut Response, value: Option<&HeaderValue>) {
+    let Some(value) = value else {
+        return;
+    };
+    if response.status().is_success() || response.status() == StatusCode::NOT_MODIFIED {
+        response.headers_mut().insert(header::CACHE_CONTROL, value.clone());
+    }
+}
@@ -0,0 +1,31 @@
+use axum::http::{header, HeaderMap};
+
+/// Checks whether the client accepts brotli responses (`Accept-Encoding: br`, not `br;q=0`)
+pub(super) fn accepts_brotli(headers: &HeaderMap) -> bool {
+    accepts_encoding(headers, "br")
+}
+
+/// Checks whether the client accepts gzip responses (`Accept-Encoding: gzip`, not `gzip;q=0`)
+pub(super) fn accepts_gzip(headers: &HeaderMap) -> bool {
+    accepts_encoding(headers, "gzip")
+}
+
+fn accepts_encoding(headers: &HeaderMap, encoding: &str) -> bool {
+    headers
+        .get(header::ACCEPT_ENCODING)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| {
+            accept.split(',').any(|coding| {
+                let mut params = coding.split(';');
+                let name = params.next().unwrap_or_default().trim();
+                let refused = params.any(|param| {
+                    param
+                        .trim()
+                        .strip_prefix("q=")
+                        .and_then(|q| q.trim().parse::<f32>().ok())
+                        .is_some_and(|q| q <= 0.0)
+                });
+                name.eq_ignore_ascii_case(encoding) && !refused
+            })
+        })
+}
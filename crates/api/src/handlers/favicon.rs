@@ -0,0 +1,6 @@
+use axum::http::StatusCode;
+
+/// Answers browsers' `/favicon.ico` requests, which would otherwise reach `serve_file` and log a resolution warning
+pub async fn favicon() -> StatusCode {
+    StatusCode::NO_CONTENT
+}
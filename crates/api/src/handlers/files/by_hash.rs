@@ -0,0 +1,51 @@
+use super::handler::serve_resolved_file;
+use crate::handlers::cache_control;
+use crate::handlers::models::AppState;
+use crate::errors::ApiError;
+use axum::{
+    extract::{Path as AxumPath, State},
+    http::HeaderMap,
+    response::Response,
+};
+
+/// Serves a file by its SHA1 (`GET /{server}/by-hash/{sha1}`), for content-addressed caching
+/// Resolved through the sha1→paths index of the server, then served like `serve_file`
+pub async fn serve_file_by_hash(
+    State(state): State<AppState>,
+    AxumPath((server_name, sha1)): AxumPath<(String, String)>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    if sha1.len() != 40 || !sha1.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(ApiError::InvalidPath(format!("'{}' is not a SHA1 hash", sha1)));
+    }
+
+    let version_data = match state.cache.get_version(&server_name).await {
+        Some(v) => v,
+        None => return Err(state.server_not_found(server_name).await),
+    };
+
+    let server_config = state
+        .cache
+        .get_server_config(&server_name)
+        .await
+        .ok_or(ApiError::NotFound)?;
+
+    // Identical files share a hash: a path that can't be served (e.g. disabled category) falls back to the next
+    let mut response = None;
+    for actual_path in version_data.paths_for_sha1(&sha1) {
+        tracing::debug!("serve_file_by_hash: {} resolved to '{}'", sha1, actual_path);
+        match serve_resolved_file(&state, &headers, &server_name, &version_data, &server_config, actual_path).await {
+            Ok(served) => {
+                response = Some(served);
+                break;
+            }
+            Err(ApiError::NotFound) => continue,
+            Err(e) => return Err(e),
+        }
+    }
+
+    let mut response = response.ok_or(ApiError::NotFound)?;
+    // A hash always names the same bytes, so CDNs and clients may keep the response
+    cache_control::apply(&mut response, state.cache_control.hashed_files.as_ref());
+    Ok(response)
+}
@@ -1,28 +1,79 @@
+use super::conditional;
+use super::models::CONTENT_SHA1_HEADER;
 use crate::handlers::models::AppState;
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderName, StatusCode},
     response::{IntoResponse, Response},
 };
+use httpdate::HttpDate;
 
 /// Attempts to serve file from RAM cache
+/// With `expected_sha1`, a cached entry that doesn't match is evicted and the caller falls back to disk
+/// The brotli variant is served when pre-compressed and `accepts_brotli` (SHA1 header stays the raw file's)
+/// Answers 304 when the entry's mtime is not after `if_modified_since`
 pub async fn try_serve_from_cache(
     state: &AppState,
     server_name: &str,
     actual_path: &str,
+    expected_sha1: Option<&str>,
+    accepts_brotli: bool,
+    if_modified_since: Option<HttpDate>,
 ) -> Option<Response> {
     if let Some(file_cache) = state.cache.get_file(server_name, actual_path).await {
+        if let Some(expected) = expected_sha1 {
+            if !file_cache.sha1.eq_ignore_ascii_case(expected) {
+                state
+                    .cache
+                    .evict_mismatched_file(server_name, actual_path, expected, &file_cache.sha1)
+                    .await;
+                return None;
+            }
+        }
+
+        if let Some(response) = file_cache
+            .modified
+            .and_then(|modified| conditional::not_modified(if_modified_since, modified, Some(&file_cache.sha1)))
+        {
+            return Some(response);
+        }
+
         tracing::debug!("serve_file: serving from RAM cache");
 
-        // Zero-copy: file_cache.data is already Bytes which uses Arc internally
-        // Cloning Bytes is cheap (just increments reference count)
-        return Some(
-            (
+        // Cached entries carry the guessed type, `[mime] overrides` still win
+        let mime_type = match state.mime_types.override_for(std::path::Path::new(actual_path)) {
+            Some(mime) => mime.to_string(),
+            None => file_cache.mime_type,
+        };
+
+        let mut response = match file_cache.brotli.filter(|_| accepts_brotli) {
+            Some(brotli) => (
                 StatusCode::OK,
-                [(axum::http::header::CONTENT_TYPE, file_cache.mime_type.clone())],
+                [
+                    (header::CONTENT_TYPE, mime_type),
+                    (header::CONTENT_ENCODING, "br".to_string()),
+                    (header::VARY, "accept-encoding".to_string()),
+                    (HeaderName::from_static(CONTENT_SHA1_HEADER), file_cache.sha1),
+                ],
+                brotli,
+            )
+                .into_response(),
+            // Zero-copy: file_cache.data is already Bytes which uses Arc internally
+            // Cloning Bytes is cheap (just increments reference count)
+            None => (
+                StatusCode::OK,
+                [
+                    (axum::http::header::CONTENT_TYPE, mime_type),
+                    (axum::http::HeaderName::from_static(CONTENT_SHA1_HEADER), file_cache.sha1.clone()),
+                ],
                 file_cache.data,
             )
                 .into_response(),
-        );
+        };
+
+        if let Some(modified) = file_cache.modified {
+            response.headers_mut().insert(header::LAST_MODIFIED, conditional::last_modified(modified));
+        }
+        return Some(response);
     }
 
     None
@@ -0,0 +1,42 @@
+use super::models::CONTENT_SHA1_HEADER;
+use axum::{
+    http::{header, HeaderMap, HeaderName, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use httpdate::HttpDate;
+use std::time::SystemTime;
+
+/// Date of the client's `If-Modified-Since`, if it should be evaluated
+/// Ignored alongside `If-None-Match` (entity tags take precedence, RFC 7232 §3.3) and when unparsable
+pub(super) fn if_modified_since(headers: &HeaderMap) -> Option<HttpDate> {
+    if headers.contains_key(header::IF_NONE_MATCH) {
+        return None;
+    }
+    headers
+        .get(header::IF_MODIFIED_SINCE)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// `Last-Modified` value of a file modification time (IMF-fixdate, RFC 7231 §7.1.1.1)
+pub(super) fn last_modified(modified: SystemTime) -> HeaderValue {
+    HeaderValue::from_str(&HttpDate::from(modified).to_string()).expect("HTTP dates are ASCII")
+}
+
+/// `304 Not Modified` when the file hasn't changed since `since` (compared at one-second precision)
+pub(super) fn not_modified(since: Option<HttpDate>, modified: SystemTime, sha1: Option<&str>) -> Option<Response> {
+    if HttpDate::from(modified) > since? {
+        return None;
+    }
+
+    let mut response = StatusCode::NOT_MODIFIED.into_response();
+    let headers = response.headers_mut();
+    headers.insert(header::LAST_MODIFIED, last_modified(modified));
+    if let Some(value) = sha1.and_then(|sha1| HeaderValue::from_str(sha1).ok()) {
+        headers.insert(HeaderName::from_static(CONTENT_SHA1_HEADER), value);
+    }
+    Some(response)
+}
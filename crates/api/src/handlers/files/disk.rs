@@ -1,23 +1,73 @@
+use super::conditional;
+use super::mime::MimeTypes;
+use super::models::CONTENT_SHA1_HEADER;
+use super::throttle;
 use crate::errors::ApiError;
 use axum::{
-    body::Body,
-    http::StatusCode,
+    body::{Body, Bytes},
+    http::{HeaderName, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
 };
-use std::path::PathBuf;
+use httpdate::HttpDate;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 use tokio_util::io::ReaderStream;
 
+/// Size above which a file is streamed rather than loaded into memory, by MIME type
+/// Built from `server.streaming_threshold_mb` and `server.streaming_thresholds_mb`
+#[derive(Debug, Clone, Default)]
+pub struct StreamingThresholds {
+    default_bytes: u64,
+    /// Lowercased MIME type (`application/json`) or top-level type (`image/*`)
+    by_mime: HashMap<String, u64>,
+}
+
+impl StreamingThresholds {
+    pub fn new(default_mb: u64, by_mime_mb: &HashMap<String, u64>) -> Self {
+        Self {
+            default_bytes: default_mb * 1024 * 1024,
+            by_mime: by_mime_mb
+                .iter()
+                .map(|(mime, mb)| (mime.trim().to_lowercase(), mb * 1024 * 1024))
+                .collect(),
+        }
+    }
+
+    /// Exact MIME type first, then `type/*`, then the global threshold
+    pub fn for_mime(&self, mime_type: &str) -> u64 {
+        let mime_type = mime_type.to_lowercase();
+        if let Some(&bytes) = self.by_mime.get(&mime_type) {
+            return bytes;
+        }
+        mime_type
+            .split_once('/')
+            .and_then(|(top_level, _)| self.by_mime.get(&format!("{}/*", top_level)))
+            .copied()
+            .unwrap_or(self.default_bytes)
+    }
+}
+
 /// Serves a file from disk, either by streaming or loading into memory
-/// The threshold is configurable via server.streaming_threshold_mb
-pub async fn serve_from_disk(full_path: PathBuf, streaming_threshold_bytes: u64) -> Result<Response, ApiError> {
+/// The threshold depends on the file's MIME type (see `StreamingThresholds` and `MimeTypes`)
+/// Streamed files are paced to `max_bytes_per_sec` (0 = unlimited), small files are never slowed
+/// `sha1` comes from the cached metadata and is exposed via the X-Content-SHA1 header
+/// The file mtime is sent as `Last-Modified`; 304 (nothing read) when not after `if_modified_since`
+/// Also returns the contents and mtime of files loaded into memory, so the caller can cache them without another stat
+pub async fn serve_from_disk(
+    full_path: PathBuf,
+    streaming_thresholds: &StreamingThresholds,
+    mime_types: &MimeTypes,
+    max_bytes_per_sec: u64,
+    sha1: Option<String>,
+    if_modified_since: Option<HttpDate>,
+) -> Result<(Response, Option<(Bytes, Option<SystemTime>)>), ApiError> {
     if !full_path.exists() {
         tracing::warn!("serve_file: File does not exist: '{}'", full_path.display());
         return Err(ApiError::NotFound);
     }
 
-    let mime_type = mime_guess::from_path(&full_path)
-        .first_or_octet_stream()
-        .to_string();
+    let mime_type = mime_types.for_path(&full_path);
 
     // Get file metadata to check size
     let metadata = tokio::fs::metadata(&full_path).await.map_err(|e| {
@@ -30,19 +80,47 @@ pub async fn serve_from_disk(full_path: PathBuf, streaming_threshold_bytes: u64)
     })?;
 
     let file_size = metadata.len();
+    let modified = metadata.modified().ok();
+
+    if let Some(response) =
+        modified.and_then(|modified| conditional::not_modified(if_modified_since, modified, sha1.as_deref()))
+    {
+        return Ok((response, None));
+    }
 
-    if file_size > streaming_threshold_bytes {
-        stream_large_file(full_path, mime_type, file_size).await
+    let (mut response, loaded) = if file_size > streaming_thresholds.for_mime(&mime_type) {
+        (stream_large_file(full_path, mime_type, file_size, max_bytes_per_sec).await?, None)
     } else {
-        load_small_file(full_path, mime_type).await
+        let content = load_small_file(&full_path).await?;
+        let response = (
+            StatusCode::OK,
+            [(axum::http::header::CONTENT_TYPE, mime_type)],
+            content.clone(),
+        )
+            .into_response();
+        (response, Some((content, modified)))
+    };
+
+    if let Some(value) = sha1.and_then(|sha1| HeaderValue::from_str(&sha1).ok()) {
+        response
+            .headers_mut()
+            .insert(HeaderName::from_static(CONTENT_SHA1_HEADER), value);
+    }
+    if let Some(modified) = modified {
+        response
+            .headers_mut()
+            .insert(axum::http::header::LAST_MODIFIED, conditional::last_modified(modified));
     }
+
+    Ok((response, loaded))
 }
 
-/// Streams a large file
+/// Streams a large file, throttled if a bandwidth cap is set
 async fn stream_large_file(
     full_path: PathBuf,
     mime_type: String,
     file_size: u64,
+    max_bytes_per_sec: u64,
 ) -> Result<Response, ApiError> {
     tracing::debug!(
         "serve_file: streaming large file ({:.2} MB)",
@@ -60,7 +138,11 @@ async fn stream_large_file(
 
     // Convert file to stream
     let stream = ReaderStream::new(file);
-    let body = Body::from_stream(stream);
+    let body = if max_bytes_per_sec > 0 {
+        Body::from_stream(throttle::throttle(stream, max_bytes_per_sec))
+    } else {
+        Body::from_stream(stream)
+    };
 
     Ok((
         StatusCode::OK,
@@ -71,8 +153,8 @@ async fn stream_large_file(
 }
 
 /// Loads a small file into memory for better performance
-async fn load_small_file(full_path: PathBuf, mime_type: String) -> Result<Response, ApiError> {
-    let content = tokio::fs::read(&full_path).await.map_err(|e| {
+async fn load_small_file(full_path: &Path) -> Result<Bytes, ApiError> {
+    let content = tokio::fs::read(full_path).await.map_err(|e| {
         tracing::error!(
             "serve_file: Failed to read file '{}': {}",
             full_path.display(),
@@ -81,10 +163,5 @@ async fn load_small_file(full_path: PathBuf, mime_type: String) -> Result<Respon
         ApiError::NotFound
     })?;
 
-    Ok((
-        StatusCode::OK,
-        [(axum::http::header::CONTENT_TYPE, mime_type)],
-        content,
-    )
-        .into_response())
+    Ok(Bytes::from(content))
 }
@@ -1,31 +1,40 @@
-use super::{cache, disk, parser, resolver};
+use super::{cache, conditional, disk, parser, remote, resolver, validator};
+use crate::handlers::cache_control;
 use crate::handlers::models::AppState;
 use crate::errors::ApiError;
+use lighty_config::ServerConfig;
 use lighty_filesystem::FileSystem;
+use lighty_models::VersionBuilder;
 use axum::{
     extract::State,
+    http::HeaderMap,
     response::Response,
 };
 
 pub async fn serve_file(
     State(state): State<AppState>,
     uri: axum::http::Uri,
+    headers: HeaderMap,
 ) -> Result<Response, ApiError> {
     let requested_path = uri.path().trim_start_matches('/');
     tracing::debug!("serve_file: requested_path = '{}'", requested_path);
 
+    // `/{server}/` would otherwise resolve as an empty file path
+    if state.redirect_bare_server_name {
+        if let Some(name) = requested_path.strip_suffix('/').filter(|name| !name.contains('/')) {
+            if let Some(response) = crate::handlers::servers::metadata_redirect(&state, name, uri.query()).await {
+                return Ok(response);
+            }
+        }
+    }
+
     // Parse and validate request path
     let parsed = parser::parse_request_path(requested_path)?;
 
     // Get server metadata from cache
     let version_data = match state.cache.get_version(&parsed.server_name).await {
         Some(v) => v,
-        None => {
-            return Err(ApiError::ServerNotFound {
-                server: parsed.server_name,
-                available: state.cache.get_all_servers().await,
-            });
-        }
+        None => return Err(state.server_not_found(parsed.server_name).await),
     };
 
     let server_config = state
@@ -40,6 +49,7 @@ pub async fn serve_file(
         &parsed.url_file_part,
         &state.base_url,
         &parsed.server_name,
+        state.case_insensitive_paths.as_deref(),
     )
     .ok_or_else(|| {
         tracing::warn!("serve_file: Could not resolve path for '{}'", parsed.url_file_part);
@@ -48,8 +58,67 @@ pub async fn serve_file(
 
     tracing::debug!("serve_file: resolved actual_path = '{}'", actual_path);
 
-    // Try to serve from RAM cache first
-    if let Some(response) = cache::try_serve_from_cache(&state, &parsed.server_name, &actual_path).await {
+    let mut response =
+        serve_resolved_file(&state, &headers, &parsed.server_name, &version_data, &server_config, &actual_path).await?;
+    cache_control::apply(&mut response, state.cache_control.files.as_ref());
+    Ok(response)
+}
+
+/// Serves a file already resolved to its category-prefixed path (e.g. "mods/foo.jar")
+/// Shared by URL and content-addressed lookups: RAM cache, then disk, then remote storage
+pub(super) async fn serve_resolved_file(
+    state: &AppState,
+    headers: &HeaderMap,
+    server_name: &str,
+    version_data: &VersionBuilder,
+    server_config: &ServerConfig,
+    actual_path: &str,
+) -> Result<Response, ApiError> {
+    // The URL map comes from the scan, but the disk fallback joins this path onto the server folder
+    let relative_path = validator::normalize_relative_path(&server_config.local_path(actual_path))?;
+
+    // Disabled categories stay hidden even before the rescan drops them from the URL map
+    if !validator::is_category_enabled(actual_path, server_config) {
+        tracing::debug!("serve_file: refusing '{}', category disabled for {}", actual_path, server_config.name);
+        return Err(ApiError::NotFound);
+    }
+
+    // Defense in depth: the URL map lists whatever landed in the server folder
+    if let Some(extensions) = &state.servable_extensions {
+        if !validator::is_servable_extension(actual_path, extensions) {
+            tracing::warn!("serve_file: refusing '{}', extension not in server.servable_extensions", actual_path);
+            return Err(ApiError::NotFound);
+        }
+    }
+
+    // Frozen: a file changed on disk since the freeze is not the pinned one, only the remote copy still is
+    if state.cache.is_staged_file(server_name, actual_path) {
+        let storage = state.remote_storage.as_deref().ok_or_else(|| {
+            tracing::debug!("serve_file: refusing '{}', staged while {} is frozen", actual_path, server_name);
+            ApiError::NotFound
+        })?;
+        let entry = resolver::resolve_file_entry(version_data, actual_path).ok_or(ApiError::NotFound)?;
+        let mime_type = state.mime_types.for_path(std::path::Path::new(actual_path));
+        return remote::serve_from_remote(
+            storage,
+            &entry.url,
+            mime_type,
+            state.max_download_bytes_per_sec,
+            Some(entry.sha1),
+        )
+        .await;
+    }
+
+    let sha1 = resolver::resolve_file_sha1(version_data, actual_path);
+
+    // Try to serve from RAM cache first (verified against the metadata SHA1 if enabled)
+    let expected_sha1 = sha1.as_deref().filter(|_| state.verify_on_serve);
+    let accepts_brotli = crate::handlers::encoding::accepts_brotli(headers);
+    let if_modified_since = conditional::if_modified_since(headers);
+    if let Some(response) =
+        cache::try_serve_from_cache(state, server_name, actual_path, expected_sha1, accepts_brotli, if_modified_since)
+            .await
+    {
         return Ok(response);
     }
 
@@ -57,7 +126,38 @@ pub async fn serve_file(
     tracing::debug!("serve_file: file not in cache, falling back to disk");
 
     let full_path = FileSystem::build_server_path(&state.base_path, &server_config.name)
-        .join(&actual_path);
+        .join(&relative_path);
+
+    // Published remotely without a local copy (`keep_local_backup = false`): proxy it
+    if let Some(storage) = state.remote_storage.as_deref().filter(|_| !state.keep_local_backup) {
+        if !tokio::fs::try_exists(&full_path).await.unwrap_or(false) {
+            let entry = resolver::resolve_file_entry(version_data, actual_path).ok_or(ApiError::NotFound)?;
+            let mime_type = state.mime_types.for_path(&full_path);
+            return remote::serve_from_remote(
+                storage,
+                &entry.url,
+                mime_type,
+                state.max_download_bytes_per_sec,
+                Some(entry.sha1),
+            )
+            .await;
+        }
+    }
+
+    let (response, loaded) = disk::serve_from_disk(
+        full_path.clone(),
+        &state.streaming_thresholds,
+        &state.mime_types,
+        state.max_download_bytes_per_sec,
+        sha1.clone(),
+        if_modified_since,
+    )
+    .await?;
+
+    // Warm the RAM cache so the next request for this file skips the disk
+    if let Some((data, modified)) = loaded.filter(|_| state.lazy_file_cache) {
+        state.cache.cache_loaded_file(server_name, actual_path, full_path, data, sha1, modified);
+    }
 
-    disk::serve_from_disk(full_path, state.streaming_threshold_bytes).await
+    Ok(response)
 }
@@ -0,0 +1,49 @@
+use super::{resolver, validator::{is_category_enabled, validate_path_component}};
+use crate::handlers::models::AppState;
+use crate::errors::ApiError;
+use crate::models::FileMetaResponse;
+use axum::{
+    extract::{Path as AxumPath, State},
+    response::Json,
+};
+
+/// Returns the metadata of a single file (`{path, sha1, size, url, mime}`) without reading it
+/// `path` is the file's URL path below the server, as served by `serve_file`
+pub async fn get_file_meta(
+    State(state): State<AppState>,
+    AxumPath((server_name, path)): AxumPath<(String, String)>,
+) -> Result<Json<FileMetaResponse>, ApiError> {
+    validate_path_component(&server_name)?;
+    validate_path_component(&path)?;
+
+    let server_config = state
+        .cache
+        .get_server_config(&server_name)
+        .await
+        .filter(|config| config.enabled);
+    let (version, server_config) = match (state.cache.get_version(&server_name).await, server_config) {
+        (Some(version), Some(server_config)) => (version, server_config),
+        _ => {
+            return Err(state.server_not_found(server_name).await);
+        }
+    };
+
+    let entry = resolver::resolve_file_path(
+        &version,
+        &path,
+        &state.base_url,
+        &server_name,
+        state.case_insensitive_paths.as_deref(),
+    )
+    .filter(|actual_path| is_category_enabled(actual_path, &server_config))
+    .and_then(|actual_path| resolver::resolve_file_entry(&version, &actual_path))
+    .ok_or(ApiError::NotFound)?;
+
+    Ok(Json(FileMetaResponse {
+        mime: state.mime_types.for_path(std::path::Path::new(&entry.path)),
+        url: state.cache.published_url(&entry.url),
+        path: entry.path,
+        sha1: entry.sha1,
+        size: entry.size,
+    }))
+}
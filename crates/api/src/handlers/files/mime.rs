@@ -0,0 +1,33 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Content type of served files: `[mime] overrides` by extension, then `mime_guess`
+#[derive(Debug, Clone, Default)]
+pub struct MimeTypes {
+    /// Lowercased extension without the dot → MIME type
+    overrides: HashMap<String, String>,
+}
+
+impl MimeTypes {
+    pub fn new(overrides: &HashMap<String, String>) -> Self {
+        Self {
+            overrides: overrides
+                .iter()
+                .map(|(extension, mime)| (extension.trim().trim_start_matches('.').to_lowercase(), mime.trim().to_string()))
+                .collect(),
+        }
+    }
+
+    /// Configured type for the extension of `path`, if any
+    pub fn override_for(&self, path: &Path) -> Option<&str> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        self.overrides.get(&extension).map(String::as_str)
+    }
+
+    pub fn for_path(&self, path: &Path) -> String {
+        match self.override_for(path) {
+            Some(mime) => mime.to_string(),
+            None => mime_guess::from_path(path).first_or_octet_stream().to_string(),
+        }
+    }
+}
@@ -3,7 +3,18 @@ mod validator;
 mod parser;
 mod resolver;
 mod cache;
+mod conditional;
 mod disk;
+mod mime;
+mod remote;
+mod throttle;
 mod handler;
+mod meta;
+mod by_hash;
 
 pub use handler::serve_file;
+pub use meta::get_file_meta;
+pub use by_hash::serve_file_by_hash;
+pub use models::CaseInsensitiveIndex;
+pub use disk::StreamingThresholds;
+pub use mime::MimeTypes;
@@ -1,3 +1,20 @@
+use dashmap::DashMap;
+use lighty_models::VersionBuilder;
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+
+/// Header carrying the SHA1 of the served file (lets launchers verify without a metadata fetch)
+pub const CONTENT_SHA1_HEADER: &str = "x-content-sha1";
+
+/// Lowercased URL → URL map of one server version
+pub(super) type LowercaseUrls = Arc<HashMap<String, String>>;
+
+/// Lowercased url→url indexes per server, built once per cached version on first miss
+#[derive(Default)]
+pub struct CaseInsensitiveIndex {
+    pub(super) servers: DashMap<String, (Weak<VersionBuilder>, LowercaseUrls)>,
+}
+
 /// Parsed request information
 pub struct ParsedRequest {
     pub server_name: String,
@@ -0,0 +1,88 @@
+use super::models::CONTENT_SHA1_HEADER;
+use super::throttle;
+use crate::errors::ApiError;
+use axum::{
+    body::Body,
+    http::{header, HeaderName, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use lighty_storage::{RemoteStream, StorageBackend};
+use std::time::Duration;
+use tokio_util::io::ReaderStream;
+
+/// Attempts to open the remote object before giving up
+const MAX_ATTEMPTS: u32 = 3;
+const INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Streams a file missing on disk from the remote storage backend (e.g. S3 without `keep_local_backup`)
+/// `url` is the file's metadata URL, mapped back to its remote key
+/// Opening the object is retried with exponential backoff; once the body started, errors abort the response
+pub async fn serve_from_remote(
+    storage: &dyn StorageBackend,
+    url: &str,
+    mime_type: String,
+    max_bytes_per_sec: u64,
+    sha1: Option<String>,
+) -> Result<Response, ApiError> {
+    let origin = storage.get_url("");
+    let Some(remote_key) = url.strip_prefix(&origin) else {
+        tracing::warn!("serve_file: '{}' is not a storage URL, cannot proxy it", url);
+        return Err(ApiError::NotFound);
+    };
+
+    let remote = open_with_retry(storage, remote_key).await?.ok_or_else(|| {
+        tracing::warn!("serve_file: '{}' is missing both on disk and in remote storage", remote_key);
+        ApiError::NotFound
+    })?;
+
+    tracing::debug!("serve_file: proxying '{}' from remote storage", remote_key);
+
+    let stream = ReaderStream::new(remote.reader);
+    let body = if max_bytes_per_sec > 0 {
+        Body::from_stream(throttle::throttle(stream, max_bytes_per_sec))
+    } else {
+        Body::from_stream(stream)
+    };
+
+    let mut response = (StatusCode::OK, [(header::CONTENT_TYPE, mime_type)], body).into_response();
+    if let Some(size) = remote.size {
+        response.headers_mut().insert(header::CONTENT_LENGTH, HeaderValue::from(size));
+    }
+    if let Some(value) = sha1.and_then(|sha1| HeaderValue::from_str(&sha1).ok()) {
+        response
+            .headers_mut()
+            .insert(HeaderName::from_static(CONTENT_SHA1_HEADER), value);
+    }
+
+    Ok(response)
+}
+
+async fn open_with_retry(storage: &dyn StorageBackend, remote_key: &str) -> Result<Option<RemoteStream>, ApiError> {
+    let mut attempt = 0;
+    loop {
+        match storage.get_stream(remote_key).await {
+            Ok(remote) => return Ok(remote),
+            Err(e) if attempt + 1 < MAX_ATTEMPTS => {
+                let backoff = INITIAL_BACKOFF * 2u32.pow(attempt);
+                tracing::warn!(
+                    "serve_file: failed to open '{}' in remote storage (attempt {}): {}. Retrying in {:?}...",
+                    remote_key,
+                    attempt + 1,
+                    e,
+                    backoff
+                );
+                tokio::time::sleep(backoff).await;
+                attempt += 1;
+            }
+            Err(e) => {
+                tracing::error!(
+                    "serve_file: failed to open '{}' in remote storage after {} attempts: {}",
+                    remote_key,
+                    MAX_ATTEMPTS,
+                    e
+                );
+                return Err(ApiError::InternalError(format!("Remote storage unavailable for '{}'", remote_key)));
+            }
+        }
+    }
+}
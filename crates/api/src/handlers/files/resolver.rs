@@ -1,14 +1,62 @@
-use lighty_models::VersionBuilder;
+use super::models::{CaseInsensitiveIndex, LowercaseUrls};
+use lighty_models::{ManifestEntry, VersionBuilder};
+use std::sync::Arc;
 
 /// Resolves the actual file path from URL using O(1) HashMap lookup
+/// With a case-insensitive index, a miss is retried ignoring case (e.g. `Mods/Foo.jar`)
 pub fn resolve_file_path(
-    version: &VersionBuilder,
+    version: &Arc<VersionBuilder>,
     url_file_part: &str,
     base_url: &str,
     server_name: &str,
+    case_index: Option<&CaseInsensitiveIndex>,
 ) -> Option<String> {
     let requested_url = format!("{}/{}/{}", base_url, server_name, url_file_part);
 
     // O(1) lookup using pre-built HashMap
-    version.url_to_path_map.get(&requested_url).cloned()
+    if let Some(path) = version.url_to_path_map.get(&requested_url) {
+        return Some(path.clone());
+    }
+
+    let lowercase_urls = case_index?.for_version(server_name, version);
+    let url = lowercase_urls.get(&requested_url.to_lowercase())?;
+    version.url_to_path_map.get(url).cloned()
+}
+
+impl CaseInsensitiveIndex {
+    /// Returns the lowercased index of a version's URL map, rebuilding it when the version changed
+    fn for_version(&self, server_name: &str, version: &Arc<VersionBuilder>) -> LowercaseUrls {
+        if let Some(entry) = self.servers.get(server_name) {
+            let (indexed_version, index) = entry.value();
+            if std::ptr::eq(indexed_version.as_ptr(), Arc::as_ptr(version)) {
+                return Arc::clone(index);
+            }
+        }
+
+        let index: LowercaseUrls = Arc::new(
+            version
+                .url_to_path_map
+                .keys()
+                .map(|url| (url.to_lowercase(), url.clone()))
+                .collect(),
+        );
+        self.servers
+            .insert(server_name.to_string(), (Arc::downgrade(version), Arc::clone(&index)));
+        index
+    }
+}
+
+/// Looks up the SHA1 recorded in metadata for a resolved file path (e.g. "mods/foo.jar")
+pub fn resolve_file_sha1(version: &VersionBuilder, actual_path: &str) -> Option<String> {
+    version.file_meta(actual_path).map(|meta| meta.sha1.clone())
+}
+
+/// Looks up the metadata entry (url, sha1, size) of a resolved file path (e.g. "mods/foo.jar")
+pub fn resolve_file_entry(version: &VersionBuilder, actual_path: &str) -> Option<ManifestEntry> {
+    version.file_meta(actual_path).map(|meta| ManifestEntry {
+        path: actual_path.to_string(),
+        url: meta.url.clone(),
+        sha1: meta.sha1.clone(),
+        size: meta.size,
+    })
 }
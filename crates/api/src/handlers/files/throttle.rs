@@ -0,0 +1,23 @@
+use futures::{Stream, StreamExt};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Paces a chunked byte stream to at most `bytes_per_sec`
+/// Each chunk is held back until the average rate since the start falls under the cap
+pub fn throttle<S, B, E>(stream: S, bytes_per_sec: u64) -> impl Stream<Item = Result<B, E>>
+where
+    S: Stream<Item = Result<B, E>> + Unpin,
+    B: AsRef<[u8]>,
+{
+    let start = Instant::now();
+
+    futures::stream::unfold((stream, 0u64), move |(mut stream, sent)| async move {
+        let chunk = stream.next().await?;
+        let sent = sent + chunk.as_ref().map_or(0, |bytes| bytes.as_ref().len() as u64);
+
+        let due = start + Duration::from_secs_f64(sent as f64 / bytes_per_sec as f64);
+        tokio::time::sleep_until(due).await;
+
+        Some((chunk, (stream, sent)))
+    })
+}
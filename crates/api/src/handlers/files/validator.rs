@@ -1,7 +1,28 @@
 use crate::errors::ApiError;
+use lighty_config::{FileCategory, ServerConfig};
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+
+/// Percent-encoded '/', '\\' and null byte (compared lowercased)
+const ENCODED_SEPARATORS: &[&str] = &["%2f", "%5c", "%00"];
 
 /// Validates a path component to prevent path traversal attacks
+/// Percent-encoded forms (`%2e%2e`, `%2f`, double encoding...) are decoded and checked too
 pub fn validate_path_component(component: &str) -> Result<(), ApiError> {
+    if component.contains('%') {
+        let lowercase = component.to_ascii_lowercase();
+        if ENCODED_SEPARATORS.iter().any(|encoded| lowercase.contains(encoded)) {
+            return Err(ApiError::InvalidPath(
+                "Path contains an encoded separator or null byte".to_string()
+            ));
+        }
+
+        let decoded = percent_decode(component);
+        if decoded != component {
+            validate_path_component(&decoded)?;
+        }
+    }
+
     // Check for path traversal attempts
     if component.contains("..") {
         return Err(ApiError::InvalidPath(
@@ -32,3 +53,140 @@ pub fn validate_path_component(component: &str) -> Result<(), ApiError> {
 
     Ok(())
 }
+
+/// Validates a path relative to the server folder and rebuilds it from its components
+/// Any component that could leave the folder (`..`, root, drive prefix) is rejected, `.` is dropped
+pub fn normalize_relative_path(path: &str) -> Result<PathBuf, ApiError> {
+    validate_path_component(path)?;
+
+    let mut normalized = PathBuf::new();
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(part) => normalized.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                return Err(ApiError::InvalidPath(
+                    "Path resolves outside the server directory".to_string()
+                ));
+            }
+        }
+    }
+
+    if normalized.as_os_str().is_empty() {
+        return Err(ApiError::InvalidPath("Path is empty".to_string()));
+    }
+
+    Ok(normalized)
+}
+
+/// Decodes `%XX` escapes, leaving malformed ones as-is
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| input.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Checks a resolved file path against `server.servable_extensions` (lowercased, without dot)
+/// Files without extension match the "" entry
+pub fn is_servable_extension(actual_path: &str, extensions: &HashSet<String>) -> bool {
+    let extension = Path::new(actual_path)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    extensions.contains(&extension)
+}
+
+/// Checks that the category of a resolved file path (e.g. "assets/...") is enabled for the server
+/// The URL map can lag behind a config reload that turns an `enable_*` flag off
+pub fn is_category_enabled(actual_path: &str, server_config: &ServerConfig) -> bool {
+    FileCategory::from_path(actual_path).is_none_or(|category| server_config.category_enabled(category))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rejects(component: &str) -> bool {
+        matches!(validate_path_component(component), Err(ApiError::InvalidPath(_)))
+    }
+
+    #[test]
+    fn component_rejects_parent_dirs() {
+        assert!(rejects(".."));
+        assert!(rejects("mods/../../etc/passwd"));
+        assert!(rejects("%2e%2e"));
+        assert!(rejects("%252e%252e"));
+    }
+
+    #[test]
+    fn component_rejects_absolute_paths() {
+        assert!(rejects("/etc/passwd"));
+        assert!(rejects("\\windows\\system32"));
+        assert!(rejects("C:\\windows"));
+        assert!(rejects("%2fetc%2fpasswd"));
+    }
+
+    #[test]
+    fn component_rejects_encoded_separators() {
+        assert!(rejects("mods%2ffoo.jar"));
+        assert!(rejects("mods%5Cfoo.jar"));
+        assert!(rejects("mods%252ffoo.jar"));
+    }
+
+    #[test]
+    fn component_rejects_nul() {
+        assert!(rejects("foo\0.jar"));
+        assert!(rejects("foo%00.jar"));
+    }
+
+    #[test]
+    fn component_accepts_plain_paths() {
+        assert!(validate_path_component("survival").is_ok());
+        assert!(validate_path_component("mods/foo-1.0.jar").is_ok());
+        assert!(validate_path_component("mods/foo%20bar.jar").is_ok());
+    }
+
+    #[test]
+    fn relative_path_rejects_encoded_traversal() {
+        assert!(normalize_relative_path("..%2fetc/passwd").is_err());
+        assert!(normalize_relative_path("mods/..%2f..%2fconfig.toml").is_err());
+        assert!(normalize_relative_path("%2e%2e/config.toml").is_err());
+    }
+
+    #[test]
+    fn relative_path_rejects_backslashes() {
+        assert!(normalize_relative_path("mods\\..\\..\\config.toml").is_err());
+        assert!(normalize_relative_path("\\etc\\passwd").is_err());
+        assert!(normalize_relative_path("mods%5c..%5cconfig.toml").is_err());
+    }
+
+    #[test]
+    fn relative_path_rejects_absolute_paths() {
+        assert!(normalize_relative_path("/etc/passwd").is_err());
+        assert!(normalize_relative_path("C:/windows/win.ini").is_err());
+    }
+
+    #[test]
+    fn relative_path_drops_current_dirs() {
+        let normalized = normalize_relative_path("mods/./foo.jar").unwrap();
+        assert_eq!(normalized, PathBuf::from("mods/foo.jar"));
+        assert!(normalize_relative_path("./.").is_err());
+    }
+}
@@ -0,0 +1,64 @@
+use crate::models::ServerInfo;
+use axum::http::{header, HeaderMap};
+use std::fmt::Write;
+
+/// Checks whether the client ranks `text/html` above `application/json` in `Accept` (browsers)
+/// Ties, `*/*` and a missing header keep the JSON default
+pub(super) fn prefers_html(headers: &HeaderMap) -> bool {
+    let Some(accept) = headers.get(header::ACCEPT).and_then(|accept| accept.to_str().ok()) else {
+        return false;
+    };
+    quality(accept, "text/html") > quality(accept, "application/json")
+}
+
+/// `q` of a media type listed in an `Accept` value (1 when omitted, 0 when not listed)
+fn quality(accept: &str, media_type: &str) -> f32 {
+    accept
+        .split(',')
+        .filter_map(|range| {
+            let mut params = range.split(';');
+            let name = params.next().unwrap_or_default().trim();
+            name.eq_ignore_ascii_case(media_type).then(|| {
+                params
+                    .find_map(|param| param.trim().strip_prefix("q=").and_then(|q| q.trim().parse::<f32>().ok()))
+                    .unwrap_or(1.0)
+            })
+        })
+        .fold(0.0, f32::max)
+}
+
+/// Minimal HTML page listing the servers with links to their metadata
+pub(super) fn render_index(servers: &[ServerInfo]) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>LightyUpdater</title></head>\n<body>\n<h1>Servers</h1>\n",
+    );
+
+    if servers.is_empty() {
+        html.push_str("<p>No servers available.</p>\n");
+    } else {
+        html.push_str("<ul>\n");
+        for server in servers {
+            let _ = writeln!(
+                html,
+                "<li><a href=\"{}\">{}</a> ({} {}, updated {})</li>",
+                escape(&server.url),
+                escape(&server.name),
+                escape(&server.loader),
+                escape(&server.minecraft_version),
+                escape(&server.last_update),
+            );
+        }
+        html.push_str("</ul>\n");
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
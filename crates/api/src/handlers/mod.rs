@@ -1,8 +1,24 @@
 mod models;
 mod state;
 mod servers;
+mod version;
+mod admin;
+mod bundle;
+mod cache_control;
+mod encoding;
+mod favicon;
+mod index;
+mod not_found;
+mod ready;
+pub(crate) mod request_id;
 pub mod files;
 
 pub use models::AppState;
-pub use servers::{list_servers, get_server_metadata};
-pub use files::serve_file;
+pub use servers::{list_servers, get_server_metadata, get_server_launch, get_server_manifest, get_server_tree};
+pub use version::get_version;
+pub use ready::{get_ready, start_drain, stop_drain};
+pub use bundle::get_server_bundle;
+pub use favicon::favicon;
+pub use request_id::{request_id, X_REQUEST_ID};
+pub use admin::{freeze_server, get_maintenance, get_stats, get_tasks, set_maintenance, promote_server, purge_server, require_admin_token, reupload_server, verify_server};
+pub use files::{get_file_meta, serve_file, serve_file_by_hash};
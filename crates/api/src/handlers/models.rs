@@ -1,4 +1,6 @@
+use crate::models::VersionInfo;
 use lighty_cache::CacheManager;
+use std::collections::HashSet;
 use std::sync::Arc;
 
 /// Application state shared across all handlers
@@ -7,5 +9,32 @@ pub struct AppState {
     pub(super) cache: Arc<CacheManager>,
     pub(super) base_url: Arc<String>,
     pub(super) base_path: Arc<String>,
-    pub(super) streaming_threshold_bytes: u64,
+    pub(super) streaming_thresholds: Arc<super::files::StreamingThresholds>,
+    /// Content types by extension (`[mime] overrides`, then the built-in guess)
+    pub(super) mime_types: Arc<super::files::MimeTypes>,
+    /// Bandwidth cap per streamed download (0 = unlimited)
+    pub(super) max_download_bytes_per_sec: u64,
+    /// `Cache-Control` of file and metadata responses
+    pub(super) cache_control: Arc<super::cache_control::CacheControl>,
+    /// Largest page of assets served by `/{server}.json?assets_page=`
+    pub(super) max_assets_per_page: usize,
+    /// Lowercased URL indexes for case-insensitive file lookups (None = exact match only)
+    pub(super) case_insensitive_paths: Option<Arc<super::files::CaseInsensitiveIndex>>,
+    /// Extensions `serve_file` may serve (None = all)
+    pub(super) servable_extensions: Option<Arc<HashSet<String>>>,
+    /// Redirect `/{server}` to `/{server}.json`
+    pub(super) redirect_bare_server_name: bool,
+    /// Servers named in the 404 of an unknown server
+    pub(super) available_servers: lighty_config::AvailableServers,
+    /// Check RAM-cached files against the metadata SHA1 before serving them
+    pub(super) verify_on_serve: bool,
+    /// Insert files served by the disk fallback into the RAM cache (`cache.enabled`)
+    pub(super) lazy_file_cache: bool,
+    /// Remote backend files missing on disk are proxied from (None = local storage)
+    pub(super) remote_storage: Option<Arc<dyn lighty_storage::StorageBackend>>,
+    /// Files stay on disk after upload (`storage.keep_local_backup`), the disk copy is never missing on purpose
+    pub(super) keep_local_backup: bool,
+    pub(super) version_info: Arc<VersionInfo>,
+    /// Set by POST /admin/drain (start time, RFC 3339): `/ready` fails so load balancers drain this instance
+    pub(super) draining_since: Arc<std::sync::RwLock<Option<String>>>,
 }
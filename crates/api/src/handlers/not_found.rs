@@ -0,0 +1,39 @@
+use super::models::AppState;
+use crate::errors::ApiError;
+use lighty_config::AvailableServers;
+
+/// Most names suggested for an unknown server
+const MAX_SUGGESTIONS: usize = 5;
+
+impl AppState {
+    /// 404 of an unknown or disabled server, naming servers as set by `server.available_servers`
+    /// `unlisted` servers are never named, and "all" only applies while `GET /` lists the servers
+    pub(super) async fn server_not_found(&self, server: String) -> ApiError {
+        let mut mode = self.available_servers;
+        if mode == AvailableServers::All && !self.cache.config.read().await.server.expose_server_list {
+            mode = AvailableServers::Similar;
+        }
+
+        let available = match mode {
+            AvailableServers::All => self.cache.get_listed_servers().await,
+            AvailableServers::Similar => similar_names(&server, self.cache.get_listed_servers().await),
+            AvailableServers::None => Vec::new(),
+        };
+        ApiError::ServerNotFound { server, available }
+    }
+}
+
+/// Names within a few edits of `requested` (ignoring case), closest first
+/// The allowed distance grows with the name: one typo per three characters, at least two
+fn similar_names(requested: &str, names: Vec<String>) -> Vec<String> {
+    let requested = requested.to_lowercase();
+    let max_distance = (requested.chars().count() / 3).max(2);
+
+    let mut similar: Vec<(usize, String)> = names
+        .into_iter()
+        .map(|name| (strsim::levenshtein(&requested, &name.to_lowercase()), name))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    similar.sort();
+    similar.into_iter().take(MAX_SUGGESTIONS).map(|(_, name)| name).collect()
+}
@@ -0,0 +1,33 @@
+use super::models::AppState;
+use crate::models::DrainStatus;
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+
+/// Readiness probe for load balancers: 200 while serving, 503 once drained by POST /admin/drain
+/// Draining only fails this probe, requests keep being served
+pub async fn get_ready(State(state): State<AppState>) -> Response {
+    let status = state.drain_status();
+    let code = if status.draining {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+    (code, Json(status)).into_response()
+}
+
+/// Fails `/ready` so the load balancer stops routing new clients here (before a shutdown)
+pub async fn start_drain(State(state): State<AppState>) -> Json<DrainStatus> {
+    let status = state.set_draining(true);
+    tracing::info!("Draining: /ready now answers 503");
+    Json(status)
+}
+
+/// Cancels a drain, `/ready` succeeds again
+pub async fn stop_drain(State(state): State<AppState>) -> Json<DrainStatus> {
+    let status = state.set_draining(false);
+    tracing::info!("Drain cancelled: /ready answers 200 again");
+    Json(status)
+}
@@ -0,0 +1,47 @@
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use tracing::Instrument;
+
+pub static X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest client-provided ID kept as is, longer ones are replaced
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// ID of the request being handled, for error bodies (None outside the `request_id` middleware)
+pub(crate) fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+/// Tags each request with an ID: the client's `X-Request-Id` if usable, else a new UUID
+/// The ID is recorded on the request's tracing span and echoed in the response header
+pub async fn request_id(mut request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(&X_REQUEST_ID)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.is_empty() && value.len() <= MAX_REQUEST_ID_LEN)
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    let header_value = HeaderValue::from_str(&id).expect("request ID is a valid header value");
+    request.headers_mut().insert(X_REQUEST_ID.clone(), header_value.clone());
+
+    let span = tracing::info_span!(
+        "request",
+        request_id = %id,
+        method = %request.method(),
+        path = %request.uri().path(),
+    );
+
+    let mut response = REQUEST_ID.scope(id, next.run(request).instrument(span)).await;
+    response.headers_mut().insert(X_REQUEST_ID.clone(), header_value);
+    response
+}
@@ -1,18 +1,33 @@
+use super::cache_control;
 use super::models::AppState;
 use crate::errors::ApiError;
-use crate::models::{ServerListResponse, ServerInfo};
-use lighty_models::VersionBuilder;
+use crate::models::{LaunchInfo, MetadataQuery, ServerListResponse, ServerInfo};
+use lighty_models::{FileCategory, VersionBuilder};
 use axum::{
-    extract::{Path as AxumPath, State},
-    response::Json,
+    extract::{Path as AxumPath, Query, RawQuery, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{Html, IntoResponse, Json, Redirect, Response},
 };
 
-pub async fn list_servers(State(state): State<AppState>) -> Result<Json<ServerListResponse>, ApiError> {
+const MSGPACK_CONTENT_TYPE: &str = "application/msgpack";
+
+/// Lists the servers, leaving out `unlisted` ones (still served by name)
+/// JSON by default, a minimal HTML page for clients preferring `text/html` (browsers)
+/// 404 with `server.expose_server_list = false` (read on each request, follows config reloads)
+pub async fn list_servers(State(state): State<AppState>, headers: HeaderMap) -> Result<Response, ApiError> {
+    if !state.cache.config.read().await.server.expose_server_list {
+        return Err(ApiError::NotFound);
+    }
+
     let server_names = state.cache.get_all_servers().await;
     let mut servers = Vec::new();
 
     for name in server_names {
         if let Some(config) = state.cache.get_server_config(&name).await {
+            if config.unlisted {
+                continue;
+            }
+
             let last_update = state.cache.get_last_update(&name)
                 .unwrap_or_else(|| chrono::Utc::now().to_rfc3339());
 
@@ -26,37 +41,358 @@ pub async fn list_servers(State(state): State<AppState>) -> Result<Json<ServerLi
         }
     }
 
-    Ok(Json(ServerListResponse { servers }))
+    let vary = [(header::VARY, "accept")];
+    if super::index::prefers_html(&headers) {
+        return Ok((vary, Html(super::index::render_index(&servers))).into_response());
+    }
+
+    Ok((vary, Json(ServerListResponse { servers })).into_response())
 }
 
+/// 308 from a bare server name (`/{server}`, a common mistake) to its `.json` metadata
+/// None for names that aren't an enabled server, left to the regular lookup
+pub(super) async fn metadata_redirect(state: &AppState, name: &str, query: Option<&str>) -> Option<Response> {
+    if !state.cache.get_all_servers().await.iter().any(|server| server == name) {
+        return None;
+    }
+
+    let location = match query {
+        Some(query) => format!("{}/{}.json?{}", state.base_url, name, query),
+        None => format!("{}/{}.json", state.base_url, name),
+    };
+    tracing::debug!("Redirecting bare server name '{}' to {}", name, location);
+    Some(Redirect::permanent(&location).into_response())
+}
+
+/// Returns the full metadata of a server
+/// JSON by default, MessagePack when the client sends `Accept: application/msgpack`
+/// `?assets_page=N&assets_per_page=M` returns a single page of assets (JSON only)
+/// `?include=client,libraries,mods` leaves out the other categories at request time (`enable_*` still decides what is scanned)
+/// The route also matches `/{server}`, redirected to `/{server}.json` with `server.redirect_bare_server_name`
 pub async fn get_server_metadata(
     State(state): State<AppState>,
     AxumPath(server_name_with_ext): AxumPath<String>,
-) -> Result<Json<VersionBuilder>, ApiError> {
-    let server_name = server_name_with_ext
-        .strip_suffix(".json")
-        .unwrap_or(&server_name_with_ext)
-        .to_string();
+    Query(query): Query<MetadataQuery>,
+    RawQuery(raw_query): RawQuery,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let mut response = metadata_response(&state, server_name_with_ext, &query, raw_query, &headers).await?;
+    cache_control::apply(&mut response, state.cache_control.metadata.as_ref());
+    Ok(response)
+}
+
+async fn metadata_response(
+    state: &AppState,
+    server_name_with_ext: String,
+    query: &MetadataQuery,
+    raw_query: Option<String>,
+    headers: &HeaderMap,
+) -> Result<Response, ApiError> {
+    let server_name = match server_name_with_ext.strip_suffix(".json") {
+        Some(server_name) => server_name.to_string(),
+        None => {
+            if state.redirect_bare_server_name {
+                if let Some(response) = metadata_redirect(state, &server_name_with_ext, raw_query.as_deref()).await {
+                    return Ok(response);
+                }
+            }
+            server_name_with_ext
+        }
+    };
 
     // Check if server is enabled
     if let Some(server_config) = state.cache.get_server_config(&server_name).await {
         if !server_config.enabled {
-            let available = state.cache.get_all_servers().await;
-            return Err(ApiError::ServerNotFound {
-                server: server_name,
-                available,
-            });
+            return Err(state.server_not_found(server_name).await);
         }
     }
 
+    if let Some(include) = &query.include {
+        if query.assets_page.is_some() || query.assets_per_page.is_some() {
+            return Err(ApiError::InvalidQuery("include can't be combined with assets paging".to_string()));
+        }
+        return serve_metadata_subset(state, server_name, include, accepts_msgpack(headers)).await;
+    }
+
+    if accepts_msgpack(headers) {
+        return serve_metadata_msgpack(state, server_name).await;
+    }
+
+    if query.assets_page.is_some() || query.assets_per_page.is_some() {
+        return serve_metadata_page(state, server_name, query).await;
+    }
+
+    // Pre-compressed variants (brotli first), left untouched by the compression layer
+    let precompressed = super::encoding::accepts_brotli(headers)
+        .then(|| state.cache.get_metadata_brotli(&server_name).map(|json| ("br", json)))
+        .flatten()
+        .or_else(|| {
+            super::encoding::accepts_gzip(headers)
+                .then(|| state.cache.get_metadata_gzip(&server_name).map(|json| ("gzip", json)))
+                .flatten()
+        });
+    if let Some((encoding, json)) = precompressed {
+        return Ok((
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, "application/json"),
+                (header::CONTENT_ENCODING, encoding),
+                (header::VARY, "accept-encoding"),
+            ],
+            json.as_ref().clone(),
+        )
+            .into_response());
+    }
+
+    // Serve the JSON pre-serialized on cache update (no per-request serialization)
+    if let Some(json) = state.cache.get_metadata_json(&server_name) {
+        return Ok((
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "application/json")],
+            json.as_ref().clone(),
+        )
+            .into_response());
+    }
+
     match state.cache.get(&server_name).await {
-        Some(builder) => Ok(Json((*builder).clone())),
-        None => {
-            let available = state.cache.get_all_servers().await;
-            Err(ApiError::ServerNotFound {
-                server: server_name,
-                available,
+        Some(builder) => Ok(Json(builder.as_ref()).into_response()),
+        None => Err(state.server_not_found(server_name).await),
+    }
+}
+
+/// Serves the metadata with one page of assets, plus an `assets_page` object (total, next page)
+async fn serve_metadata_page(state: &AppState, server_name: String, query: &MetadataQuery) -> Result<Response, ApiError> {
+    let page = query.assets_page.unwrap_or(1);
+    let per_page = query
+        .assets_per_page
+        .unwrap_or(state.max_assets_per_page)
+        .min(state.max_assets_per_page);
+    if page == 0 || per_page == 0 {
+        return Err(ApiError::InvalidQuery("assets_page and assets_per_page start at 1".to_string()));
+    }
+
+    let Some(json) = state.cache.get_metadata_page(&server_name, page, per_page).await else {
+        return Err(state.server_not_found(server_name).await);
+    };
+
+    Ok((
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "application/json")],
+        json,
+    )
+        .into_response())
+}
+
+/// Serves the metadata with only the `include`d categories, serialized per request (debugging aid)
+async fn serve_metadata_subset(
+    state: &AppState,
+    server_name: String,
+    include: &str,
+    msgpack: bool,
+) -> Result<Response, ApiError> {
+    let categories = parse_categories(include)?;
+
+    let Some(version) = state.cache.get_published_version(&server_name).await else {
+        return Err(state.server_not_found(server_name).await);
+    };
+    let subset = version.with_categories(&categories);
+
+    if !msgpack {
+        return Ok(Json(subset).into_response());
+    }
+
+    let body = to_msgpack(&subset).map_err(|e| {
+        tracing::error!("Failed to serialize {} metadata as MessagePack: {}", server_name, e);
+        ApiError::InternalError(e.to_string())
+    })?;
+    Ok((StatusCode::OK, [(header::CONTENT_TYPE, MSGPACK_CONTENT_TYPE)], body).into_response())
+}
+
+/// Parses a comma-separated list of category names (`client`, `libraries`, `mods`, `natives`, `assets`, `extra`)
+fn parse_categories(list: &str) -> Result<Vec<FileCategory>, ApiError> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            FileCategory::ALL
+                .into_iter()
+                .find(|category| category.dir().eq_ignore_ascii_case(name))
+                .ok_or_else(|| ApiError::InvalidQuery(format!("Unknown category '{}' in include", name)))
+        })
+        .collect()
+}
+
+/// Checks whether the client asked for MessagePack metadata
+fn accepts_msgpack(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| {
+            accept.split(',').any(|media_type| {
+                let media_type = media_type.split(';').next().unwrap_or_default().trim();
+                media_type.eq_ignore_ascii_case(MSGPACK_CONTENT_TYPE)
+                    || media_type.eq_ignore_ascii_case("application/x-msgpack")
             })
+        })
+}
+
+/// MessagePack encoding of a version, with fields as named maps so skipped optional fields stay unambiguous
+fn to_msgpack(version: &VersionBuilder) -> Result<Vec<u8>, rmp_serde::encode::Error> {
+    rmp_serde::to_vec_named(version)
+}
+
+/// Serializes the metadata as MessagePack on request (smaller payload, not pre-serialized)
+/// Fields are encoded as named maps so skipped optional fields stay unambiguous
+async fn serve_metadata_msgpack(state: &AppState, server_name: String) -> Result<Response, ApiError> {
+    let Some(version) = state.cache.get_published_version(&server_name).await else {
+        return Err(state.server_not_found(server_name).await);
+    };
+
+    let body = to_msgpack(version.as_ref()).map_err(|e| {
+        tracing::error!("Failed to serialize {} metadata as MessagePack: {}", server_name, e);
+        ApiError::InternalError(e.to_string())
+    })?;
+
+    Ok((
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, MSGPACK_CONTENT_TYPE)],
+        body,
+    )
+        .into_response())
+}
+
+/// Returns a flat, path-sorted list of all files of a server (`{path, url, sha1, size}`)
+pub async fn get_server_manifest(
+    State(state): State<AppState>,
+    AxumPath(server_name): AxumPath<String>,
+) -> Result<Response, ApiError> {
+    // Check if server is enabled
+    if let Some(server_config) = state.cache.get_server_config(&server_name).await {
+        if !server_config.enabled {
+            return Err(state.server_not_found(server_name).await);
+        }
+    }
+
+    let mut response = if let Some(json) = state.cache.get_manifest_json(&server_name) {
+        (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "application/json")],
+            json.as_ref().clone(),
+        )
+            .into_response()
+    } else {
+        match state.cache.get(&server_name).await {
+            Some(builder) => Json(builder.manifest()).into_response(),
+            None => return Err(state.server_not_found(server_name).await),
+        }
+    };
+    cache_control::apply(&mut response, state.cache_control.metadata.as_ref());
+    Ok(response)
+}
+
+/// Returns the files of a server as a folder tree, with `{url, sha1, size}` on the files
+pub async fn get_server_tree(
+    State(state): State<AppState>,
+    AxumPath(server_name): AxumPath<String>,
+) -> Result<Response, ApiError> {
+    // Check if server is enabled
+    if let Some(server_config) = state.cache.get_server_config(&server_name).await {
+        if !server_config.enabled {
+            return Err(state.server_not_found(server_name).await);
+        }
+    }
+
+    let mut response = if let Some(json) = state.cache.get_tree_json(&server_name) {
+        (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "application/json")],
+            json.as_ref().clone(),
+        )
+            .into_response()
+    } else {
+        match state.cache.get(&server_name).await {
+            Some(builder) => Json(builder.file_tree()).into_response(),
+            None => return Err(state.server_not_found(server_name).await),
         }
+    };
+    cache_control::apply(&mut response, state.cache_control.metadata.as_ref());
+    Ok(response)
+}
+
+/// Returns only the launch parameters of a server (`main_class`, `java_version`, `arguments`)
+/// For launchers resolving files lazily (by hash or `/meta/`), read from the cached version
+pub async fn get_server_launch(
+    State(state): State<AppState>,
+    AxumPath(server_name): AxumPath<String>,
+) -> Result<Response, ApiError> {
+    // Check if server is enabled
+    if let Some(server_config) = state.cache.get_server_config(&server_name).await {
+        if !server_config.enabled {
+            return Err(state.server_not_found(server_name).await);
+        }
+    }
+
+    let Some(version) = state.cache.get_version(&server_name).await else {
+        return Err(state.server_not_found(server_name).await);
+    };
+
+    let mut response = Json(LaunchInfo {
+        main_class: version.main_class.clone(),
+        java_version: version.java_version.clone(),
+        arguments: version.arguments.clone(),
+    })
+    .into_response();
+    cache_control::apply(&mut response, state.cache_control.metadata.as_ref());
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Version with every optional field both set and skipped
+    fn sample_version() -> VersionBuilder {
+        serde_json::from_value(serde_json::json!({
+            "main_class": { "main_class": "net.minecraft.client.main.Main" },
+            "java_version": { "major_version": 21 },
+            "arguments": { "game": ["--demo"], "jvm": ["-Xmx4G"] },
+            "libraries": [
+                { "name": "com.example:lib:1.0", "url": "http://localhost/s/lib.jar", "path": "com/example/lib.jar", "sha1": "aa", "size": 10 },
+                { "name": "org.example:bare:2.0" }
+            ],
+            "mods": [
+                { "name": "a.jar", "url": "http://localhost/s/a.jar", "path": "a.jar", "sha1": "bb", "size": 3, "crc32": "0a0b0c0d", "mtime": 1700000000 }
+            ],
+            "natives": null,
+            "client": { "name": "client", "url": "http://localhost/s/client.jar", "path": "client.jar", "sha1": "cc", "size": 5 },
+            "scan_warnings": ["mods/broken.jar: unreadable"],
+            "assets": [
+                { "hash": "dd", "size": 7, "url": "http://localhost/s/a/f.png", "path": "a/f.png" },
+                { "hash": "ee", "size": 0 }
+            ]
+        }))
+        .expect("valid sample version")
+    }
+
+    #[test]
+    fn msgpack_metadata_round_trips() {
+        let version = sample_version();
+
+        let bytes = to_msgpack(&version).expect("encodes");
+        let decoded: VersionBuilder = rmp_serde::from_slice(&bytes).expect("decodes");
+
+        assert_eq!(
+            serde_json::to_value(&decoded).unwrap(),
+            serde_json::to_value(&version).unwrap()
+        );
+    }
+
+    #[test]
+    fn msgpack_metadata_uses_named_fields() {
+        let bytes = to_msgpack(&sample_version()).expect("encodes");
+        let value: serde_json::Value = rmp_serde::from_slice(&bytes).expect("decodes as a map");
+
+        assert_eq!(value["java_version"]["major_version"], 21);
+        assert!(value["libraries"][1].get("url").is_none());
     }
 }
@@ -1,16 +1,73 @@
 use super::models::AppState;
+use super::cache_control::CacheControl;
+use super::files::{CaseInsensitiveIndex, MimeTypes, StreamingThresholds};
+use crate::models::{DrainStatus, VersionInfo};
 use lighty_cache::CacheManager;
+use lighty_config::Config;
 use axum::extract::FromRef;
 use std::sync::Arc;
 
 impl AppState {
-    pub fn new(cache: Arc<CacheManager>, base_url: String, base_path: String, streaming_threshold_mb: u64) -> Self {
+    /// Builds the handler state from the settings read at startup
+    pub fn new(cache: Arc<CacheManager>, config: &Config, version_info: VersionInfo) -> Self {
+        let server = &config.server;
+
         Self {
+            remote_storage: cache.storage().filter(|storage| storage.is_remote()),
+            keep_local_backup: config.storage.keep_local_backup,
             cache,
-            base_url: Arc::new(base_url),
-            base_path: Arc::new(base_path),
-            streaming_threshold_bytes: streaming_threshold_mb * 1024 * 1024,
+            base_url: Arc::new(server.base_url.to_string()),
+            base_path: Arc::new(server.base_path.to_string()),
+            streaming_thresholds: Arc::new(StreamingThresholds::new(
+                server.streaming_threshold_mb,
+                &server.streaming_thresholds_mb,
+            )),
+            mime_types: Arc::new(MimeTypes::new(&config.mime.overrides)),
+            max_download_bytes_per_sec: server.max_download_kbps * 1024,
+            cache_control: Arc::new(CacheControl::new(server)),
+            max_assets_per_page: server.max_assets_per_page.max(1),
+            case_insensitive_paths: server
+                .case_insensitive_paths
+                .then(|| Arc::new(CaseInsensitiveIndex::default())),
+            servable_extensions: (!server.servable_extensions.is_empty()).then(|| {
+                Arc::new(
+                    server
+                        .servable_extensions
+                        .iter()
+                        .map(|extension| extension.trim_start_matches('.').to_lowercase())
+                        .collect(),
+                )
+            }),
+            redirect_bare_server_name: server.redirect_bare_server_name,
+            available_servers: server.available_servers,
+            verify_on_serve: config.cache.verify_on_serve,
+            lazy_file_cache: config.cache.enabled,
+            version_info: Arc::new(version_info),
+            draining_since: Arc::default(),
+        }
+    }
+}
+
+impl AppState {
+    pub(super) fn drain_status(&self) -> DrainStatus {
+        let since = self.draining_since.read().unwrap_or_else(|e| e.into_inner()).clone();
+        DrainStatus {
+            draining: since.is_some(),
+            since,
+        }
+    }
+
+    /// Starts or stops draining, keeping the start time of a drain already in progress
+    pub(super) fn set_draining(&self, draining: bool) -> DrainStatus {
+        {
+            let mut since = self.draining_since.write().unwrap_or_else(|e| e.into_inner());
+            match (draining, since.is_some()) {
+                (true, false) => *since = Some(chrono::Utc::now().to_rfc3339()),
+                (false, true) => *since = None,
+                _ => {}
+            }
         }
+        self.drain_status()
     }
 }
 
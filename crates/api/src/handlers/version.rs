@@ -0,0 +1,8 @@
+use super::models::AppState;
+use crate::models::VersionInfo;
+use axum::{extract::State, response::Json};
+
+/// Reports the deployed build (crate version, git commit, build timestamp)
+pub async fn get_version(State(state): State<AppState>) -> Json<VersionInfo> {
+    Json(state.version_info.as_ref().clone())
+}
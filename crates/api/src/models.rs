@@ -14,6 +14,108 @@ pub struct ServerInfo {
     pub last_update: String,  // ISO 8601 timestamp (RFC 3339)
 }
 
+/// Launch parameters of a server without its files, returned by GET /{server}/launch.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchInfo {
+    pub main_class: lighty_models::MainClass,
+    pub java_version: lighty_models::JavaVersion,
+    pub arguments: lighty_models::Arguments,
+}
+
+/// Metadata of a single file, returned by GET /{server}/meta/{path}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileMetaResponse {
+    pub path: String,  // Category-prefixed path (e.g. "mods/mod.jar")
+    pub sha1: String,
+    pub size: u64,
+    pub url: String,
+    pub mime: String,
+}
+
+/// Optional asset paging and category filter of GET /{server}.json (no parameters = everything)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MetadataQuery {
+    /// Page number, starting at 1
+    pub assets_page: Option<usize>,
+    pub assets_per_page: Option<usize>,
+    /// Comma-separated categories to keep (e.g. "client,libraries,mods")
+    pub include: Option<String>,
+}
+
+/// Build information reported by GET /version
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionInfo {
+    pub version: String,
+    pub git_commit: String,
+    pub build_timestamp: String,  // ISO 8601 timestamp (RFC 3339)
+}
+
+/// Runtime statistics reported by GET /admin/stats
+#[derive(Debug, Clone, Serialize)]
+pub struct StatsResponse {
+    pub file_cache: FileCacheStats,
+    pub scans: Vec<lighty_cache::ScanDurationStats>,
+}
+
+/// Body of POST /admin/maintenance
+#[derive(Debug, Clone, Deserialize)]
+pub struct MaintenanceRequest {
+    pub enabled: bool,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Drain state, reported by GET /ready and /admin/drain
+#[derive(Debug, Clone, Serialize)]
+pub struct DrainStatus {
+    /// `/ready` answers 503 while draining, files are still served
+    pub draining: bool,
+    /// ISO 8601 timestamp (RFC 3339)
+    pub since: Option<String>,
+}
+
+/// Optional re-hash sample of POST /admin/verify/{server}
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct VerifyQuery {
+    /// Files re-hashed against their metadata SHA1 (0 = existence and size only)
+    #[serde(default)]
+    pub sample: usize,
+}
+
+/// Drift between the cached metadata and the disk, reported by POST /admin/verify/{server}
+#[derive(Debug, Clone, Serialize)]
+pub struct VerifyReport {
+    pub server: String,
+    /// Files listed by the cached metadata
+    pub checked: usize,
+    /// Files re-hashed (spread evenly over the manifest)
+    pub hashed: usize,
+    /// Category-prefixed paths with no file on disk (or in remote storage without `keep_local_backup`)
+    pub missing: Vec<String>,
+    pub corrupt: Vec<CorruptFile>,
+}
+
+/// File whose size or SHA1 differs from the metadata
+#[derive(Debug, Clone, Serialize)]
+pub struct CorruptFile {
+    pub path: String,  // Category-prefixed path (e.g. "mods/mod.jar")
+    pub reason: String,
+}
+
+/// Background tasks listed by GET /admin/tasks
+#[derive(Debug, Clone, Serialize)]
+pub struct TasksResponse {
+    /// Tasks still running
+    pub running: usize,
+    pub tasks: Vec<lighty_cache::BackgroundTask>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileCacheStats {
+    pub entries: u64,
+    pub size_kb: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ErrorResponse {
     pub error: ErrorDetail,
@@ -25,4 +127,7 @@ pub struct ErrorDetail {
     pub message: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub available_servers: Option<Vec<String>>,
+    /// `X-Request-Id` of the failed request, to find it in the server logs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
@@ -0,0 +1,91 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use lighty_models::Asset;
+
+/// Paging details appended to a paged metadata document
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AssetsPage {
+    pub page: usize,
+    pub per_page: usize,
+    pub total: usize,
+    /// None on the last page
+    pub next_page: Option<usize>,
+}
+
+/// Byte ranges of the assets inside the serialized metadata
+/// Lets a page of assets be served without re-serializing libraries and mods
+#[derive(Debug)]
+pub struct AssetIndex {
+    /// Offset of the assets array (`[`)
+    array_start: usize,
+    /// Offset of the closing `]`
+    array_end: usize,
+    /// Offset of each asset
+    starts: Vec<usize>,
+}
+
+impl AssetIndex {
+    /// Locates the assets in `metadata`, which must end with the assets array (last field)
+    /// Returns None if the layout is not the expected one
+    pub fn build(metadata: &[u8], assets: &[Asset]) -> Option<Self> {
+        let mut lengths = Vec::with_capacity(assets.len());
+        for asset in assets {
+            lengths.push(serde_json::to_vec(asset).ok()?.len());
+        }
+
+        // `...,"assets":[a1,a2,...]}`
+        let array_end = metadata.len().checked_sub(2)?;
+        if metadata.get(array_end..) != Some(b"]}".as_slice()) {
+            return None;
+        }
+        let items_len = lengths.iter().sum::<usize>() + lengths.len().saturating_sub(1);
+        let array_start = array_end.checked_sub(items_len + 1)?;
+        if metadata.get(array_start) != Some(&b'[') || !metadata[..array_start].ends_with(b"\"assets\":") {
+            return None;
+        }
+
+        let mut starts = Vec::with_capacity(lengths.len());
+        let mut offset = array_start + 1;
+        for len in lengths {
+            starts.push(offset);
+            offset += len + 1;
+        }
+
+        Some(Self { array_start, array_end, starts })
+    }
+
+    pub fn total(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// Builds the metadata document with only one page of assets (`page` starts at 1)
+    pub fn page(&self, metadata: &Bytes, page: usize, per_page: usize) -> Bytes {
+        let total = self.total();
+        let first = page.saturating_sub(1).saturating_mul(per_page).min(total);
+        let last = first.saturating_add(per_page).min(total);
+
+        let items = if first == last {
+            Bytes::new()
+        } else {
+            // Contiguous range, commas between items included
+            let end = if last < total { self.starts[last] - 1 } else { self.array_end };
+            metadata.slice(self.starts[first]..end)
+        };
+
+        let page_info = AssetsPage {
+            page,
+            per_page,
+            total,
+            next_page: (last < total).then_some(page + 1),
+        };
+        let page_info = serde_json::to_vec(&page_info).unwrap_or_default();
+
+        let mut body = BytesMut::with_capacity(self.array_start + items.len() + page_info.len() + 20);
+        body.put_slice(&metadata[..self.array_start]);
+        body.put_u8(b'[');
+        body.put_slice(&items);
+        body.put_slice(b"],\"assets_page\":");
+        body.put_slice(&page_info);
+        body.put_u8(b'}');
+        body.freeze()
+    }
+}
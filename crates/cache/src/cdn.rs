@@ -1,4 +1,5 @@
 use super::errors::CacheError;
+use lighty_config::{CdnSettings, PurgeZone};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
@@ -6,8 +7,7 @@ type Result<T> = std::result::Result<T, CacheError>;
 
 pub struct CdnClient {
     provider: CdnProvider,
-    zone_id: String,
-    api_token: String,
+    zones: Vec<PurgeZone>,
     client: reqwest::Client,
 }
 
@@ -28,7 +28,7 @@ struct PurgeResponse {
 }
 
 impl CdnClient {
-    pub fn new(provider: &str, zone_id: String, api_token: String) -> Self {
+    pub fn new(provider: &str, zones: Vec<PurgeZone>) -> Self {
         let provider = match provider.to_lowercase().as_str() {
             "cloudfront" => CdnProvider::CloudFront,
             _ => CdnProvider::Cloudflare, // Default to Cloudflare
@@ -36,19 +36,47 @@ impl CdnClient {
 
         Self {
             provider,
-            zone_id,
-            api_token,
+            zones,
             client: reqwest::Client::new(),
         }
     }
 
-    pub async fn purge_files(&self, file_urls: Vec<String>) -> Result<()> {
+    /// Client for `zone_id` (every server, if set) and each of `[[cdn.zones]]`
+    pub fn from_settings(settings: &CdnSettings) -> Self {
+        let mut zones = Vec::with_capacity(settings.zones.len() + 1);
+        if !settings.zone_id.is_empty() {
+            zones.push(PurgeZone {
+                zone_id: settings.zone_id.clone(),
+                api_token: settings.api_token.clone(),
+                servers: Vec::new(),
+            });
+        }
+        zones.extend(settings.zones.iter().cloned());
+        Self::new(&settings.provider, zones)
+    }
+
+    pub fn zone_count(&self) -> usize {
+        self.zones.len()
+    }
+
+    /// Purges the files of a server in every zone covering it
+    /// All zones are attempted, the first failure is returned
+    pub async fn purge_files(&self, server_name: &str, file_urls: Vec<String>) -> Result<()> {
         if file_urls.is_empty() {
             return Ok(());
         }
 
         match self.provider {
-            CdnProvider::Cloudflare => self.purge_cloudflare(file_urls).await,
+            CdnProvider::Cloudflare => {
+                let mut result = Ok(());
+                for zone in self.zones.iter().filter(|zone| zone.covers(server_name)) {
+                    let purged = self.purge_cloudflare(zone, &file_urls).await;
+                    if result.is_ok() {
+                        result = purged;
+                    }
+                }
+                result
+            }
             CdnProvider::CloudFront => {
                 tracing::warn!("CloudFront CDN purge not implemented yet");
                 Ok(())
@@ -56,36 +84,37 @@ impl CdnClient {
         }
     }
 
-    async fn purge_cloudflare(&self, file_urls: Vec<String>) -> Result<()> {
+    async fn purge_cloudflare(&self, zone: &PurgeZone, file_urls: &[String]) -> Result<()> {
         const MAX_RETRIES: usize = 3;
         const INITIAL_BACKOFF: Duration = Duration::from_millis(100);
 
         let url = format!(
             "https://api.cloudflare.com/client/v4/zones/{}/purge_cache",
-            self.zone_id
+            zone.zone_id
         );
 
-        let body = PurgeRequest { files: file_urls.clone() };
+        let body = PurgeRequest { files: file_urls.to_vec() };
 
         // Retry with exponential backoff
         for attempt in 0..MAX_RETRIES {
-            match self.purge_cloudflare_internal(&url, &body).await {
+            match self.purge_cloudflare_internal(&url, &zone.api_token, &body).await {
                 Ok(()) => {
-                    tracing::info!("Cloudflare CDN cache purged for {} files", file_urls.len());
+                    tracing::info!("Cloudflare CDN cache purged for {} files (zone {})", file_urls.len(), zone.zone_id);
                     return Ok(());
                 }
                 Err(e) if attempt < MAX_RETRIES - 1 => {
                     let backoff = INITIAL_BACKOFF * 2u32.pow(attempt as u32);
                     tracing::warn!(
-                        "Cloudflare CDN purge attempt {} failed: {}. Retrying in {:?}...",
+                        "Cloudflare CDN purge attempt {} failed (zone {}): {}. Retrying in {:?}...",
                         attempt + 1,
+                        zone.zone_id,
                         e,
                         backoff
                     );
                     tokio::time::sleep(backoff).await;
                 }
                 Err(e) => {
-                    tracing::error!("Cloudflare CDN purge failed after {} attempts: {}", MAX_RETRIES, e);
+                    tracing::error!("Cloudflare CDN purge failed (zone {}) after {} attempts: {}", zone.zone_id, MAX_RETRIES, e);
                     return Err(e);
                 }
             }
@@ -94,14 +123,14 @@ impl CdnClient {
         unreachable!()
     }
 
-    async fn purge_cloudflare_internal(&self, url: &str, body: &PurgeRequest) -> Result<()> {
+    async fn purge_cloudflare_internal(&self, url: &str, api_token: &str, body: &PurgeRequest) -> Result<()> {
         const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
 
         let response = self
             .client
             .post(url)
             .timeout(REQUEST_TIMEOUT)
-            .header("Authorization", format!("Bearer {}", self.api_token))
+            .header("Authorization", format!("Bearer {}", api_token))
             .json(body)
             .send()
             .await?;
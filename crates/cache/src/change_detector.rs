@@ -1,5 +1,7 @@
 use lighty_models::VersionBuilder;
 use super::models::ChangeDetector;
+use std::collections::HashMap;
+use std::hash::Hash;
 
 impl ChangeDetector {
     /// Détecte si des changements existent et retourne les détails
@@ -14,6 +16,7 @@ impl ChangeDetector {
         Self::check_mods_changes(old, new, &mut changes);
         Self::check_natives_changes(old, new, &mut changes);
         Self::check_assets_changes(old, new, &mut changes);
+        Self::check_extra_files_changes(old, new, &mut changes);
 
         (!changes.is_empty(), changes)
     }
@@ -124,6 +127,30 @@ impl ChangeDetector {
         }
     }
 
+    fn check_extra_files_changes(
+        old: &VersionBuilder,
+        new: &VersionBuilder,
+        changes: &mut Vec<String>,
+    ) {
+        if !Self::extra_files_have_changed(old, new) {
+            return;
+        }
+
+        let old_count = old.extra_files.len();
+        let new_count = new.extra_files.len();
+
+        if old_count != new_count {
+            let change_msg = if new_count > old_count {
+                format!("extra files added ({} -> {})", old_count, new_count)
+            } else {
+                format!("extra files removed ({} -> {})", old_count, new_count)
+            };
+            changes.push(change_msg);
+        } else {
+            changes.push("extra files updated".to_string());
+        }
+    }
+
     // === Fonctions de comparaison ===
 
     fn client_has_changed(old: &VersionBuilder, new: &VersionBuilder) -> bool {
@@ -137,43 +164,27 @@ impl ChangeDetector {
     }
 
     fn libraries_have_changed(old: &VersionBuilder, new: &VersionBuilder) -> bool {
-        if old.libraries.len() != new.libraries.len() {
-            return true;
-        }
-
-        old.libraries
-            .iter()
-            .zip(new.libraries.iter())
-            .any(|(old_lib, new_lib)| {
-                old_lib.sha1 != new_lib.sha1 || old_lib.size != new_lib.size
-            })
+        Self::keyed_differ(
+            old.libraries.iter().map(|lib| (&lib.path, (&lib.sha1, lib.size))),
+            new.libraries.iter().map(|lib| (&lib.path, (&lib.sha1, lib.size))),
+        )
     }
 
     fn mods_have_changed(old: &VersionBuilder, new: &VersionBuilder) -> bool {
-        if old.mods.len() != new.mods.len() {
-            return true;
-        }
-
-        old.mods
-            .iter()
-            .zip(new.mods.iter())
-            .any(|(old_mod, new_mod)| {
-                old_mod.sha1 != new_mod.sha1 || old_mod.size != new_mod.size
-            })
+        Self::keyed_differ(
+            old.mods.iter().map(|m| (&m.name, (&m.sha1, m.size))),
+            new.mods.iter().map(|m| (&m.name, (&m.sha1, m.size))),
+        )
     }
 
     fn natives_have_changed(old: &VersionBuilder, new: &VersionBuilder) -> bool {
         match (&old.natives, &new.natives) {
             (Some(old_natives), Some(new_natives)) => {
-                if old_natives.len() != new_natives.len() {
-                    return true;
-                }
-                old_natives
-                    .iter()
-                    .zip(new_natives.iter())
-                    .any(|(old_n, new_n)| {
-                        old_n.sha1 != new_n.sha1 || old_n.size != new_n.size
-                    })
+                // Keyed by path: the same file name can exist for several OSes
+                Self::keyed_differ(
+                    old_natives.iter().map(|n| (&n.path, (&n.sha1, n.size))),
+                    new_natives.iter().map(|n| (&n.path, (&n.sha1, n.size))),
+                )
             }
             (None, Some(_)) | (Some(_), None) => true,
             (None, None) => false,
@@ -181,15 +192,30 @@ impl ChangeDetector {
     }
 
     fn assets_have_changed(old: &VersionBuilder, new: &VersionBuilder) -> bool {
-        if old.assets.len() != new.assets.len() {
-            return true;
-        }
+        Self::keyed_differ(
+            old.assets.iter().map(|a| (&a.path, (&a.hash, a.size))),
+            new.assets.iter().map(|a| (&a.path, (&a.hash, a.size))),
+        )
+    }
+
+    fn extra_files_have_changed(old: &VersionBuilder, new: &VersionBuilder) -> bool {
+        Self::keyed_differ(
+            old.extra_files.iter().map(|f| (&f.path, (&f.sha1, f.size))),
+            new.extra_files.iter().map(|f| (&f.path, (&f.sha1, f.size))),
+        )
+    }
 
-        old.assets
-            .iter()
-            .zip(new.assets.iter())
-            .any(|(old_asset, new_asset)| {
-                old_asset.hash != new_asset.hash || old_asset.size != new_asset.size
-            })
+    /// Compares two file lists by key (like `FileDiff`), independent of their order
+    fn keyed_differ<K, V>(
+        old: impl Iterator<Item = (K, V)>,
+        new: impl Iterator<Item = (K, V)>,
+    ) -> bool
+    where
+        K: Eq + Hash,
+        V: PartialEq,
+    {
+        let old_map: HashMap<K, V> = old.collect();
+        let new_map: HashMap<K, V> = new.collect();
+        old_map != new_map
     }
 }
@@ -0,0 +1,138 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Stops calling a failing remote API for a cooldown after consecutive failures
+/// Once the cooldown ends a single trial call is let through (half-open): success closes
+/// the circuit, failure reopens it for another cooldown
+pub struct CircuitBreaker {
+    /// Consecutive failures opening the circuit (0 = never opens)
+    threshold: u32,
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+}
+
+#[derive(Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    /// Start of the half-open trial call, concurrent callers are refused until it reports back
+    /// Expires after a cooldown, so a trial dropped before reporting doesn't keep the circuit open
+    trial_started: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            cooldown,
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    /// Whether a call may be attempted now
+    pub fn allow(&self) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        match state.open_until {
+            Some(until) if now < until => false,
+            Some(_) => {
+                // Half-open: only the caller claiming the trial gets through
+                if state.trial_started.is_some_and(|started| now < started + self.cooldown) {
+                    return false;
+                }
+                state.trial_started = Some(now);
+                true
+            }
+            None => true,
+        }
+    }
+
+    pub fn record_success(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.consecutive_failures = 0;
+        state.open_until = None;
+        state.trial_started = None;
+    }
+
+    /// Records a failed call, returns true if it opened the circuit
+    pub fn record_failure(&self) -> bool {
+        if self.threshold == 0 {
+            return false;
+        }
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.trial_started = None;
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+        if state.consecutive_failures >= self.threshold {
+            state.open_until = Some(Instant::now() + self.cooldown);
+            return true;
+        }
+        false
+    }
+
+    pub fn cooldown(&self) -> Duration {
+        self.cooldown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COOLDOWN: Duration = Duration::from_millis(50);
+
+    fn open_breaker() -> CircuitBreaker {
+        let breaker = CircuitBreaker::new(3, COOLDOWN);
+        for _ in 0..3 {
+            breaker.record_failure();
+        }
+        breaker
+    }
+
+    #[test]
+    fn opens_at_the_threshold() {
+        let breaker = CircuitBreaker::new(3, COOLDOWN);
+        assert!(!breaker.record_failure());
+        assert!(!breaker.record_failure());
+        assert!(breaker.allow());
+        assert!(breaker.record_failure());
+        assert!(!breaker.allow());
+    }
+
+    #[test]
+    fn never_opens_with_a_zero_threshold() {
+        let breaker = CircuitBreaker::new(0, COOLDOWN);
+        for _ in 0..10 {
+            assert!(!breaker.record_failure());
+        }
+        assert!(breaker.allow());
+    }
+
+    #[test]
+    fn half_open_lets_a_single_trial_through() {
+        let breaker = open_breaker();
+        assert!(!breaker.allow());
+
+        std::thread::sleep(COOLDOWN + Duration::from_millis(10));
+        assert!(breaker.allow());
+        // Concurrent callers wait for the trial to report back
+        assert!(!breaker.allow());
+
+        // A failed trial reopens the circuit for another cooldown
+        assert!(breaker.record_failure());
+        assert!(!breaker.allow());
+    }
+
+    #[test]
+    fn success_closes_the_circuit() {
+        let breaker = open_breaker();
+        std::thread::sleep(COOLDOWN + Duration::from_millis(10));
+        assert!(breaker.allow());
+
+        breaker.record_success();
+        assert!(breaker.allow());
+        assert!(breaker.allow());
+        // The failure count starts over
+        assert!(!breaker.record_failure());
+        assert!(breaker.allow());
+    }
+}
@@ -1,15 +1,24 @@
+use super::circuit_breaker::CircuitBreaker;
 use super::errors::CacheError;
+use lighty_config::{CloudflareSettings, PurgeZone};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 type Result<T> = std::result::Result<T, CacheError>;
 
 pub struct CloudflareClient {
-    zone_id: String,
-    api_token: String,
+    zones: Vec<ZoneClient>,
+    /// Attempts per purge (at least 1)
+    max_retries: u32,
     client: reqwest::Client,
 }
 
+/// A zone and the circuit breaker pausing its purges after repeated failures
+struct ZoneClient {
+    zone: PurgeZone,
+    breaker: CircuitBreaker,
+}
+
 #[derive(Serialize)]
 struct PurgeRequest {
     files: Vec<String>,
@@ -21,21 +30,80 @@ struct PurgeResponse {
 }
 
 impl CloudflareClient {
-    pub fn new(zone_id: String, api_token: String) -> Self {
+    pub fn new(zones: Vec<PurgeZone>, max_retries: u32, breaker_threshold: u32, breaker_cooldown: Duration) -> Self {
         Self {
-            zone_id,
-            api_token,
+            zones: zones
+                .into_iter()
+                .map(|zone| ZoneClient {
+                    zone,
+                    breaker: CircuitBreaker::new(breaker_threshold, breaker_cooldown),
+                })
+                .collect(),
+            max_retries: max_retries.max(1),
             client: reqwest::Client::new(),
         }
     }
 
+    /// Client for `zone_id` (every server, if set) and each of `[[cloudflare.zones]]`
+    pub fn from_settings(settings: &CloudflareSettings) -> Self {
+        let mut zones = Vec::with_capacity(settings.zones.len() + 1);
+        if !settings.zone_id.is_empty() {
+            zones.push(PurgeZone {
+                zone_id: settings.zone_id.clone(),
+                api_token: settings.api_token.clone(),
+                servers: Vec::new(),
+            });
+        }
+        zones.extend(settings.zones.iter().cloned());
+        Self::new(
+            zones,
+            settings.max_retries,
+            settings.circuit_breaker_threshold,
+            Duration::from_secs(settings.circuit_breaker_cooldown_secs),
+        )
+    }
+
+    pub fn zone_count(&self) -> usize {
+        self.zones.len()
+    }
+
+    /// Purges the server's metadata JSON in every zone covering it
+    /// All zones are attempted, the first failure is returned
+    /// Zones whose circuit is open are skipped with `CacheError::CircuitOpen`
     pub async fn purge_cache(&self, server_name: &str) -> Result<()> {
-        const MAX_RETRIES: usize = 3;
+        let mut result = Ok(());
+        for zone in self.zones.iter().filter(|zone| zone.zone.covers(server_name)) {
+            let purged = if zone.breaker.allow() {
+                let purged = self.purge_zone(&zone.zone, server_name).await;
+                match &purged {
+                    Ok(()) => zone.breaker.record_success(),
+                    Err(_) => {
+                        if zone.breaker.record_failure() {
+                            tracing::warn!(
+                                "Cloudflare purges for zone {} keep failing, pausing them for {:?}",
+                                zone.zone.zone_id,
+                                zone.breaker.cooldown()
+                            );
+                        }
+                    }
+                }
+                purged
+            } else {
+                Err(CacheError::CircuitOpen(zone.zone.zone_id.clone()))
+            };
+            if result.is_ok() {
+                result = purged;
+            }
+        }
+        result
+    }
+
+    async fn purge_zone(&self, zone: &PurgeZone, server_name: &str) -> Result<()> {
         const INITIAL_BACKOFF: Duration = Duration::from_millis(100);
 
         let url = format!(
             "https://api.cloudflare.com/client/v4/zones/{}/purge_cache",
-            self.zone_id
+            zone.zone_id
         );
 
         // Purge metadata JSON
@@ -43,25 +111,32 @@ impl CloudflareClient {
         let body = PurgeRequest { files };
 
         // Retry with exponential backoff
-        for attempt in 0..MAX_RETRIES {
-            match self.purge_cache_internal(&url, &body).await {
+        for attempt in 0..self.max_retries {
+            match self.purge_cache_internal(&url, &zone.api_token, &body).await {
                 Ok(()) => {
-                    tracing::info!("Cloudflare cache purged for {}", server_name);
+                    tracing::info!("Cloudflare cache purged for {} (zone {})", server_name, zone.zone_id);
                     return Ok(());
                 }
-                Err(e) if attempt < MAX_RETRIES - 1 => {
-                    let backoff = INITIAL_BACKOFF * 2u32.pow(attempt as u32);
+                Err(e) if attempt < self.max_retries - 1 => {
+                    let backoff = INITIAL_BACKOFF * 2u32.saturating_pow(attempt);
                     tracing::warn!(
-                        "Cloudflare purge attempt {} failed for {}: {}. Retrying in {:?}...",
+                        "Cloudflare purge attempt {} failed for {} (zone {}): {}. Retrying in {:?}...",
                         attempt + 1,
                         server_name,
+                        zone.zone_id,
                         e,
                         backoff
                     );
                     tokio::time::sleep(backoff).await;
                 }
                 Err(e) => {
-                    tracing::error!("Cloudflare purge failed for {} after {} attempts: {}", server_name, MAX_RETRIES, e);
+                    tracing::error!(
+                        "Cloudflare purge failed for {} (zone {}) after {} attempts: {}",
+                        server_name,
+                        zone.zone_id,
+                        self.max_retries,
+                        e
+                    );
                     return Err(e);
                 }
             }
@@ -70,14 +145,14 @@ impl CloudflareClient {
         unreachable!()
     }
 
-    async fn purge_cache_internal(&self, url: &str, body: &PurgeRequest) -> Result<()> {
+    async fn purge_cache_internal(&self, url: &str, api_token: &str, body: &PurgeRequest) -> Result<()> {
         const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
 
         let response = self
             .client
             .post(url)
             .timeout(REQUEST_TIMEOUT)
-            .header("Authorization", format!("Bearer {}", self.api_token))
+            .header("Authorization", format!("Bearer {}", api_token))
             .json(body)
             .send()
             .await?;
@@ -14,9 +14,24 @@ pub enum CacheError {
     #[error("Join error: {0}")]
     JoinError(#[from] tokio::task::JoinError),
 
+    #[error("Storage backend not initialized")]
+    StorageNotInitialized,
+
     #[error("Server not found: {0}")]
     ServerNotFound(String),
 
+    #[error("Neither [cloudflare] nor [cdn] purging is configured")]
+    PurgeNotConfigured,
+
+    #[error("{0} is unavailable in maintenance mode")]
+    InMaintenance(String),
+
+    #[error("Server {0} is not frozen")]
+    NotFrozen(String),
+
+    #[error("Rescan of server {0} did not complete, it stays frozen")]
+    PromoteFailed(String),
+
     #[error("Cache operation failed: {0}")]
     CacheOperationFailed(String),
 
@@ -25,6 +40,9 @@ pub enum CacheError {
 
     #[error("Cloudflare API error: {0}")]
     CloudflareError(String),
+
+    #[error("Cloudflare purges paused after repeated failures (zone {0})")]
+    CircuitOpen(String),
 }
 
 // Convert reqwest errors to CacheError
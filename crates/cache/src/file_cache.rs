@@ -1,6 +1,7 @@
 use super::errors::CacheError;
 use bytes::Bytes;
 use std::path::Path;
+use std::time::SystemTime;
 use super::models::FileCache;
 
 type Result<T> = std::result::Result<T, CacheError>;
@@ -9,30 +10,45 @@ impl FileCache {
     pub fn from_file_sync(path: &Path) -> Result<Self> {
         // Read file into memory synchronously
         let data = std::fs::read(path)?;
+        let modified = std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+        Ok(Self::from_bytes(path, Bytes::from(data), None, modified))
+    }
+
+    /// Builds a cache entry from contents already in memory (`path` picks the MIME type)
+    /// The contents are hashed unless their `sha1` is already known; `modified` is the mtime read with them
+    pub fn from_bytes(path: &Path, data: Bytes, sha1: Option<String>, modified: Option<SystemTime>) -> Self {
         let size = data.len() as u64;
 
-        // Calculate SHA1
-        let sha1 = {
+        let sha1 = sha1.unwrap_or_else(|| {
             use sha1::{Digest, Sha1};
             let mut hasher = Sha1::new();
             hasher.update(&data);
             format!("{:x}", hasher.finalize())
-        };
+        });
 
         // Get MIME type
         let mime_type = mime_guess::from_path(path)
             .first_or_octet_stream()
             .to_string();
 
-        Ok(Self {
-            data: Bytes::from(data),
+        Self {
+            data,
             sha1,
             size,
             mime_type,
-        })
+            brotli: None,
+            modified,
+        }
+    }
+
+    /// Adds the brotli variant for text formats (no-op for already compressed ones)
+    pub fn precompress(&mut self) {
+        if super::precompress::is_compressible(&self.mime_type) {
+            self.brotli = super::precompress::brotli(&self.data);
+        }
     }
 
     pub fn memory_usage(&self) -> u64 {
-        self.data.len() as u64
+        (self.data.len() + self.brotli.as_ref().map_or(0, Bytes::len)) as u64
     }
 }
@@ -1,20 +1,28 @@
-use lighty_models::VersionBuilder;
+use lighty_models::{FileCategory, VersionBuilder};
 use std::collections::HashMap;
 
 /// Changements détectés entre deux versions
 #[derive(Debug)]
 pub struct FileDiff {
     pub added: Vec<FileChange>,
+    /// New content, or a URL that moved (e.g. `[storage] upload_*` toggled), so the file is synced and republished
     pub modified: Vec<FileChange>,
     pub removed: Vec<FileChange>,
+    /// Files with the same content but another mtime (`cache.include_mtime` on, or toggled)
+    /// Nothing to sync, but the published metadata changes
+    pub retimed: usize,
 }
 
 #[derive(Debug, Clone)]
 pub struct FileChange {
     pub file_type: FileType,
     pub remote_key: String,
+    /// `{server}/{category-prefixed path}`, mapped onto the server's folders before reading
     pub local_path: String,
     pub url: String,
+    /// Checksum and size of the file, used to skip uploads the remote already holds
+    pub sha1: Option<String>,
+    pub size: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
@@ -24,6 +32,21 @@ pub enum FileType {
     Mod,
     Native,
     Asset,
+    Extra,
+}
+
+impl FileType {
+    /// Server folder holding files of this type
+    pub fn category(&self) -> FileCategory {
+        match self {
+            FileType::Client => FileCategory::Client,
+            FileType::Library => FileCategory::Libraries,
+            FileType::Mod => FileCategory::Mods,
+            FileType::Native => FileCategory::Natives,
+            FileType::Asset => FileCategory::Assets,
+            FileType::Extra => FileCategory::Extra,
+        }
+    }
 }
 
 impl FileDiff {
@@ -36,13 +59,15 @@ impl FileDiff {
         let mut added = Vec::new();
         let mut modified = Vec::new();
         let mut removed = Vec::new();
+        let mut retimed = 0;
 
         if let Some(old) = old {
-            Self::diff_client(server_name, old, new, &mut added, &mut modified, &mut removed);
-            Self::diff_libraries(server_name, old, new, &mut added, &mut modified, &mut removed);
-            Self::diff_mods(server_name, old, new, &mut added, &mut modified, &mut removed);
-            Self::diff_natives(server_name, old, new, &mut added, &mut modified, &mut removed);
-            Self::diff_assets(server_name, old, new, &mut added, &mut modified, &mut removed);
+            Self::diff_client(server_name, old, new, &mut added, &mut modified, &mut removed, &mut retimed);
+            Self::diff_libraries(server_name, old, new, &mut added, &mut modified, &mut removed, &mut retimed);
+            Self::diff_mods(server_name, old, new, &mut added, &mut modified, &mut removed, &mut retimed);
+            Self::diff_natives(server_name, old, new, &mut added, &mut modified, &mut removed, &mut retimed);
+            Self::diff_assets(server_name, old, new, &mut added, &mut modified, &mut removed, &mut retimed);
+            Self::diff_extra_files(server_name, old, new, &mut added, &mut modified, &mut removed, &mut retimed);
         } else {
             // First scan: all files are "added"
             Self::add_all_files(server_name, new, &mut added);
@@ -52,23 +77,39 @@ impl FileDiff {
             added,
             modified,
             removed,
+            retimed,
         }
     }
 
-    /// Updates the URL map incrementally based on this diff (avoids full rebuild)
+    /// Changes of the categories matching `keep`, e.g. the ones synced to remote storage
+    pub fn filter_categories(&self, keep: impl Fn(FileCategory) -> bool) -> Self {
+        let filter = |changes: &[FileChange]| -> Vec<FileChange> {
+            changes.iter().filter(|change| keep(change.file_type.category())).cloned().collect()
+        };
+        Self {
+            added: filter(&self.added),
+            modified: filter(&self.modified),
+            removed: filter(&self.removed),
+            retimed: self.retimed,
+        }
+    }
+
+    /// Updates the URL and path metadata maps incrementally based on this diff (avoids full rebuild)
     pub fn apply_to_url_map(&self, builder: &mut VersionBuilder) {
-        // Add new files and update modified files
-        for change in self.added.iter().chain(self.modified.iter()) {
+        // Remove deleted files first, a new file may reuse their path
+        for change in &self.removed {
             if !change.url.is_empty() {
-                let path = Self::extract_relative_path(&change.local_path);
-                builder.add_url_mapping(change.url.clone(), path);
+                builder.remove_url_mapping(&change.url);
             }
         }
 
-        // Remove deleted files
-        for change in &self.removed {
+        // Add new files and update modified files
+        for change in self.added.iter().chain(self.modified.iter()) {
             if !change.url.is_empty() {
-                builder.remove_url_mapping(&change.url);
+                let relative_path = Self::extract_relative_path(&change.local_path);
+                // Strip the category folder, add_url_mapping prefixes it like build_url_map
+                let path = relative_path.split_once('/').map_or(relative_path.as_str(), |(_, path)| path);
+                builder.add_url_mapping(&change.url, change.file_type.category(), path, change.sha1.as_ref(), change.size);
             }
         }
     }
@@ -91,34 +132,40 @@ impl FileDiff {
         added: &mut Vec<FileChange>,
         modified: &mut Vec<FileChange>,
         removed: &mut Vec<FileChange>,
+        retimed: &mut usize,
     ) {
         match (&old.client, &new.client) {
             (None, Some(client)) => {
                 added.push(FileChange {
                     file_type: FileType::Client,
                     remote_key: format!("{}/client.jar", server_name),
-                    local_path: format!("{}/client/client.jar", server_name),
+                    local_path: format!("{}/client/{}", server_name, client.path),
                     url: client.url.clone(),
+                    sha1: Some(client.sha1.clone()),
+                    size: Some(client.size),
                 });
             }
             (Some(old_client), None) => {
                 removed.push(FileChange {
                     file_type: FileType::Client,
                     remote_key: format!("{}/client.jar", server_name),
-                    local_path: format!("{}/client/client.jar", server_name),
+                    local_path: format!("{}/client/{}", server_name, old_client.path),
                     url: old_client.url.clone(),
+                    sha1: Some(old_client.sha1.clone()),
+                    size: Some(old_client.size),
                 });
             }
-            (Some(old_client), Some(new_client)) => {
-                if old_client.sha1 != new_client.sha1 {
-                    modified.push(FileChange {
-                        file_type: FileType::Client,
-                        remote_key: format!("{}/client.jar", server_name),
-                        local_path: format!("{}/client/client.jar", server_name),
-                        url: new_client.url.clone(),
-                    });
-                }
+            (Some(old_client), Some(new_client)) if old_client.sha1 != new_client.sha1 || old_client.url != new_client.url => {
+                modified.push(FileChange {
+                    file_type: FileType::Client,
+                    remote_key: format!("{}/client.jar", server_name),
+                    local_path: format!("{}/client/{}", server_name, new_client.path),
+                    url: new_client.url.clone(),
+                    sha1: Some(new_client.sha1.clone()),
+                    size: Some(new_client.size),
+                });
             }
+            (Some(old_client), Some(new_client)) if old_client.mtime != new_client.mtime => *retimed += 1,
             _ => {}
         }
     }
@@ -130,6 +177,7 @@ impl FileDiff {
         added: &mut Vec<FileChange>,
         modified: &mut Vec<FileChange>,
         removed: &mut Vec<FileChange>,
+        retimed: &mut usize,
     ) {
         // Create maps for O(1) lookup
         let old_map: HashMap<_, _> = old
@@ -152,13 +200,17 @@ impl FileDiff {
 
             if let Some(old_lib) = old_map.get(path) {
                 // Exists in both: check if modified
-                if old_lib.sha1 != new_lib.sha1 {
+                if old_lib.sha1 != new_lib.sha1 || old_lib.url != new_lib.url {
                     modified.push(FileChange {
                         file_type: FileType::Library,
                         remote_key,
                         local_path,
                         url,
+                        sha1: new_lib.sha1.clone(),
+                        size: new_lib.size,
                     });
+                } else if old_lib.mtime != new_lib.mtime {
+                    *retimed += 1;
                 }
             } else {
                 // Only in new: added
@@ -167,6 +219,8 @@ impl FileDiff {
                     remote_key,
                     local_path,
                     url,
+                    sha1: new_lib.sha1.clone(),
+                    size: new_lib.size,
                 });
             }
         }
@@ -181,6 +235,8 @@ impl FileDiff {
                     remote_key: format!("{}/libraries/{}", server_name, path_str),
                     local_path: format!("{}/libraries/{}", server_name, path_str),
                     url,
+                    sha1: old_lib.sha1.clone(),
+                    size: old_lib.size,
                 });
             }
         }
@@ -193,23 +249,28 @@ impl FileDiff {
         added: &mut Vec<FileChange>,
         modified: &mut Vec<FileChange>,
         removed: &mut Vec<FileChange>,
+        retimed: &mut usize,
     ) {
         let old_map: HashMap<_, _> = old.mods.iter().map(|m| (&m.name, m)).collect();
         let new_map: HashMap<_, _> = new.mods.iter().map(|m| (&m.name, m)).collect();
 
         for (name, new_mod) in &new_map {
             let remote_key = format!("{}/mods/{}", server_name, name);
-            let local_path = format!("{}/mods/{}", server_name, name);
+            let local_path = format!("{}/mods/{}", server_name, new_mod.path.as_deref().unwrap_or(name));
             let url = new_mod.url.as_deref().unwrap_or_default().to_string();
 
             if let Some(old_mod) = old_map.get(name) {
-                if old_mod.sha1 != new_mod.sha1 {
+                if old_mod.sha1 != new_mod.sha1 || old_mod.url != new_mod.url {
                     modified.push(FileChange {
                         file_type: FileType::Mod,
                         remote_key,
                         local_path,
                         url,
+                        sha1: new_mod.sha1.clone(),
+                        size: new_mod.size,
                     });
+                } else if old_mod.mtime != new_mod.mtime {
+                    *retimed += 1;
                 }
             } else {
                 added.push(FileChange {
@@ -217,6 +278,8 @@ impl FileDiff {
                     remote_key,
                     local_path,
                     url,
+                    sha1: new_mod.sha1.clone(),
+                    size: new_mod.size,
                 });
             }
         }
@@ -227,8 +290,10 @@ impl FileDiff {
                 removed.push(FileChange {
                     file_type: FileType::Mod,
                     remote_key: format!("{}/mods/{}", server_name, name),
-                    local_path: format!("{}/mods/{}", server_name, name),
+                    local_path: format!("{}/mods/{}", server_name, old_mod.path.as_deref().unwrap_or(name)),
                     url,
+                    sha1: old_mod.sha1.clone(),
+                    size: old_mod.size,
                 });
             }
         }
@@ -241,6 +306,7 @@ impl FileDiff {
         added: &mut Vec<FileChange>,
         modified: &mut Vec<FileChange>,
         removed: &mut Vec<FileChange>,
+        retimed: &mut usize,
     ) {
         match (&old.natives, &new.natives) {
             (None, Some(new_natives)) => {
@@ -248,8 +314,10 @@ impl FileDiff {
                     added.push(FileChange {
                         file_type: FileType::Native,
                         remote_key: format!("{}/natives/{}", server_name, native.name),
-                        local_path: format!("{}/natives/{}", server_name, native.name),
+                        local_path: format!("{}/natives/{}", server_name, native.path),
                         url: native.url.clone(),
+                        sha1: Some(native.sha1.clone()),
+                        size: Some(native.size),
                     });
                 }
             }
@@ -258,8 +326,10 @@ impl FileDiff {
                     removed.push(FileChange {
                         file_type: FileType::Native,
                         remote_key: format!("{}/natives/{}", server_name, native.name),
-                        local_path: format!("{}/natives/{}", server_name, native.name),
+                        local_path: format!("{}/natives/{}", server_name, native.path),
                         url: native.url.clone(),
+                        sha1: Some(native.sha1.clone()),
+                        size: Some(native.size),
                     });
                 }
             }
@@ -269,17 +339,21 @@ impl FileDiff {
 
                 for (name, new_native) in &new_map {
                     let remote_key = format!("{}/natives/{}", server_name, name);
-                    let local_path = format!("{}/natives/{}", server_name, name);
+                    let local_path = format!("{}/natives/{}", server_name, new_native.path);
                     let url = new_native.url.clone();
 
                     if let Some(old_native) = old_map.get(name) {
-                        if old_native.sha1 != new_native.sha1 {
+                        if old_native.sha1 != new_native.sha1 || old_native.url != new_native.url {
                             modified.push(FileChange {
                                 file_type: FileType::Native,
                                 remote_key,
                                 local_path,
                                 url,
+                                sha1: Some(new_native.sha1.clone()),
+                                size: Some(new_native.size),
                             });
+                        } else if old_native.mtime != new_native.mtime {
+                            *retimed += 1;
                         }
                     } else {
                         added.push(FileChange {
@@ -287,6 +361,8 @@ impl FileDiff {
                             remote_key,
                             local_path,
                             url,
+                            sha1: Some(new_native.sha1.clone()),
+                            size: Some(new_native.size),
                         });
                     }
                 }
@@ -296,8 +372,10 @@ impl FileDiff {
                         removed.push(FileChange {
                             file_type: FileType::Native,
                             remote_key: format!("{}/natives/{}", server_name, name),
-                            local_path: format!("{}/natives/{}", server_name, name),
+                            local_path: format!("{}/natives/{}", server_name, old_native.path),
                             url: old_native.url.clone(),
+                            sha1: Some(old_native.sha1.clone()),
+                            size: Some(old_native.size),
                         });
                     }
                 }
@@ -313,6 +391,7 @@ impl FileDiff {
         added: &mut Vec<FileChange>,
         modified: &mut Vec<FileChange>,
         removed: &mut Vec<FileChange>,
+        retimed: &mut usize,
     ) {
         let old_map: HashMap<_, _> = old.assets.iter().map(|a| (&a.path, a)).collect();
         let new_map: HashMap<_, _> = new.assets.iter().map(|a| (&a.path, a)).collect();
@@ -324,13 +403,17 @@ impl FileDiff {
             let url = new_asset.url.as_deref().unwrap_or_default().to_string();
 
             if let Some(old_asset) = old_map.get(path) {
-                if old_asset.hash != new_asset.hash {
+                if old_asset.hash != new_asset.hash || old_asset.url != new_asset.url {
                     modified.push(FileChange {
                         file_type: FileType::Asset,
                         remote_key,
                         local_path,
                         url,
+                        sha1: Some(new_asset.hash.clone()),
+                        size: Some(new_asset.size),
                     });
+                } else if old_asset.mtime != new_asset.mtime {
+                    *retimed += 1;
                 }
             } else {
                 added.push(FileChange {
@@ -338,6 +421,8 @@ impl FileDiff {
                     remote_key,
                     local_path,
                     url,
+                    sha1: Some(new_asset.hash.clone()),
+                    size: Some(new_asset.size),
                 });
             }
         }
@@ -351,6 +436,63 @@ impl FileDiff {
                     remote_key: format!("{}/assets/{}", server_name, path_str),
                     local_path: format!("{}/assets/{}", server_name, path_str),
                     url,
+                    sha1: Some(old_asset.hash.clone()),
+                    size: Some(old_asset.size),
+                });
+            }
+        }
+    }
+
+    fn diff_extra_files(
+        server_name: &str,
+        old: &VersionBuilder,
+        new: &VersionBuilder,
+        added: &mut Vec<FileChange>,
+        modified: &mut Vec<FileChange>,
+        removed: &mut Vec<FileChange>,
+        retimed: &mut usize,
+    ) {
+        let old_map: HashMap<_, _> = old.extra_files.iter().map(|f| (&f.path, f)).collect();
+        let new_map: HashMap<_, _> = new.extra_files.iter().map(|f| (&f.path, f)).collect();
+
+        for (path, new_file) in &new_map {
+            let remote_key = format!("{}/extra/{}", server_name, path);
+            let local_path = format!("{}/extra/{}", server_name, path);
+
+            if let Some(old_file) = old_map.get(path) {
+                if old_file.sha1 != new_file.sha1 || old_file.url != new_file.url {
+                    modified.push(FileChange {
+                        file_type: FileType::Extra,
+                        remote_key,
+                        local_path,
+                        url: new_file.url.clone(),
+                        sha1: Some(new_file.sha1.clone()),
+                        size: Some(new_file.size),
+                    });
+                } else if old_file.mtime != new_file.mtime {
+                    *retimed += 1;
+                }
+            } else {
+                added.push(FileChange {
+                    file_type: FileType::Extra,
+                    remote_key,
+                    local_path,
+                    url: new_file.url.clone(),
+                    sha1: Some(new_file.sha1.clone()),
+                    size: Some(new_file.size),
+                });
+            }
+        }
+
+        for (path, old_file) in &old_map {
+            if !new_map.contains_key(path) {
+                removed.push(FileChange {
+                    file_type: FileType::Extra,
+                    remote_key: format!("{}/extra/{}", server_name, path),
+                    local_path: format!("{}/extra/{}", server_name, path),
+                    url: old_file.url.clone(),
+                    sha1: Some(old_file.sha1.clone()),
+                    size: Some(old_file.size),
                 });
             }
         }
@@ -362,8 +504,10 @@ impl FileDiff {
             added.push(FileChange {
                 file_type: FileType::Client,
                 remote_key: format!("{}/client.jar", server_name),
-                local_path: format!("{}/client/client.jar", server_name),
+                local_path: format!("{}/client/{}", server_name, client.path),
                 url: client.url.clone(),
+                sha1: Some(client.sha1.clone()),
+                size: Some(client.size),
             });
         }
 
@@ -376,6 +520,8 @@ impl FileDiff {
                     remote_key: format!("{}/libraries/{}", server_name, path),
                     local_path: format!("{}/libraries/{}", server_name, path),
                     url,
+                    sha1: lib.sha1.clone(),
+                    size: lib.size,
                 });
             }
         }
@@ -386,8 +532,10 @@ impl FileDiff {
             added.push(FileChange {
                 file_type: FileType::Mod,
                 remote_key: format!("{}/mods/{}", server_name, mod_file.name),
-                local_path: format!("{}/mods/{}", server_name, mod_file.name),
+                local_path: format!("{}/mods/{}", server_name, mod_file.path.as_deref().unwrap_or(&mod_file.name)),
                 url,
+                sha1: mod_file.sha1.clone(),
+                size: mod_file.size,
             });
         }
 
@@ -397,8 +545,10 @@ impl FileDiff {
                 added.push(FileChange {
                     file_type: FileType::Native,
                     remote_key: format!("{}/natives/{}", server_name, native.name),
-                    local_path: format!("{}/natives/{}", server_name, native.name),
+                    local_path: format!("{}/natives/{}", server_name, native.path),
                     url: native.url.clone(),
+                    sha1: Some(native.sha1.clone()),
+                    size: Some(native.size),
                 });
             }
         }
@@ -412,8 +562,174 @@ impl FileDiff {
                     remote_key: format!("{}/assets/{}", server_name, path),
                     local_path: format!("{}/assets/{}", server_name, path),
                     url,
+                    sha1: Some(asset.hash.clone()),
+                    size: Some(asset.size),
                 });
             }
         }
+
+        // Extra files
+        for extra in &new.extra_files {
+            added.push(FileChange {
+                file_type: FileType::Extra,
+                remote_key: format!("{}/extra/{}", server_name, extra.path),
+                local_path: format!("{}/extra/{}", server_name, extra.path),
+                url: extra.url.clone(),
+                sha1: Some(extra.sha1.clone()),
+                size: Some(extra.size),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const BASE: &str = "http://localhost:8080/survival";
+
+    fn version(libraries: serde_json::Value, mods: serde_json::Value, extra_files: serde_json::Value) -> VersionBuilder {
+        let mut version: VersionBuilder = serde_json::from_value(json!({
+            "main_class": { "main_class": "net.minecraft.client.main.Main" },
+            "java_version": { "major_version": 21 },
+            "arguments": { "game": [], "jvm": [] },
+            "libraries": libraries,
+            "mods": mods,
+            "natives": [{
+                "name": "lwjgl.dll",
+                "url": format!("{}/natives/windows/lwjgl.dll", BASE),
+                "path": "windows/lwjgl.dll",
+                "sha1": "4444444444444444444444444444444444444444",
+                "size": 40,
+                "os": "windows"
+            }],
+            "client": {
+                "name": "client.jar",
+                "url": format!("{}/client/client.jar", BASE),
+                "path": "client.jar",
+                "sha1": "1111111111111111111111111111111111111111",
+                "size": 10
+            },
+            "extra_files": extra_files,
+            "assets": [{
+                "hash": "5555555555555555555555555555555555555555",
+                "size": 50,
+                "url": format!("{}/assets/55/5555555555555555555555555555555555555555", BASE),
+                "path": "55/5555555555555555555555555555555555555555"
+            }]
+        }))
+        .unwrap();
+        version.build_url_map();
+        version
+    }
+
+    fn library(name: &str, sha1: &str) -> serde_json::Value {
+        json!({
+            "name": name,
+            "url": format!("{}/libraries/{}", BASE, name),
+            "path": name,
+            "sha1": sha1,
+            "size": 20
+        })
+    }
+
+    fn mod_file(name: &str, sha1: &str, size: u64) -> serde_json::Value {
+        json!({
+            "name": name,
+            "url": format!("{}/mods/{}", BASE, name),
+            "path": name,
+            "sha1": sha1,
+            "size": size
+        })
+    }
+
+    fn extra_file(path: &str, sha1: &str) -> serde_json::Value {
+        json!({
+            "url": format!("{}/extra/{}", BASE, path),
+            "path": path,
+            "sha1": sha1,
+            "size": 60
+        })
+    }
+
+    #[test]
+    fn incremental_update_matches_full_rebuild() {
+        let old = version(
+            json!([
+                library("org/kept.jar", "2222222222222222222222222222222222222222"),
+                library("org/removed.jar", "2222222222222222222222222222222222222223"),
+            ]),
+            json!([
+                mod_file("changed.jar", "3333333333333333333333333333333333333333", 30),
+                mod_file("kept.jar", "3333333333333333333333333333333333333334", 31),
+            ]),
+            json!([extra_file("config/options.txt", "6666666666666666666666666666666666666666")]),
+        );
+        let new = version(
+            json!([
+                library("org/kept.jar", "2222222222222222222222222222222222222222"),
+                library("org/added.jar", "2222222222222222222222222222222222222224"),
+            ]),
+            json!([
+                mod_file("changed.jar", "3333333333333333333333333333333333333335", 32),
+                mod_file("kept.jar", "3333333333333333333333333333333333333334", 31),
+                mod_file("added.jar", "3333333333333333333333333333333333333336", 33),
+            ]),
+            json!([extra_file("config/servers.dat", "6666666666666666666666666666666666666667")]),
+        );
+
+        let diff = FileDiff::compute("survival", Some(&old), &new);
+        assert!(!diff.added.is_empty() && !diff.modified.is_empty() && !diff.removed.is_empty());
+
+        let mut updated = old.clone();
+        diff.apply_to_url_map(&mut updated);
+
+        assert_eq!(updated.url_to_path_map, new.url_to_path_map);
+        assert_eq!(updated.path_to_meta_map, new.path_to_meta_map);
+        assert_eq!(updated.sha1_to_path_map, new.sha1_to_path_map);
+    }
+
+    #[test]
+    fn mtime_only_changes_are_retimed() {
+        let mods = || json!([mod_file("kept.jar", "3333333333333333333333333333333333333334", 31)]);
+        let extra = || json!([extra_file("config/options.txt", "6666666666666666666666666666666666666666")]);
+        let old = version(json!([]), mods(), extra());
+
+        // `cache.include_mtime` turned on: same files, each now carrying its mtime
+        let mut new = version(json!([]), mods(), extra());
+        new.mods[0].mtime = Some(1_700_000_000);
+        new.extra_files[0].mtime = Some(1_700_000_001);
+        let diff = FileDiff::compute("survival", Some(&old), &new);
+        assert!(diff.added.is_empty() && diff.modified.is_empty() && diff.removed.is_empty());
+        assert_eq!(diff.retimed, 2);
+
+        // File touched without a content change
+        let mut touched = new.clone();
+        touched.mods[0].mtime = Some(1_700_000_100);
+        assert_eq!(FileDiff::compute("survival", Some(&new), &touched).retimed, 1);
+
+        assert_eq!(FileDiff::compute("survival", Some(&new), &new).retimed, 0);
+    }
+
+    #[test]
+    fn moved_urls_are_modified() {
+        let mods = || json!([mod_file("kept.jar", "3333333333333333333333333333333333333334", 31)]);
+        let old = version(json!([]), mods(), json!([]));
+
+        // Mods kept off remote storage: same file, published under `server.base_url`
+        let mut new = version(json!([]), mods(), json!([]));
+        new.mods[0].url = Some("https://updater.example.com/survival/mods/kept.jar".to_string());
+        new.build_url_map();
+
+        let diff = FileDiff::compute("survival", Some(&old), &new);
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        assert_eq!(diff.modified.len(), 1);
+        assert_eq!(diff.modified[0].url, "https://updater.example.com/survival/mods/kept.jar");
+
+        let mut updated = old.clone();
+        diff.apply_to_url_map(&mut updated);
+        assert_eq!(updated.url_to_path_map, new.url_to_path_map);
+        assert_eq!(updated.path_to_meta_map, new.path_to_meta_map);
     }
 }
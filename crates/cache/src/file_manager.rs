@@ -1,11 +1,14 @@
 use super::models::{FileCacheManager, FileCache};
 use super::errors::CacheError;
-use lighty_config::ServerConfig;
+use lighty_config::{CacheWarming, FileCategory, ServerConfig};
 use lighty_filesystem::FileSystem;
+use bytes::Bytes;
 use dashmap::DashMap;
 use moka::future::Cache;
+use std::path::PathBuf;
 use std::sync::Arc;
-use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::SystemTime;
 use tokio::sync::broadcast;
 use walkdir::WalkDir;
 
@@ -13,7 +16,13 @@ type Result<T> = std::result::Result<T, CacheError>;
 
 impl FileCacheManager {
     /// Creates a new FileCacheManager with specified capacity in GB (0 = unlimited)
-    pub fn new(max_capacity_gb: u64, shutdown_tx: broadcast::Sender<()>) -> Self {
+    /// and per-file size cutoff in MB (0 = no limit)
+    pub fn new(
+        max_capacity_gb: u64,
+        max_file_size_mb: u64,
+        precompress_brotli: bool,
+        shutdown_tx: broadcast::Sender<()>,
+    ) -> Self {
         let cache = if max_capacity_gb == 0 {
             // Unlimited capacity
             Cache::builder()
@@ -36,9 +45,12 @@ impl FileCacheManager {
 
         Self {
             cache,
+            max_file_size_bytes: max_file_size_mb * 1024 * 1024,
+            precompress_brotli,
             shutdown_tx,
             tasks: Arc::new(DashMap::new()),
             task_counter: Arc::new(AtomicUsize::new(0)),
+            generation: Arc::new(AtomicU64::new(0)),
         }
     }
 
@@ -48,6 +60,79 @@ impl FileCacheManager {
         self.cache.get(&key).await
     }
 
+    /// Removes a file from the cache (e.g. stale or corrupt entry)
+    pub async fn invalidate_file(&self, server: &str, path: &str) {
+        let key: Arc<str> = format!("{}/{}", server, path).into();
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        self.cache.invalidate(&key).await;
+    }
+
+    /// Removes every cached file of a server (server removed from the config)
+    pub async fn invalidate_server(&self, server: &str) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        let prefix = format!("{}/", server);
+        let keys: Vec<Arc<str>> = self
+            .cache
+            .iter()
+            .filter(|(key, _)| key.starts_with(&prefix))
+            .map(|(key, _)| Arc::clone(key.as_ref()))
+            .collect();
+        for key in keys {
+            self.cache.invalidate(&key).await;
+        }
+    }
+
+    /// Caches a file the disk fallback already read, so later requests are served from RAM
+    /// Runs in the background and once per file (concurrent misses share the load), oversized files are skipped
+    /// `sha1` is the metadata's checksum of the file, the data is only hashed without one
+    pub fn insert_loaded(
+        &self,
+        server: &str,
+        path: &str,
+        full_path: PathBuf,
+        data: Bytes,
+        sha1: Option<String>,
+        modified: Option<SystemTime>,
+    ) {
+        if self.max_file_size_bytes > 0 && data.len() as u64 > self.max_file_size_bytes {
+            return;
+        }
+
+        let key: Arc<str> = format!("{}/{}", server, path).into();
+        let cache = self.cache.clone();
+        let precompress = self.precompress_brotli;
+        let generation = Arc::clone(&self.generation);
+        let started = generation.load(Ordering::Acquire);
+        tokio::spawn(async move {
+            let init = async {
+                // Invalidated since the disk read: the bytes may be stale
+                if generation.load(Ordering::Acquire) != started {
+                    return None;
+                }
+                let load = move || {
+                    let mut file = FileCache::from_bytes(&full_path, data, sha1, modified);
+                    if precompress {
+                        file.precompress();
+                    }
+                    file
+                };
+                match tokio::task::spawn_blocking(load).await {
+                    Ok(file) => Some(file),
+                    Err(e) => {
+                        tracing::warn!("Failed to cache {}: {}", key, e);
+                        None
+                    }
+                }
+            };
+            let entry = cache.entry(Arc::clone(&key)).or_optionally_insert_with(init).await;
+
+            // Invalidated while loading: drop the entry this load just inserted
+            if entry.is_some_and(|entry| entry.is_fresh()) && generation.load(Ordering::Acquire) != started {
+                cache.invalidate(&key).await;
+            }
+        });
+    }
+
     /// Adds a file to the cache
     async fn add_file(&self, server: &str, path: &str, file: FileCache) -> Result<()> {
         let key: Arc<str> = format!("{}/{}", server, path).into();
@@ -56,18 +141,53 @@ impl FileCacheManager {
     }
 
     /// Gets cache statistics (entry count and weighted size in KB)
-    pub fn get_stats(&self) -> (u64, u64) {
+    /// Pending inserts/evictions are applied first, moka only updates its counters lazily
+    pub async fn get_stats(&self) -> (u64, u64) {
+        self.cache.run_pending_tasks().await;
         let entry_count = self.cache.entry_count();
         let weighted_size_kb = self.cache.weighted_size();
         (entry_count, weighted_size_kb)
     }
 
     /// Loads all files from all servers into cache (partial success: continues even if some fail)
+    /// `file_load_threads` sizes a dedicated rayon pool for the load (0 = global pool)
+    /// Only files under the `warm_categories` folders are preloaded, the rest are served from disk
+    /// `CacheWarming::Lazy` preloads nothing and `MetadataOnly` only `.json` files, others are
+    /// cached on first request by the disk fallback
     pub async fn load_all_servers(
         &self,
         servers: &[Arc<ServerConfig>],
         base_path: &str,
+        file_load_threads: usize,
+        warm_categories: &[FileCategory],
+        warming: CacheWarming,
     ) -> Result<()> {
+        if warm_categories.is_empty() {
+            tracing::info!("No warm categories configured, skipping RAM cache preload");
+            return Ok(());
+        }
+        if warming == CacheWarming::Lazy {
+            tracing::info!("Lazy cache warming, files are cached on first request");
+            return Ok(());
+        }
+
+        // Scoped pool, dropped once the load is done
+        let pool = if file_load_threads > 0 {
+            match rayon::ThreadPoolBuilder::new()
+                .num_threads(file_load_threads)
+                .thread_name(|i| format!("file-load-{}", i))
+                .build()
+            {
+                Ok(pool) => Some(Arc::new(pool)),
+                Err(e) => {
+                    tracing::warn!("Failed to build file load thread pool, using global pool: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
         let server_names: Vec<_> = servers
             .iter()
             .filter(|s| s.enabled)
@@ -77,7 +197,9 @@ impl FileCacheManager {
         let load_futures: Vec<_> = servers
             .iter()
             .filter(|server_config| server_config.enabled)
-            .map(|server_config| self.load_server_files(server_config.as_ref(), base_path))
+            .map(|server_config| {
+                self.load_server_files(server_config.as_ref(), base_path, warm_categories, warming, pool.clone())
+            })
             .collect();
 
         let results = futures::future::join_all(load_futures).await;
@@ -116,44 +238,72 @@ impl FileCacheManager {
         &self,
         server_config: &ServerConfig,
         base_path: &str,
+        warm_categories: &[FileCategory],
+        warming: CacheWarming,
+        pool: Option<Arc<rayon::ThreadPool>>,
     ) -> Result<()> {
         let server_path = FileSystem::build_server_path(base_path, &server_config.name);
 
-        // Collect all files to cache
-        let files: Vec<_> = WalkDir::new(&server_path)
-            .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(|e| e.path().is_file())
-            .filter(|e| {
-                // Only cache .jar, .json, and asset files
-                let path = e.path();
-                path.extension().map_or(false, |ext| ext == "jar" || ext == "json")
-                    || path.starts_with(server_path.join("assets"))
-            })
-            .map(|e| e.path().to_path_buf())
-            .collect();
+        // Collect all files to cache, keyed by their category-prefixed path
+        let mut files = Vec::new();
+        for &category in warm_categories {
+            let dir = server_path.join(server_config.category_dir(category));
+            files.extend(
+                WalkDir::new(&dir)
+                    .into_iter()
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.path().is_file())
+                    .filter(|e| {
+                        // Only cache .jar, .json, asset and extra files (only .json for metadata_only)
+                        let path = e.path();
+                        if warming == CacheWarming::MetadataOnly {
+                            return path.extension().is_some_and(|ext| ext == "json");
+                        }
+                        path.extension().is_some_and(|ext| ext == "jar" || ext == "json")
+                            || matches!(category, FileCategory::Assets | FileCategory::Extra)
+                    })
+                    .filter(|e| {
+                        // Oversized files are always served from disk
+                        self.max_file_size_bytes == 0
+                            || e.metadata().is_ok_and(|m| m.len() <= self.max_file_size_bytes)
+                    })
+                    .filter(|e| {
+                        e.path()
+                            .strip_prefix(&server_path)
+                            .is_ok_and(|relative| !server_config.in_other_category_dir(category, relative))
+                    })
+                    .filter_map(|e| {
+                        let relative = e.path().strip_prefix(&dir).ok()?.to_string_lossy().replace('\\', "/");
+                        Some((format!("{}/{}", category.dir(), relative), e.path().to_path_buf()))
+                    }),
+            );
+        }
 
         // Load files in parallel using thread pool
         let server_name = server_config.name.clone();
-        let base_path_clone = server_path.clone();
+        let precompress = self.precompress_brotli;
 
         let results: Vec<_> = tokio::task::spawn_blocking(move || {
             use rayon::prelude::*;
-            files
-                .par_iter()
-                .filter_map(|file_path| {
-                    let relative_path = file_path
-                        .strip_prefix(&base_path_clone)
-                        .ok()?
-                        .to_string_lossy()
-                        .replace('\\', "/");
-
-                    // Load file synchronously in thread pool
-                    let file_cache = FileCache::from_file_sync(file_path).ok()?;
-
-                    Some((relative_path, file_cache))
-                })
-                .collect::<Vec<_>>()
+            let load = || {
+                files
+                    .par_iter()
+                    .filter_map(|(relative_path, file_path)| {
+                        // Load file synchronously in thread pool
+                        let mut file_cache = FileCache::from_file_sync(file_path).ok()?;
+                        if precompress {
+                            file_cache.precompress();
+                        }
+
+                        Some((relative_path.clone(), file_cache))
+                    })
+                    .collect::<Vec<_>>()
+            };
+
+            match pool {
+                Some(pool) => pool.install(load),
+                None => load(),
+            }
         })
         .await?;
 
@@ -0,0 +1,40 @@
+use std::path::Path;
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+/// Cheap fingerprint of a server folder (entry count, total size, newest mtime)
+/// Used by the polling rescan to skip re-hashing servers whose contents did not change
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirFingerprint {
+    entry_count: usize,
+    total_size: u64,
+    max_mtime: Option<SystemTime>,
+}
+
+impl DirFingerprint {
+    /// Walks the folder reading metadata only (no file contents)
+    /// Directories are included so that deletions (which bump the parent mtime) are caught
+    pub fn compute(path: &Path) -> Self {
+        let mut fingerprint = Self {
+            entry_count: 0,
+            total_size: 0,
+            max_mtime: None,
+        };
+
+        for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+
+            fingerprint.entry_count += 1;
+            if metadata.is_file() {
+                fingerprint.total_size += metadata.len();
+            }
+            if let Ok(mtime) = metadata.modified() {
+                fingerprint.max_mtime = fingerprint.max_mtime.max(Some(mtime));
+            }
+        }
+
+        fingerprint
+    }
+}
@@ -6,13 +6,21 @@ mod change_detector;
 mod rescan_orchestrator;
 mod file_diff;
 mod cloudflare;
+mod circuit_breaker;
 mod cdn;
 mod errors;
 mod server_path_cache;
+mod pending_sync;
+mod fingerprint;
+mod scan_stats;
+mod asset_pages;
+mod precompress;
 
-pub use models::{CacheManager, FileCacheManager, FileCache, ChangeDetector, RescanOrchestrator};
+pub use models::{BackgroundTask, CacheManager, FileCacheManager, FileCache, ChangeDetector, RescanOrchestrator, ReuploadSummary, PurgeSummary, FreezeStatus, MaintenanceStatus, UrlRewrite};
 pub use file_diff::{FileDiff, FileChange, FileType};
 pub use cloudflare::CloudflareClient;
 pub use cdn::CdnClient;
 pub use errors::CacheError;
 pub use server_path_cache::ServerPathCache;
+pub use scan_stats::ScanDurationStats;
+pub use asset_pages::AssetsPage;
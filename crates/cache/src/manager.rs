@@ -1,4 +1,5 @@
-use super::models::{CacheManager, FileCacheManager, RescanOrchestrator, FileCache, CacheUpdater, CacheStore};
+use super::models::{BackgroundTask, TrackedTask, CacheManager, FileCacheManager, RescanOrchestrator, FileCache, CacheUpdater, CacheStore, ReuploadSummary, PurgeSummary, FreezeStatus, MaintenanceStatus, UrlRewrite, MetadataEncodings};
+use bytes::Bytes;
 use super::errors::CacheError;
 use lighty_config::{Config, ServerConfig};
 use lighty_events::{AppEvent, EventBus};
@@ -10,6 +11,9 @@ use tokio::sync::{RwLock, broadcast};
 
 type Result<T> = std::result::Result<T, CacheError>;
 
+/// Reason reported while maintenance mode comes from `[server] maintenance_mode`
+const CONFIG_MAINTENANCE_REASON: &str = "Enabled by [server] maintenance_mode";
+
 impl CacheManager {
     pub async fn new(
         config: Arc<RwLock<Config>>,
@@ -20,22 +24,43 @@ impl CacheManager {
     ) -> Self {
         let (shutdown_tx, _) = broadcast::channel(1);
 
-        // Create cache store (implements CacheUpdater trait)
-        let (cache_store, cache) = CacheStore::new();
-        let last_updated = Arc::new(DashMap::new());
-
         // Read cache capacity, base path, and servers from config
-        let (max_cache_gb, base_path, servers) = {
+        let (max_cache_gb, max_cached_file_mb, max_cached_versions, precompress_brotli, precompress_gzip, public_base_url, base_path, servers, maintenance_mode) = {
             let config_read = config.read().await;
             (
                 config_read.cache.max_memory_cache_gb,
+                config_read.cache.max_cached_file_mb,
+                config_read.cache.max_cached_versions,
+                config_read.cache.precompress_brotli,
+                config_read.cache.precompress_gzip,
+                config_read.cdn.public_base_url.clone(),
                 std::path::PathBuf::from(config_read.server.base_path.as_ref()),
                 config_read.servers.clone(),
+                config_read.server.maintenance_mode,
             )
         };
 
+        // Create version cache store (implements CacheUpdater trait)
+        let url_rewrite = storage
+            .as_ref()
+            .and_then(|storage| UrlRewrite::new(storage.as_ref(), &public_base_url));
+        let cache = Arc::new(CacheStore::new(
+            max_cached_versions,
+            url_rewrite,
+            MetadataEncodings {
+                brotli: precompress_brotli,
+                gzip: precompress_gzip,
+            },
+        ));
+        let last_updated = Arc::new(DashMap::new());
+
         // Create file cache manager with configured capacity
-        let file_cache_manager = Arc::new(FileCacheManager::new(max_cache_gb, shutdown_tx.clone()));
+        let file_cache_manager = Arc::new(FileCacheManager::new(
+            max_cache_gb,
+            max_cached_file_mb,
+            precompress_brotli,
+            shutdown_tx.clone(),
+        ));
 
         // Create and initialize server path cache for O(1) lookups
         let server_path_cache = Arc::new(super::server_path_cache::ServerPathCache::new());
@@ -43,7 +68,7 @@ impl CacheManager {
 
         // Create rescan orchestrator with storage, cdn and cloudflare
         let rescan_orchestrator = Arc::new(RescanOrchestrator::new(
-            Arc::new(cache_store),
+            Arc::clone(&cache) as Arc<dyn CacheUpdater>,
             Arc::clone(&last_updated),
             Arc::clone(&config),
             Arc::clone(&events),
@@ -53,9 +78,13 @@ impl CacheManager {
             base_path,
             Arc::clone(&server_path_cache),
         ));
+        if maintenance_mode {
+            rescan_orchestrator.enter_maintenance(CONFIG_MAINTENANCE_REASON.to_string());
+        }
 
         Self {
             cache,
+            lazy_loads: Arc::new(DashMap::new()),
             file_cache_manager,
             last_updated,
             rescan_orchestrator,
@@ -78,6 +107,29 @@ impl CacheManager {
         self.rescan_orchestrator.resume();
     }
 
+    /// Enters maintenance mode: files and metadata keep being served, rescans and uploads stop
+    pub fn enter_maintenance(&self, reason: String) -> MaintenanceStatus {
+        self.rescan_orchestrator.enter_maintenance(reason)
+    }
+
+    /// Leaves maintenance mode
+    pub fn exit_maintenance(&self) -> MaintenanceStatus {
+        self.rescan_orchestrator.exit_maintenance()
+    }
+
+    /// Applies a `[server] maintenance_mode` changed by a config reload
+    pub fn set_maintenance_from_config(&self, enabled: bool) {
+        if enabled {
+            self.enter_maintenance(CONFIG_MAINTENANCE_REASON.to_string());
+        } else {
+            self.exit_maintenance();
+        }
+    }
+
+    pub fn get_maintenance_status(&self) -> MaintenanceStatus {
+        self.rescan_orchestrator.maintenance_status()
+    }
+
     /// Rebuild server path cache (call after config reload)
     pub async fn rebuild_server_cache(&self) {
         let (servers, base_path) = {
@@ -88,12 +140,66 @@ impl CacheManager {
         tracing::debug!("Server path cache rebuilt after config reload");
     }
 
+    /// Applies a changed `[cdn] public_base_url` to the published metadata (call after config reload)
+    pub fn refresh_public_base_url(&self, public_base_url: &str) {
+        let url_rewrite = self
+            .rescan_orchestrator
+            .storage
+            .as_ref()
+            .and_then(|storage| UrlRewrite::new(storage.as_ref(), public_base_url));
+
+        if self.cache.set_url_rewrite(url_rewrite) {
+            tracing::info!("Public base URL changed, metadata re-serialized");
+        }
+    }
+
     /// Rebuild server path cache with provided data (used during config hot-reload to avoid deadlock)
     pub fn rebuild_server_cache_with_data(&self, servers: &[Arc<ServerConfig>], base_path: &str) {
         self.server_path_cache.rebuild(servers, base_path);
         tracing::debug!("Server path cache rebuilt after config reload (with provided data)");
     }
 
+    /// Updates the path cache entry of a single server (call after config reload)
+    /// Disabled servers are dropped, like in a full rebuild
+    pub fn update_server_path(&self, server: &ServerConfig, base_path: &str) {
+        let server_path = std::path::Path::new(base_path).join(server.name.as_ref());
+        if server.enabled {
+            self.server_path_cache.update_server(server.name.to_string(), server_path);
+        } else {
+            self.server_path_cache.remove_server(&server_path);
+        }
+    }
+
+    /// Drops the path cache entry of a server removed from the config
+    pub fn remove_server_path(&self, server_name: &str, base_path: &str) {
+        self.server_path_cache.remove_server(&std::path::Path::new(base_path).join(server_name));
+    }
+
+    /// Forgets a server removed from the config: version cache, last update, RAM files and path cache entry
+    /// Scans and RAM cache loads still in flight can't cache it again until `restore_server`
+    /// Files on disk and in remote storage are left untouched
+    pub async fn remove_server(&self, server_name: &str, base_path: &str) {
+        self.cache.remove(server_name);
+        self.lazy_loads.remove(server_name);
+        self.rescan_orchestrator.forget_server(server_name);
+        self.file_cache_manager.invalidate_server(server_name).await;
+        self.remove_server_path(server_name, base_path);
+
+        self.events.emit(AppEvent::ServerRemoved {
+            name: server_name.to_string(),
+        });
+    }
+
+    /// Lets a server removed earlier be cached again (server added back to the config)
+    pub fn restore_server(&self, server_name: &str) {
+        self.cache.restore(server_name);
+    }
+
+    /// Lets the rescan loop pick up reloaded settings: watched servers, rescan interval (call after config reload)
+    pub fn notify_config_reloaded(&self) {
+        self.rescan_orchestrator.notify_config_reloaded();
+    }
+
     /// Signals graceful shutdown to all background tasks
     pub async fn shutdown(&self) {
         tracing::info!("CacheManager: Initiating graceful shutdown...");
@@ -106,8 +212,8 @@ impl CacheManager {
 
         // Remove and await each handle
         for task_id in handles {
-            if let Some((_, handle)) = self.tasks.remove(&task_id) {
-                let _ = handle.await;
+            if let Some((_, task)) = self.tasks.remove(&task_id) {
+                let _ = task.handle.await;
             }
         }
 
@@ -121,12 +227,52 @@ impl CacheManager {
         self.file_cache_manager.get_file(server, path).await
     }
 
+    /// Evicts a RAM-cached file whose SHA1 no longer matches the metadata
+    pub async fn evict_mismatched_file(&self, server: &str, path: &str, expected_sha1: &str, cached_sha1: &str) {
+        self.file_cache_manager.invalidate_file(server, path).await;
+        self.events.emit(AppEvent::Error {
+            context: format!("Cached file {}/{} failed verification", server, path),
+            error: format!("expected SHA1 {}, cached {}", expected_sha1, cached_sha1),
+        });
+    }
+
+    /// Caches a file served from disk (lazy population, bounded by the RAM cache capacity)
+    /// `sha1` comes from the metadata and `modified` from the disk read, saving a re-hash and a blocking stat
+    pub fn cache_loaded_file(
+        &self,
+        server: &str,
+        path: &str,
+        full_path: std::path::PathBuf,
+        data: Bytes,
+        sha1: Option<String>,
+        modified: Option<std::time::SystemTime>,
+    ) {
+        self.file_cache_manager.insert_loaded(server, path, full_path, data, sha1, modified);
+    }
+
     /// Get cache statistics (entry count and weighted size in KB)
-    pub fn get_cache_stats(&self) -> (u64, u64) {
-        self.file_cache_manager.get_stats()
+    pub async fn get_cache_stats(&self) -> (u64, u64) {
+        self.file_cache_manager.get_stats().await
+    }
+
+    /// Recent scan durations of the configured servers (last and rolling average)
+    pub async fn get_scan_durations(&self) -> Vec<crate::ScanDurationStats> {
+        let config = self.config.read().await;
+        self.rescan_orchestrator
+            .scan_durations(|name| config.servers.iter().any(|s| s.name.as_ref() == name))
     }
 
     pub async fn initialize(&self) -> Result<()> {
+        // Every scan needs a storage backend, fail at startup instead of skipping rescans later
+        if !self.rescan_orchestrator.has_storage() {
+            let err = CacheError::StorageNotInitialized;
+            self.events.emit(AppEvent::Error {
+                context: "Cache initialization".to_string(),
+                error: err.to_string(),
+            });
+            return Err(err);
+        }
+
         let config = self.config.read().await;
         if !config.cache.enabled {
             return Ok(());
@@ -135,11 +281,16 @@ impl CacheManager {
         if config.cache.auto_scan {
             let servers = config.servers.clone();
             let base_path = config.server.base_path.clone();
+            let file_load_threads = config.cache.file_load_threads;
+            let warm_categories = config.cache.warm_categories.clone();
+            let warming = config.cache.cache_warming;
             drop(config);
 
             self.events.emit(AppEvent::InitialScanStarted);
             self.rescan_orchestrator.scan_all_servers().await?;
-            self.file_cache_manager.load_all_servers(&servers, base_path.as_ref()).await?;
+            self.file_cache_manager
+                .load_all_servers(&servers, base_path.as_ref(), file_load_threads, &warm_categories, warming)
+                .await?;
         }
 
         Ok(())
@@ -170,17 +321,72 @@ impl CacheManager {
         });
 
         let task_id = self.task_counter.fetch_add(1, Ordering::SeqCst);
-        self.tasks.insert(task_id, handle);
+        self.tasks.insert(task_id, TrackedTask::new("rescan loop".to_string(), handle));
+    }
+
+    /// Long-lived background tasks of the cache (rescan loop), sorted by id
+    /// Ended tasks of the manager stay listed with `running = false`, e.g. a rescan loop that stopped
+    pub fn background_tasks(&self) -> Vec<BackgroundTask> {
+        let mut tasks: Vec<_> = self.tasks.iter().map(|entry| entry.status(*entry.key())).collect();
+        tasks.sort_by_key(|task| task.id);
+        tasks
     }
 
     pub async fn get(&self, server_name: &str) -> Option<Arc<VersionBuilder>> {
-        self.cache.get(server_name).map(|entry| Arc::clone(entry.value()))
+        self.get_version(server_name).await
     }
 
     pub async fn force_rescan(&self, server_name: &str) -> Result<()> {
         self.rescan_orchestrator.force_rescan_server(server_name).await
     }
 
+    /// Rescans a server through change detection (cloud sync, purges), e.g. after `upload_*` changed
+    pub async fn republish_server(&self, server_name: &str) -> Result<()> {
+        self.rescan_orchestrator.republish_server(server_name).await
+    }
+
+    /// Re-uploads all files of a server to remote storage (unchanged files included)
+    pub async fn reupload_server(&self, server_name: &str) -> Result<ReuploadSummary> {
+        let version = self
+            .get_version(server_name)
+            .await
+            .ok_or_else(|| CacheError::ServerNotFound(server_name.to_string()))?;
+        self.rescan_orchestrator.reupload_server(server_name, &version).await
+    }
+
+    /// Purges a server's metadata and files from the edge cache
+    pub async fn purge_server(&self, server_name: &str) -> Result<PurgeSummary> {
+        let version = self
+            .get_version(server_name)
+            .await
+            .ok_or_else(|| CacheError::ServerNotFound(server_name.to_string()))?;
+        self.rescan_orchestrator.purge_server(server_name, &version).await
+    }
+
+    /// Pins a server to the version it currently serves (staged rollouts)
+    pub async fn freeze(&self, server_name: &str) -> Result<FreezeStatus> {
+        let version = self
+            .get_version(server_name)
+            .await
+            .ok_or_else(|| CacheError::ServerNotFound(server_name.to_string()))?;
+        Ok(self.rescan_orchestrator.freeze(server_name, version))
+    }
+
+    /// Unfreezes a server and publishes the files staged meanwhile
+    pub async fn unfreeze_and_promote(&self, server_name: &str) -> Result<FreezeStatus> {
+        self.rescan_orchestrator.unfreeze_and_promote(server_name).await
+    }
+
+    /// Freeze state of every frozen server
+    pub fn get_frozen_servers(&self) -> Vec<FreezeStatus> {
+        self.rescan_orchestrator.frozen_servers()
+    }
+
+    /// Whether a file of a frozen server was changed on disk since the freeze (its local copy must not be served)
+    pub fn is_staged_file(&self, server_name: &str, path: &str) -> bool {
+        self.rescan_orchestrator.is_staged(server_name, path)
+    }
+
     pub async fn get_all_servers(&self) -> Vec<String> {
         let config = self.config.read().await;
         config.servers
@@ -190,13 +396,106 @@ impl CacheManager {
             .collect()
     }
 
+    /// Enabled servers that are not `unlisted`, the names clients may discover
+    pub async fn get_listed_servers(&self) -> Vec<String> {
+        let config = self.config.read().await;
+        config.servers
+            .iter()
+            .filter(|s| s.enabled && !s.unlisted)
+            .map(|s| s.name.to_string())
+            .collect()
+    }
+
     pub async fn get_server_config(&self, name: &str) -> Option<Arc<ServerConfig>> {
         let config = self.config.read().await;
         config.servers.iter().find(|s| s.name.as_ref() == name).map(Arc::clone)
     }
 
+    /// Gets a server version, lazily rescanning it if it was evicted from the bounded cache
     pub async fn get_version(&self, name: &str) -> Option<Arc<VersionBuilder>> {
-        self.cache.get(name).map(|entry| Arc::clone(entry.value()))
+        if let Some(version) = self.cache.get(name) {
+            return Some(version);
+        }
+
+        if !self.cache.is_evicted(name) {
+            return None;
+        }
+
+        let lock = Arc::clone(self.lazy_loads.entry(name.to_string()).or_default().value());
+        let _guard = lock.lock().await;
+
+        // Another request may have reloaded it while we were waiting
+        if let Some(version) = self.cache.get(name) {
+            return Some(version);
+        }
+
+        tracing::debug!("Server {} was evicted from version cache, rescanning", name);
+        if let Err(e) = self.rescan_orchestrator.force_rescan_server(name).await {
+            tracing::warn!("Failed to reload evicted server {}: {}", name, e);
+        }
+        self.lazy_loads.remove(name);
+
+        self.cache.get(name)
+    }
+
+    /// Returns a server version with file URLs as published (re-based onto `cdn.public_base_url`)
+    pub async fn get_published_version(&self, name: &str) -> Option<Arc<VersionBuilder>> {
+        let version = self.get_version(name).await?;
+        match self.cache.url_rewrite() {
+            Some(rewrite) => Some(Arc::new(version.with_rebased_urls(&rewrite.origin, &rewrite.public_base))),
+            None => Some(version),
+        }
+    }
+
+    /// Storage backend files are published to
+    pub fn storage(&self) -> Option<Arc<dyn lighty_storage::StorageBackend>> {
+        self.rescan_orchestrator.storage()
+    }
+
+    /// Returns a file URL as published (re-based onto `cdn.public_base_url`)
+    pub fn published_url(&self, url: &str) -> String {
+        match self.cache.url_rewrite() {
+            Some(rewrite) => rewrite.apply(url),
+            None => url.to_string(),
+        }
+    }
+
+    /// Returns the pre-serialized metadata JSON for a server (built on cache update)
+    pub fn get_metadata_json(&self, name: &str) -> Option<Arc<Bytes>> {
+        self.cache.get_metadata_json(name)
+    }
+
+    /// Returns the brotli-compressed metadata JSON for a server (`cache.precompress_brotli`)
+    pub fn get_metadata_brotli(&self, name: &str) -> Option<Arc<Bytes>> {
+        self.cache.get_metadata_brotli(name)
+    }
+
+    /// Returns the gzip-compressed metadata JSON for a server (`cache.precompress_gzip`)
+    pub fn get_metadata_gzip(&self, name: &str) -> Option<Arc<Bytes>> {
+        self.cache.get_metadata_gzip(name)
+    }
+
+    /// Returns the metadata JSON with one page of assets (`page` starts at 1)
+    /// Sliced from the pre-serialized document, serialized on the fly if unavailable
+    pub async fn get_metadata_page(&self, name: &str, page: usize, per_page: usize) -> Option<Bytes> {
+        if let Some(json) = self.cache.get_metadata_page(name, page, per_page) {
+            return Some(json);
+        }
+
+        let version = self.get_published_version(name).await?;
+        let metadata = Bytes::from(serde_json::to_vec(version.as_ref()).ok()?);
+        let index = super::asset_pages::AssetIndex::build(&metadata, &version.assets)?;
+        Some(index.page(&metadata, page, per_page))
+    }
+
+    /// Returns the pre-serialized flat file manifest for a server (built on cache update)
+    pub fn get_manifest_json(&self, name: &str) -> Option<Arc<Bytes>> {
+        self.cache.get_manifest_json(name)
+    }
+
+    /// Returns the pre-serialized hierarchical file tree for a server (built on cache update)
+    pub fn get_tree_json(&self, name: &str) -> Option<Arc<Bytes>> {
+        self.cache.get_tree_json(name)
     }
 
     pub fn get_last_update(&self, name: &str) -> Option<String> {
@@ -206,15 +505,81 @@ impl CacheManager {
 
 // Implement CacheUpdater trait for CacheManager (allows decoupled updates from RescanOrchestrator)
 impl CacheUpdater for CacheManager {
-    fn insert(&self, server_name: String, version: Arc<VersionBuilder>) {
-        self.cache.insert(server_name, version);
+    fn insert_serialized(&self, server_name: String, version: Arc<VersionBuilder>, json: Option<super::models::SerializedVersion>) {
+        self.cache.insert_serialized(server_name, version, json);
     }
 
     fn get(&self, server_name: &str) -> Option<Arc<VersionBuilder>> {
-        self.cache.get(server_name).map(|entry| Arc::clone(entry.value()))
+        CacheUpdater::get(self.cache.as_ref(), server_name)
     }
 
     fn contains(&self, server_name: &str) -> bool {
-        self.cache.contains_key(server_name)
+        self.cache.contains(server_name)
+    }
+
+    fn is_evicted(&self, server_name: &str) -> bool {
+        self.cache.is_evicted(server_name)
+    }
+
+    fn url_rewrite(&self) -> Option<UrlRewrite> {
+        self.cache.url_rewrite()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn default_config() -> Config {
+        config_with("").await
+    }
+
+    async fn config_with(extra: &str) -> Config {
+        let path = std::env::temp_dir().join(format!("lighty-cache-test-{}-{}.toml", std::process::id(), fastrand::u64(..)));
+        let content = format!("{}\n{}", lighty_config::scaffold_template(None), extra);
+        tokio::fs::write(&path, content).await.unwrap();
+        let config = Config::from_file_no_migration(&path).await;
+        let _ = tokio::fs::remove_file(&path).await;
+        config.unwrap()
+    }
+
+    #[tokio::test]
+    async fn initialize_fails_without_storage() {
+        let config = Arc::new(RwLock::new(default_config().await));
+        let manager = CacheManager::new(config, EventBus::new(true), None, None, None).await;
+
+        assert!(matches!(manager.initialize().await, Err(CacheError::StorageNotInitialized)));
+    }
+
+    const SURVIVAL: &str = r#"
+[[servers]]
+name = "survival"
+loader = "vanilla"
+loader_version = ""
+minecraft_version = "1.21.1"
+main_class = "Main"
+java_version = 21
+"#;
+
+    #[tokio::test]
+    async fn failed_promote_keeps_server_frozen() {
+        let config = Arc::new(RwLock::new(config_with(SURVIVAL).await));
+        // Without storage the promote rescan can't run
+        let manager = CacheManager::new(config, EventBus::new(true), None, None, None).await;
+        let version: VersionBuilder = serde_json::from_value(serde_json::json!({
+            "main_class": { "main_class": "Main" },
+            "java_version": { "major_version": 21 },
+            "arguments": { "game": [], "jvm": [] },
+            "libraries": [],
+            "mods": [],
+            "assets": []
+        }))
+        .unwrap();
+        let frozen = manager.rescan_orchestrator.freeze("survival", Arc::new(version));
+
+        assert!(matches!(manager.unfreeze_and_promote("survival").await, Err(CacheError::PromoteFailed(_))));
+        let still_frozen = manager.get_frozen_servers();
+        assert_eq!(still_frozen.len(), 1);
+        assert_eq!(still_frozen[0].frozen_since, frozen.frozen_since);
     }
 }
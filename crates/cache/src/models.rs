@@ -4,55 +4,321 @@ use lighty_models::VersionBuilder;
 use bytes::Bytes;
 use dashmap::DashMap;
 use moka::future::Cache;
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use tokio::sync::{RwLock, broadcast};
 use tokio::task::JoinHandle;
 
+/// Pre-serialized JSON documents of a server version
+#[derive(Clone)]
+pub struct SerializedVersion {
+    /// Full `{server}.json` metadata
+    pub metadata: Arc<Bytes>,
+    /// Flat `{server}/manifest.json` file list
+    pub manifest: Arc<Bytes>,
+    /// Hierarchical `{server}/tree.json` file list
+    pub tree: Arc<Bytes>,
+    /// Asset positions in `metadata`, for paged responses
+    pub asset_index: Option<Arc<super::asset_pages::AssetIndex>>,
+    /// Brotli variant of `metadata` (`cache.precompress_brotli`)
+    pub metadata_brotli: Option<Arc<Bytes>>,
+    /// Gzip variant of `metadata` (`cache.precompress_gzip`)
+    pub metadata_gzip: Option<Arc<Bytes>>,
+}
+
+/// Compressed variants built along with the metadata JSON (`cache.precompress_*`)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MetadataEncodings {
+    pub brotli: bool,
+    pub gzip: bool,
+}
+
+/// A server version with its pre-serialized documents, stored and replaced as one value
+/// so readers never pair the metadata of one scan with the files of another
+pub struct CachedVersion {
+    pub version: Arc<VersionBuilder>,
+    /// None if serialization failed (the metadata routes then fall back to the version)
+    pub serialized: Option<SerializedVersion>,
+}
+
+/// Re-bases published file URLs from the storage origin onto a public CDN base
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlRewrite {
+    /// Storage URL prefix (`storage.get_url("")`)
+    pub origin: String,
+    /// Replacement prefix, ending with '/'
+    pub public_base: String,
+}
+
+impl UrlRewrite {
+    /// Builds a rewrite from `[cdn] public_base_url` (None if unset)
+    pub fn new(storage: &dyn lighty_storage::StorageBackend, public_base_url: &str) -> Option<Self> {
+        let public_base_url = public_base_url.trim_end_matches('/');
+        if public_base_url.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            origin: storage.get_url(""),
+            public_base: format!("{}/", public_base_url),
+        })
+    }
+
+    pub fn apply(&self, url: &str) -> String {
+        match url.strip_prefix(&self.origin) {
+            Some(rest) => format!("{}{}", self.public_base, rest),
+            None => url.to_string(),
+        }
+    }
+}
+
+/// Serializes a server version to JSON once, off the request path
+/// File URLs are re-based onto the public CDN base if configured
+pub fn serialize_version(
+    version: &VersionBuilder,
+    url_rewrite: Option<&UrlRewrite>,
+    encodings: MetadataEncodings,
+) -> Option<SerializedVersion> {
+    let rebased = url_rewrite.map(|rewrite| version.with_rebased_urls(&rewrite.origin, &rewrite.public_base));
+    let version = rebased.as_ref().unwrap_or(version);
+
+    let serialized = serde_json::to_vec(version).and_then(|metadata| {
+        Ok((
+            metadata,
+            serde_json::to_vec(&version.manifest())?,
+            serde_json::to_vec(&version.file_tree())?,
+        ))
+    });
+
+    match serialized {
+        Ok((metadata, manifest, tree)) => {
+            let asset_index = super::asset_pages::AssetIndex::build(&metadata, &version.assets).map(Arc::new);
+            if asset_index.is_none() {
+                tracing::warn!("Failed to index assets of the serialized metadata, pages will be built on request");
+            }
+            let metadata_brotli = encodings.brotli
+                .then(|| super::precompress::brotli(&metadata))
+                .flatten()
+                .map(Arc::new);
+            let metadata_gzip = encodings.gzip
+                .then(|| super::precompress::gzip(&metadata))
+                .flatten()
+                .map(Arc::new);
+            Some(SerializedVersion {
+                metadata_brotli,
+                metadata_gzip,
+                metadata: Arc::new(Bytes::from(metadata)),
+                manifest: Arc::new(Bytes::from(manifest)),
+                tree: Arc::new(Bytes::from(tree)),
+                asset_index,
+            })
+        }
+        Err(e) => {
+            tracing::warn!("Failed to pre-serialize server metadata: {}", e);
+            None
+        }
+    }
+}
+
 /// Trait for updating the version cache (allows decoupling from internal implementation)
+/// Versions are immutable once published: an update builds a complete new `VersionBuilder` and
+/// replaces the entry in a single insert, never mutating the one readers may hold
 pub trait CacheUpdater: Send + Sync {
-    /// Insert or update a server version in the cache
-    fn insert(&self, server_name: String, version: Arc<VersionBuilder>);
+    /// Insert or update a server version in the cache (serializes its metadata JSON)
+    fn insert(&self, server_name: String, version: Arc<VersionBuilder>) {
+        let json = serialize_version(&version, self.url_rewrite().as_ref(), self.metadata_encodings());
+        self.insert_serialized(server_name, version, json);
+    }
+
+    /// Insert or update a server version along with its already serialized JSON, atomically
+    /// A `None` JSON invalidates any previously serialized metadata
+    fn insert_serialized(&self, server_name: String, version: Arc<VersionBuilder>, json: Option<SerializedVersion>);
 
     /// Get a server version from the cache
     fn get(&self, server_name: &str) -> Option<Arc<VersionBuilder>>;
 
     /// Check if a server exists in the cache
     fn contains(&self, server_name: &str) -> bool;
+
+    /// Check if a server was evicted from a bounded cache (rescanned on next access)
+    fn is_evicted(&self, _server_name: &str) -> bool {
+        false
+    }
+
+    /// Public URL rewrite applied when serializing metadata
+    fn url_rewrite(&self) -> Option<UrlRewrite> {
+        None
+    }
+
+    /// Compressed variants built along with serialized metadata
+    fn metadata_encodings(&self) -> MetadataEncodings {
+        MetadataEncodings::default()
+    }
 }
 
-/// Simple cache store wrapper (implements CacheUpdater for DashMap)
+/// Version cache store (implements CacheUpdater over a Moka LRU cache)
+/// Bounded by `max_cached_versions`; evicted servers are rescanned lazily on next access
 pub struct CacheStore {
-    cache: Arc<DashMap<String, Arc<VersionBuilder>>>,
+    pub(super) versions: moka::sync::Cache<String, Arc<CachedVersion>>,
+    /// Servers evicted for being cold, waiting for a lazy rescan
+    pub(super) evicted: Arc<DashMap<String, ()>>,
+    /// Servers removed from the config, versions of scans still in flight are dropped until re-added
+    pub(super) removed: DashMap<String, ()>,
+    pub(super) url_rewrite: parking_lot::RwLock<Option<UrlRewrite>>,
+    pub(super) encodings: MetadataEncodings,
 }
 
 impl CacheStore {
-    pub fn new() -> (Self, Arc<DashMap<String, Arc<VersionBuilder>>>) {
-        let cache = Arc::new(DashMap::new());
-        let store = Self {
-            cache: Arc::clone(&cache),
-        };
-        (store, cache)
+    /// Creates a version cache holding at most `max_cached_versions` servers (0 = unlimited)
+    pub fn new(max_cached_versions: u64, url_rewrite: Option<UrlRewrite>, encodings: MetadataEncodings) -> Self {
+        let evicted = Arc::new(DashMap::new());
+
+        let mut builder = moka::sync::Cache::builder()
+            .eviction_policy(moka::policy::EvictionPolicy::lru());
+        if max_cached_versions > 0 {
+            builder = builder.max_capacity(max_cached_versions);
+        }
+
+        let listener_evicted = Arc::clone(&evicted);
+        let versions = builder
+            .eviction_listener(move |server_name: Arc<String>, _, cause: moka::notification::RemovalCause| {
+                if cause.was_evicted() {
+                    listener_evicted.insert(server_name.to_string(), ());
+                    tracing::debug!("Evicted cold server {} from version cache", server_name);
+                }
+            })
+            .build();
+
+        Self {
+            versions,
+            evicted,
+            removed: DashMap::new(),
+            url_rewrite: parking_lot::RwLock::new(url_rewrite),
+            encodings,
+        }
+    }
+
+    /// Replaces the public URL rewrite and re-serializes all cached versions if it changed
+    pub fn set_url_rewrite(&self, url_rewrite: Option<UrlRewrite>) -> bool {
+        {
+            let mut current = self.url_rewrite.write();
+            if *current == url_rewrite {
+                return false;
+            }
+            *current = url_rewrite.clone();
+        }
+
+        for (server_name, entry) in self.versions.iter() {
+            let serialized = serialize_version(&entry.version, url_rewrite.as_ref(), self.encodings);
+            let replacement = Arc::new(CachedVersion {
+                version: Arc::clone(&entry.version),
+                serialized,
+            });
+            // Skipped if a rescan published a newer version meanwhile (already serialized with the new rewrite)
+            self.versions
+                .entry_by_ref(server_name.as_str())
+                .and_compute_with(|current| match current {
+                    Some(current) if Arc::ptr_eq(&current.value().version, &entry.version) => {
+                        moka::ops::compute::Op::Put(replacement)
+                    }
+                    _ => moka::ops::compute::Op::Nop,
+                });
+        }
+
+        true
+    }
+
+    /// Reads from the pre-serialized documents of a server's current version
+    fn with_serialized<T>(&self, server_name: &str, read: impl FnOnce(&SerializedVersion) -> Option<T>) -> Option<T> {
+        read(self.versions.get(server_name)?.serialized.as_ref()?)
+    }
+
+    /// Returns the pre-serialized metadata JSON for a server
+    pub fn get_metadata_json(&self, server_name: &str) -> Option<Arc<Bytes>> {
+        self.with_serialized(server_name, |json| Some(Arc::clone(&json.metadata)))
+    }
+
+    /// Returns the brotli-compressed metadata JSON for a server, if pre-compressed
+    pub fn get_metadata_brotli(&self, server_name: &str) -> Option<Arc<Bytes>> {
+        self.with_serialized(server_name, |json| json.metadata_brotli.clone())
+    }
+
+    /// Returns the gzip-compressed metadata JSON for a server, if pre-compressed
+    pub fn get_metadata_gzip(&self, server_name: &str) -> Option<Arc<Bytes>> {
+        self.with_serialized(server_name, |json| json.metadata_gzip.clone())
+    }
+
+    /// Returns the pre-serialized flat manifest JSON for a server
+    pub fn get_manifest_json(&self, server_name: &str) -> Option<Arc<Bytes>> {
+        self.with_serialized(server_name, |json| Some(Arc::clone(&json.manifest)))
+    }
+
+    /// Returns the pre-serialized file tree JSON for a server
+    pub fn get_tree_json(&self, server_name: &str) -> Option<Arc<Bytes>> {
+        self.with_serialized(server_name, |json| Some(Arc::clone(&json.tree)))
+    }
+
+    /// Drops a server's version and serialized metadata (server removed from the config)
+    /// Later inserts are ignored until `restore`, so an in-flight scan can't bring the server back
+    pub fn remove(&self, server_name: &str) {
+        self.removed.insert(server_name.to_string(), ());
+        self.versions.invalidate(server_name);
+        self.evicted.remove(server_name);
+    }
+
+    /// Accepts versions of a server again (server added back to the config)
+    pub fn restore(&self, server_name: &str) {
+        self.removed.remove(server_name);
+    }
+
+    /// Builds a page of the metadata JSON from the pre-serialized document
+    pub fn get_metadata_page(&self, server_name: &str, page: usize, per_page: usize) -> Option<Bytes> {
+        self.with_serialized(server_name, |json| Some(json.asset_index.as_ref()?.page(&json.metadata, page, per_page)))
     }
 }
 
 impl CacheUpdater for CacheStore {
-    fn insert(&self, server_name: String, version: Arc<VersionBuilder>) {
-        self.cache.insert(server_name, version);
+    fn insert_serialized(&self, server_name: String, version: Arc<VersionBuilder>, json: Option<SerializedVersion>) {
+        if self.removed.contains_key(&server_name) {
+            tracing::debug!("Dropping version of removed server {}", server_name);
+            return;
+        }
+        self.evicted.remove(&server_name);
+        self.versions.insert(server_name.clone(), Arc::new(CachedVersion { version, serialized: json }));
+
+        // Removed while inserting: `remove` may have run before this insert landed
+        if self.removed.contains_key(&server_name) {
+            self.versions.invalidate(&server_name);
+        }
     }
 
     fn get(&self, server_name: &str) -> Option<Arc<VersionBuilder>> {
-        self.cache.get(server_name).map(|entry| Arc::clone(entry.value()))
+        self.versions.get(server_name).map(|entry| Arc::clone(&entry.version))
     }
 
     fn contains(&self, server_name: &str) -> bool {
-        self.cache.contains_key(server_name)
+        self.versions.contains_key(server_name)
+    }
+
+    fn is_evicted(&self, server_name: &str) -> bool {
+        self.evicted.contains_key(server_name)
+    }
+
+    fn url_rewrite(&self) -> Option<UrlRewrite> {
+        self.url_rewrite.read().clone()
+    }
+
+    fn metadata_encodings(&self) -> MetadataEncodings {
+        self.encodings
     }
 }
 
 /// Main cache manager coordinating all caching operations
 pub struct CacheManager {
-    pub(super) cache: Arc<DashMap<String, Arc<VersionBuilder>>>,
+    pub(super) cache: Arc<CacheStore>,
+    /// Per-server locks so concurrent misses on an evicted server trigger a single rescan
+    pub(super) lazy_loads: Arc<DashMap<String, Arc<tokio::sync::Mutex<()>>>>,
     pub(super) file_cache_manager: Arc<FileCacheManager>,
     pub(super) last_updated: Arc<DashMap<String, String>>,
     pub(super) rescan_orchestrator: Arc<RescanOrchestrator>,
@@ -60,18 +326,60 @@ pub struct CacheManager {
     pub config: Arc<RwLock<Config>>,
     pub(super) events: Arc<EventBus>,
     pub(super) shutdown_tx: broadcast::Sender<()>,
-    pub(super) tasks: Arc<DashMap<usize, JoinHandle<()>>>,
+    pub(super) tasks: Arc<DashMap<usize, TrackedTask>>,
     pub(super) task_counter: Arc<std::sync::atomic::AtomicUsize>,
 }
 
+/// A spawned background task with a label for `/admin/tasks`
+pub(super) struct TrackedTask {
+    pub(super) label: String,
+    pub(super) started_at: String,
+    pub(super) handle: JoinHandle<()>,
+}
+
+impl TrackedTask {
+    pub(super) fn new(label: String, handle: JoinHandle<()>) -> Self {
+        Self {
+            label,
+            started_at: chrono::Utc::now().to_rfc3339(),
+            handle,
+        }
+    }
+
+    pub(super) fn status(&self, id: usize) -> BackgroundTask {
+        BackgroundTask {
+            id,
+            label: self.label.clone(),
+            started_at: self.started_at.clone(),
+            running: !self.handle.is_finished(),
+        }
+    }
+}
+
+/// A background task as reported by `/admin/tasks`
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BackgroundTask {
+    pub id: usize,
+    pub label: String,
+    pub started_at: String,
+    /// False once the task ended (e.g. a rescan loop that stopped)
+    pub running: bool,
+}
+
 /// Manages file caching using Moka LRU cache
 pub struct FileCacheManager {
     pub(super) cache: Cache<Arc<str>, FileCache>,
+    /// Files above this size are never loaded into RAM (0 = no limit)
+    pub(super) max_file_size_bytes: u64,
+    /// Keep a brotli variant of text files next to the raw data
+    pub(super) precompress_brotli: bool,
     #[allow(dead_code)]
     pub(super) shutdown_tx: broadcast::Sender<()>,
     pub(super) tasks: Arc<DashMap<usize, JoinHandle<()>>>,
     #[allow(dead_code)]
     pub(super) task_counter: Arc<std::sync::atomic::AtomicUsize>,
+    /// Bumped by every invalidation, lazy loads started before it are not kept
+    pub(super) generation: Arc<std::sync::atomic::AtomicU64>,
 }
 
 /// Represents a cached file with its data and metadata
@@ -81,11 +389,63 @@ pub struct FileCache {
     pub sha1: String,
     pub size: u64,
     pub mime_type: String,
+    /// Brotli variant, served to clients accepting `br` (`cache.precompress_brotli`)
+    pub brotli: Option<Bytes>,
+    /// Modification time of the file when it was cached, sent as `Last-Modified`
+    pub modified: Option<std::time::SystemTime>,
 }
 
 /// Detects changes between two VersionBuilder instances
 pub struct ChangeDetector;
 
+/// Outcome of a full re-upload of a server to remote storage
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ReuploadSummary {
+    pub server: String,
+    pub uploaded: usize,
+    pub failed: usize,
+}
+
+/// Outcome of a manual edge cache purge of a server
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PurgeSummary {
+    pub server: String,
+    /// Metadata JSON purged through `[cloudflare]` (false if not configured)
+    pub metadata_purged: bool,
+    /// File URLs purged through `[cdn]` (0 if not configured)
+    pub files_purged: usize,
+    pub errors: Vec<String>,
+}
+
+/// Freeze state of a server, reported by the freeze/promote admin endpoints
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FreezeStatus {
+    pub server: String,
+    /// ISO 8601 timestamp (RFC 3339)
+    pub frozen_since: String,
+    /// Changes found by the last rescan while frozen, published on promote
+    pub pending_added: usize,
+    pub pending_modified: usize,
+    pub pending_removed: usize,
+}
+
+/// Maintenance mode state, reported by `/admin/maintenance`
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct MaintenanceStatus {
+    pub enabled: bool,
+    pub reason: Option<String>,
+    /// ISO 8601 timestamp (RFC 3339)
+    pub since: Option<String>,
+}
+
+/// A server pinned to the version it had when frozen
+pub(super) struct FrozenServer {
+    pub(super) version: Arc<VersionBuilder>,
+    pub(super) status: FreezeStatus,
+    /// Pinned files changed or removed on disk since the freeze, their local copy is not the pinned one
+    pub(super) staged: HashSet<String>,
+}
+
 /// Orchestrates automatic and manual server rescanning
 pub struct RescanOrchestrator {
     pub(super) cache: Arc<dyn CacheUpdater>,
@@ -98,4 +458,125 @@ pub struct RescanOrchestrator {
     pub(super) cloudflare: Option<Arc<super::cloudflare::CloudflareClient>>,
     pub(super) base_path: std::path::PathBuf,
     pub(super) server_path_cache: Arc<super::server_path_cache::ServerPathCache>,
+    /// Servers with a cloud sync in flight, with changes queued for the follow-up sync
+    pub(super) in_flight_syncs: Arc<DashMap<String, super::pending_sync::PendingSync>>,
+    /// Folder fingerprints from the last polling rescan (unchanged => scan skipped)
+    pub(super) fingerprints: Arc<DashMap<String, super::fingerprint::DirFingerprint>>,
+    /// Zero-byte files found by the last scan of each server, reported only when first seen
+    pub(super) empty_files: Arc<DashMap<String, HashSet<std::path::PathBuf>>>,
+    /// Signals the rescan loop that the config was reloaded (watched folders, rescan interval)
+    pub(super) config_reloaded: tokio::sync::watch::Sender<()>,
+    /// Recent scan durations per server
+    pub(super) scan_stats: Arc<super::scan_stats::ScanStats>,
+    /// Servers whose published version is pinned until promoted
+    pub(super) frozen: Arc<DashMap<String, FrozenServer>>,
+    /// Set while in maintenance mode (rescan loop paused, no uploads)
+    pub(super) maintenance: std::sync::RwLock<Option<MaintenanceStatus>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn version(assets: Value) -> VersionBuilder {
+        let mut version: VersionBuilder = serde_json::from_value(json!({
+            "main_class": { "main_class": "net.minecraft.client.main.Main" },
+            "java_version": { "major_version": 21 },
+            "arguments": { "game": [], "jvm": [] },
+            "libraries": [],
+            "mods": [],
+            "natives": [],
+            "extra_files": [],
+            "scan_warnings": ["mods/broken.jar: I/O error"],
+            "assets": assets
+        }))
+        .unwrap();
+        version.build_url_map();
+        version
+    }
+
+    fn asset(index: usize) -> Value {
+        let hash = format!("{:040x}", index);
+        let mut asset = json!({
+            "hash": hash,
+            "size": index * 7,
+            "url": format!("http://localhost:8080/survival/assets/{}/{}", &hash[..2], hash),
+            // Escaped characters must not shift the byte ranges of the next assets
+            "path": format!("{}/sounds \"é\"\\{}", &hash[..2], index)
+        });
+        if index.is_multiple_of(2) {
+            asset["crc32"] = json!(format!("{:08x}", index));
+        }
+        if index.is_multiple_of(3) {
+            asset["mtime"] = json!(1_700_000_000 + index as u64);
+        }
+        asset
+    }
+
+    #[test]
+    fn metadata_pages_match_full_version() {
+        for count in [0, 1, 7] {
+            let version = Arc::new(version(Value::Array((0..count).map(asset).collect())));
+            let full = serde_json::to_value(version.as_ref()).unwrap();
+            let store = CacheStore::new(0, None, MetadataEncodings::default());
+            store.insert("survival".to_string(), Arc::clone(&version));
+
+            for per_page in [1, 2, 3, 7, 10] {
+                for page in 1..=count.div_ceil(per_page).max(1) + 1 {
+                    let body = store.get_metadata_page("survival", page, per_page).unwrap();
+                    let mut paged: Value = serde_json::from_slice(&body).unwrap();
+
+                    let first = ((page - 1) * per_page).min(count);
+                    let last = (first + per_page).min(count);
+                    let info = paged.as_object_mut().unwrap().remove("assets_page").unwrap();
+                    assert_eq!(
+                        info,
+                        json!({
+                            "page": page,
+                            "per_page": per_page,
+                            "total": count,
+                            "next_page": (last < count).then_some(page + 1)
+                        })
+                    );
+
+                    let mut expected = full.clone();
+                    expected["assets"] = Value::Array(full["assets"].as_array().unwrap()[first..last].to_vec());
+                    assert_eq!(paged, expected, "{} asset(s), page {} of {}", count, page, per_page);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn readers_never_see_a_torn_version() {
+        let store = Arc::new(CacheStore::new(0, None, MetadataEncodings::default()));
+        store.insert("survival".to_string(), Arc::new(version(json!([]))));
+        let done = Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let store = Arc::clone(&store);
+                let done = Arc::clone(&done);
+                std::thread::spawn(move || {
+                    let mut reads = 0;
+                    while !done.load(std::sync::atomic::Ordering::Relaxed) || reads == 0 {
+                        let entry = store.versions.get("survival").unwrap();
+                        let json = entry.serialized.as_ref().unwrap();
+                        assert_eq!(json.metadata.as_ref(), serde_json::to_vec(entry.version.as_ref()).unwrap().as_slice());
+                        assert_eq!(json.manifest.as_ref(), serde_json::to_vec(&entry.version.manifest()).unwrap().as_slice());
+                        reads += 1;
+                    }
+                })
+            })
+            .collect();
+
+        for count in 1..=200 {
+            store.insert("survival".to_string(), Arc::new(version(Value::Array((0..count % 9).map(asset).collect()))));
+        }
+        done.store(true, std::sync::atomic::Ordering::Relaxed);
+        for reader in readers {
+            reader.join().unwrap();
+        }
+    }
 }
@@ -0,0 +1,44 @@
+use super::file_diff::{FileChange, FileDiff};
+use std::collections::{HashMap, HashSet};
+
+/// Cloud storage operations for one server, coalesced by remote key
+/// Used to queue changes that arrive while a sync is already in flight
+#[derive(Debug, Default)]
+pub struct PendingSync {
+    /// remote_key -> latest change (local_path is relative to base_path)
+    pub uploads: HashMap<String, FileChange>,
+    /// Uploads of files new to the server (the first sync lists every file as added)
+    /// Only these may already be in remote storage, a modified file never matches its stored copy
+    pub added: HashSet<String>,
+    pub deletes: HashSet<String>,
+}
+
+impl PendingSync {
+    pub fn from_diff(diff: &FileDiff) -> Self {
+        let mut pending = Self::default();
+        pending.merge(diff);
+        pending
+    }
+
+    /// Merges a newer diff; the latest operation on a remote key wins
+    pub fn merge(&mut self, diff: &FileDiff) {
+        for change in &diff.added {
+            self.added.insert(change.remote_key.clone());
+        }
+
+        for change in diff.added.iter().chain(diff.modified.iter()) {
+            self.deletes.remove(&change.remote_key);
+            self.uploads.insert(change.remote_key.clone(), change.clone());
+        }
+
+        for change in &diff.removed {
+            self.uploads.remove(&change.remote_key);
+            self.added.remove(&change.remote_key);
+            self.deletes.insert(change.remote_key.clone());
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.uploads.is_empty() && self.deletes.is_empty()
+    }
+}
@@ -0,0 +1,35 @@
+use bytes::Bytes;
+use std::io::Write;
+
+/// Slower than the on-the-fly compression level, but paid once per file instead of per request
+const BROTLI_QUALITY: u32 = 9;
+const BROTLI_WINDOW_BITS: u32 = 22;
+const GZIP_LEVEL: u32 = 9;
+
+/// Text formats worth pre-compressing (jars, images, sounds... are already compressed)
+pub fn is_compressible(mime_type: &str) -> bool {
+    let mime_type = mime_type.split(';').next().unwrap_or_default().trim();
+    mime_type.starts_with("text/")
+        || mime_type.ends_with("+json")
+        || mime_type.ends_with("+xml")
+        || matches!(
+            mime_type,
+            "application/json" | "application/xml" | "application/javascript" | "application/toml"
+        )
+}
+
+/// Brotli-compresses `data`, None if it fails or doesn't shrink it
+pub fn brotli(data: &[u8]) -> Option<Bytes> {
+    let mut writer = brotli::CompressorWriter::new(Vec::new(), 4096, BROTLI_QUALITY, BROTLI_WINDOW_BITS);
+    writer.write_all(data).ok()?;
+    let compressed = writer.into_inner();
+    (compressed.len() < data.len()).then(|| Bytes::from(compressed))
+}
+
+/// Gzip-compresses `data`, None if it fails or doesn't shrink it
+pub fn gzip(data: &[u8]) -> Option<Bytes> {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::new(GZIP_LEVEL));
+    encoder.write_all(data).ok()?;
+    let compressed = encoder.finish().ok()?;
+    (compressed.len() < data.len()).then(|| Bytes::from(compressed))
+}
@@ -1,9 +1,14 @@
-use super::RescanOrchestrator;
+use super::{RescanOrchestrator, ReuploadSummary, PurgeSummary, FreezeStatus, MaintenanceStatus};
+use super::models::FrozenServer;
 use super::errors::CacheError;
-use lighty_config::{Config, ServerConfig};
+use super::pending_sync::PendingSync;
+use super::file_diff::FileChange;
+use super::fingerprint::DirFingerprint;
+use lighty_config::{Config, FileCategory, ServerConfig, WatcherBackend};
 use lighty_events::{AppEvent, EventBus};
-use lighty_scanner::ServerScanner;
+use lighty_scanner::{ScanError, ScanFailure, ScanOptions, ServerScanner};
 use lighty_models::VersionBuilder;
+use lighty_storage::StorageBackend;
 use dashmap::DashMap;
 use notify::{Watcher, RecursiveMode, Event, EventKind};
 use std::path::PathBuf;
@@ -11,11 +16,14 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
-use tokio::time::{interval, Duration};
+use tokio::time::{Duration, Instant};
 use std::collections::{HashSet, HashMap};
 
 type Result<T> = std::result::Result<T, CacheError>;
 
+/// Lower bound on the polling loop sleep, so a past-due server can't spin the loop
+const MIN_POLL_SLEEP: Duration = Duration::from_millis(100);
+
 fn get_current_timestamp() -> String {
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -23,17 +31,110 @@ fn get_current_timestamp() -> String {
         .as_secs();
 
     let datetime = chrono::DateTime::from_timestamp(now as i64, 0)
-        .unwrap_or_else(|| chrono::Utc::now());
+        .unwrap_or_else(chrono::Utc::now);
     datetime.to_rfc3339()
 }
 
+/// Whether remote storage already holds `change`, by the SHA1 recorded at upload
+/// A failed lookup counts as a mismatch, so the file is uploaded
+async fn remote_matches(storage: &dyn StorageBackend, change: &FileChange) -> bool {
+    let Some(sha1) = &change.sha1 else {
+        return false;
+    };
+
+    storage
+        .remote_checksum(&change.remote_key)
+        .await
+        .map(|remote_sha1| remote_sha1.as_ref() == Some(sha1))
+        .unwrap_or_else(|e| {
+            tracing::debug!("Failed to look up {} in remote storage: {}", change.remote_key, e);
+            false
+        })
+}
+
+/// Spreads `interval` by up to ±`percent`% (capped at 100), so instances sharing a config poll at different moments
+fn jittered(interval: Duration, percent: u64, rng: &mut fastrand::Rng) -> Duration {
+    if percent == 0 {
+        return interval;
+    }
+    let spread = percent.min(100) as f64 / 100.0;
+    // In (-1, 1]: `f64()` can return 0 but never 1, so a 100% spread never yields a zero delay
+    interval.mul_f64(1.0 + spread * (1.0 - rng.f64() * 2.0))
+}
+
+/// In-flight cloud sync marker of a server, removed when the sync is dropped before finishing
+/// Without it a cancelled sync would leave the marker behind and queue every later sync forever
+struct InFlightSync<'a> {
+    syncs: &'a DashMap<String, PendingSync>,
+    server_name: &'a str,
+    /// Set once the sync loop removed the marker itself (it may already belong to a newer sync)
+    released: bool,
+}
+
+impl Drop for InFlightSync<'_> {
+    fn drop(&mut self) {
+        if self.released {
+            return;
+        }
+        if let Some((_, pending)) = self.syncs.remove(self.server_name) {
+            tracing::warn!(
+                "Cloud sync for {} cancelled before finishing ({} queued changes dropped), run /admin/reupload/{} to resync",
+                self.server_name,
+                pending.uploads.len() + pending.deletes.len(),
+                self.server_name
+            );
+        }
+    }
+}
+
+/// Categories kept off remote storage (`[storage] upload_* = false`)
+struct LocalCategories {
+    categories: Vec<FileCategory>,
+    /// `server.base_url` + '/', the URL prefix of local storage
+    base_url: String,
+}
+
+impl LocalCategories {
+    fn from_config(config: &Config) -> Self {
+        Self {
+            categories: config.storage.local_categories(),
+            base_url: format!("{}/", config.server.base_url),
+        }
+    }
+
+    /// Moves the URLs of these categories from the storage origin onto `server.base_url`, where this server
+    /// resolves them like with local storage. Returns whether URLs may have moved (lookup maps are then stale)
+    fn publish(&self, builder: &mut VersionBuilder, storage: &dyn StorageBackend) -> bool {
+        if self.categories.is_empty() || !storage.is_remote() {
+            return false;
+        }
+        builder.rebase_urls(&self.categories, &storage.get_url(""), &self.base_url);
+        true
+    }
+}
+
+/// Builds a watcher callback forwarding content changes to the rescan loop
+fn file_event_handler(
+    tx: tokio::sync::mpsc::UnboundedSender<Event>,
+) -> impl Fn(std::result::Result<Event, notify::Error>) + Send + 'static {
+    move |res| {
+        if let Ok(event) = res {
+            // Only trigger on actual file modifications, not metadata changes
+            if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) {
+                let _ = tx.send(event);
+            }
+        }
+    }
+}
+
 impl RescanOrchestrator {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         cache: Arc<dyn super::models::CacheUpdater>,
         last_updated: Arc<DashMap<String, String>>,
         config: Arc<RwLock<Config>>,
         events: Arc<EventBus>,
-        storage: Option<Arc<dyn lighty_storage::StorageBackend>>,
+        storage: Option<Arc<dyn StorageBackend>>,
         cdn: Option<Arc<super::cdn::CdnClient>>,
         cloudflare: Option<Arc<super::cloudflare::CloudflareClient>>,
         base_path: PathBuf,
@@ -50,9 +151,48 @@ impl RescanOrchestrator {
             cloudflare,
             base_path,
             server_path_cache,
+            in_flight_syncs: Arc::new(DashMap::new()),
+            fingerprints: Arc::new(DashMap::new()),
+            empty_files: Arc::new(DashMap::new()),
+            config_reloaded: tokio::sync::watch::Sender::new(()),
+            scan_stats: Arc::new(super::scan_stats::ScanStats::default()),
+            frozen: Arc::new(DashMap::new()),
+            maintenance: std::sync::RwLock::new(None),
+        }
+    }
+
+    /// Recent scan durations of the given servers, sorted by name
+    pub fn scan_durations(&self, mut is_configured: impl FnMut(&str) -> bool) -> Vec<super::scan_stats::ScanDurationStats> {
+        self.scan_stats.retain(&mut is_configured);
+        self.scan_stats.snapshot()
+    }
+
+    /// Drops the rescan state of a server removed from the config
+    pub fn forget_server(&self, server_name: &str) {
+        self.last_updated.remove(server_name);
+        self.fingerprints.remove(server_name);
+        self.empty_files.remove(server_name);
+        self.frozen.remove(server_name);
+        self.scan_stats.retain(|name| name != server_name);
+    }
+
+    /// Records how long a scan took (postponed scans are left out, they stop early)
+    fn record_scan_duration<T>(&self, server_name: &str, result: &std::result::Result<T, ScanError>, elapsed: Duration) {
+        if !matches!(result, Err(ScanError::FileUnstable(_))) {
+            self.scan_stats.record(server_name, elapsed);
         }
     }
 
+    /// Whether a storage backend was provided
+    pub fn has_storage(&self) -> bool {
+        self.storage.is_some()
+    }
+
+    /// Storage backend files are published to
+    pub fn storage(&self) -> Option<Arc<dyn StorageBackend>> {
+        self.storage.clone()
+    }
+
     /// Pauses the rescan loop
     pub fn pause(&self) {
         self.paused.store(true, Ordering::SeqCst);
@@ -65,56 +205,198 @@ impl RescanOrchestrator {
         tracing::debug!("Rescan resumed");
     }
 
+    /// Enters maintenance mode: rescans paused, cloud syncs skipped, reads keep being served
+    /// Already in maintenance: only the reason is updated
+    pub fn enter_maintenance(&self, reason: String) -> MaintenanceStatus {
+        let mut maintenance = self.maintenance.write().unwrap_or_else(|e| e.into_inner());
+        let status = maintenance.get_or_insert_with(|| {
+            tracing::info!("Entering maintenance mode: {}", reason);
+            MaintenanceStatus {
+                enabled: true,
+                reason: None,
+                since: Some(get_current_timestamp()),
+            }
+        });
+        status.reason = Some(reason);
+        self.pause();
+        status.clone()
+    }
+
+    /// Leaves maintenance mode, rescans resume on their next tick
+    pub fn exit_maintenance(&self) -> MaintenanceStatus {
+        if self.maintenance.write().unwrap_or_else(|e| e.into_inner()).take().is_some() {
+            tracing::info!("Leaving maintenance mode");
+        }
+        self.resume();
+        MaintenanceStatus::default()
+    }
+
+    /// Current maintenance mode state
+    pub fn maintenance_status(&self) -> MaintenanceStatus {
+        self.maintenance
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+            .unwrap_or_default()
+    }
+
+    fn in_maintenance(&self) -> bool {
+        self.maintenance.read().unwrap_or_else(|e| e.into_inner()).is_some()
+    }
+
+    fn ensure_not_in_maintenance(&self, operation: &str) -> Result<()> {
+        if self.in_maintenance() {
+            return Err(CacheError::InMaintenance(operation.to_string()));
+        }
+        Ok(())
+    }
+
     /// Runs the continuous rescan loop
+    /// Switches between polling and file watcher modes when `rescan_interval` changes on config reload
     pub async fn run_rescan_loop(&self) {
-        let rescan_interval = {
-            let config_read = self.config.read().await;
-            config_read.cache.rescan_interval
-        };
+        let mut reloads = self.config_reloaded.subscribe();
 
-        if rescan_interval == 0 {
-            self.events.emit(AppEvent::ContinuousScanEnabled);
-            self.run_file_watcher_loop().await;
-        } else {
-            self.events.emit(AppEvent::AutoScanEnabled { interval: rescan_interval });
-            let mut interval = interval(Duration::from_secs(rescan_interval));
-            interval.tick().await;
+        loop {
+            let rescan_interval = {
+                let config_read = self.config.read().await;
+                config_read.cache.rescan_interval
+            };
 
-            loop {
-                interval.tick().await;
+            let mode = async {
+                if rescan_interval == 0 {
+                    self.events.emit(AppEvent::ContinuousScanEnabled);
+                    self.run_file_watcher_loop().await;
+                } else {
+                    self.events.emit(AppEvent::AutoScanEnabled { interval: rescan_interval });
+                    self.run_polling_loop(rescan_interval).await;
+                }
+                // Mode failed to start: still wait for the next interval change
+                std::future::pending::<()>().await
+            };
 
-                // Check if rescan is paused (e.g., during config reload)
-                // Relaxed ordering is sufficient for simple flag check
-                if self.paused.load(Ordering::Relaxed) {
-                    continue;
+            tokio::select! {
+                _ = mode => {}
+                _ = self.wait_for_interval_change(&mut reloads, rescan_interval) => {
+                    tracing::info!("Rescan interval changed, restarting rescan loop");
                 }
+            }
+        }
+    }
 
-                let (servers, base_path) = {
-                    let config_read = self.config.read().await;
-                    (
-                        config_read.servers.clone(),
-                        config_read.server.base_path.clone(),
-                    )
+    /// Resolves once a config reload changes `rescan_interval` away from `current`
+    async fn wait_for_interval_change(&self, reloads: &mut tokio::sync::watch::Receiver<()>, current: u64) {
+        while reloads.changed().await.is_ok() {
+            let rescan_interval = self.config.read().await.cache.rescan_interval;
+            if rescan_interval != current {
+                return;
+            }
+        }
+        std::future::pending::<()>().await
+    }
+
+    /// Rescans each server on its own schedule, skipping folders that look untouched
+    /// A server's `rescan_interval` overrides the global one (0 = never polled)
+    async fn run_polling_loop(&self, rescan_interval: u64) {
+        let global_interval = Duration::from_secs(rescan_interval);
+        let mut next_due: HashMap<String, Instant> = HashMap::new();
+        // Seeded from entropy, so every instance draws its own delays
+        let mut rng = fastrand::Rng::new();
+
+        loop {
+            let (servers, base_path, jitter_percent) = {
+                let config_read = self.config.read().await;
+                (
+                    config_read.servers.clone(),
+                    config_read.server.base_path.clone(),
+                    config_read.cache.rescan_jitter_percent,
+                )
+            };
+
+            // Check if rescan is paused (e.g., during config reload)
+            // Relaxed ordering is sufficient for simple flag check
+            let paused = self.paused.load(Ordering::Relaxed);
+            let now = Instant::now();
+
+            // Forget removed servers and servers no longer polled
+            next_due.retain(|name, _| {
+                servers
+                    .iter()
+                    .any(|server| server.name.as_ref() == name && server.rescan_interval != Some(0))
+            });
+
+            for server_config in &servers {
+                let server_interval = match server_config.rescan_interval {
+                    Some(0) => continue,
+                    Some(secs) => Duration::from_secs(secs),
+                    None => global_interval,
                 };
 
-                for server_config in &servers {
-                    if !server_config.enabled {
-                        continue;
-                    }
-                    self.rescan_server(server_config, base_path.as_ref()).await;
+                // First scan one interval after start (the initial scan just ran)
+                let due = next_due
+                    .entry(server_config.name.to_string())
+                    .or_insert_with(|| now + jittered(server_interval, jitter_percent, &mut rng));
+                if *due > now || paused {
+                    continue;
                 }
+                *due = now + jittered(server_interval, jitter_percent, &mut rng);
+
+                // Evicted servers are rescanned lazily on their next access
+                if !server_config.enabled || self.cache.is_evicted(&server_config.name) {
+                    continue;
+                }
+
+                self.poll_server(server_config, base_path.as_ref()).await;
+            }
+
+            // Sleep until the next server is due, waking at least every global interval for config changes
+            let wake = next_due
+                .values()
+                .copied()
+                .min()
+                .map_or(now + global_interval, |due| due.min(now + global_interval));
+            tokio::time::sleep_until(wake.max(Instant::now() + MIN_POLL_SLEEP)).await;
+        }
+    }
+
+    /// Rescans a server if its folder fingerprint changed since the last poll
+    async fn poll_server(&self, server_config: &ServerConfig, base_path: &str) {
+        // Skip the expensive re-hash when the folder looks untouched
+        let server_path = self.base_path.join(server_config.name.as_ref());
+        let fingerprint = match tokio::task::spawn_blocking(move || DirFingerprint::compute(&server_path)).await {
+            Ok(fingerprint) => fingerprint,
+            Err(e) => {
+                tracing::warn!("Failed to fingerprint server {}: {}", server_config.name, e);
+                self.rescan_server(server_config, base_path).await;
+                return;
             }
+        };
+
+        let unchanged = self
+            .fingerprints
+            .get(server_config.name.as_ref())
+            .is_some_and(|previous| *previous == fingerprint);
+        if unchanged {
+            tracing::trace!("Server {} unchanged since last scan, skipping", server_config.name);
+            return;
+        }
+
+        // A failed rescan keeps the old fingerprint, so the next poll retries it
+        if self.rescan_server(server_config, base_path).await {
+            self.fingerprints.insert(server_config.name.to_string(), fingerprint);
         }
     }
 
     /// Runs file watcher loop for continuous monitoring (event-driven instead of polling)
     async fn run_file_watcher_loop(&self) {
         // Check if file watcher is enabled
-        let (enabled, debounce_ms) = {
+        let (enabled, debounce_ms, poll_interval_ms, backend, watch_base_path) = {
             let config = self.config.read().await;
             (
                 config.hot_reload.files.enabled,
                 config.hot_reload.files.debounce_ms,
+                config.hot_reload.files.poll_interval_ms,
+                config.hot_reload.files.watcher,
+                config.hot_reload.files.watch_base_path,
             )
         };
 
@@ -127,39 +409,54 @@ impl RescanOrchestrator {
 
         let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
 
-        // Setup file watcher
-        let mut watcher = match notify::recommended_watcher(move |res: std::result::Result<Event, notify::Error>| {
-            if let Ok(event) = res {
-                // Only trigger on actual file modifications, not metadata changes
-                if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) {
-                    let _ = tx.send(event);
-                }
+        let poll_config = notify::Config::default()
+            .with_poll_interval(Duration::from_millis(poll_interval_ms));
+
+        // Setup file watcher (native backend first with polling fallback, or polling forced for NFS/SMB mounts)
+        let watcher: notify::Result<Box<dyn Watcher + Send>> = match backend {
+            WatcherBackend::Poll => {
+                tracing::info!("Using polling file watcher ({}ms)", poll_interval_ms);
+                notify::PollWatcher::new(file_event_handler(tx), poll_config).map(|w| Box::new(w) as _)
             }
-        }) {
-            Ok(w) => w,
+            WatcherBackend::Native => match notify::recommended_watcher(file_event_handler(tx.clone())) {
+                Ok(w) => Ok(Box::new(w) as _),
+                Err(e) => {
+                    self.events.emit(AppEvent::FileWatcherDegraded {
+                        error: e.to_string(),
+                        poll_interval_ms,
+                    });
+                    notify::PollWatcher::new(file_event_handler(tx), poll_config).map(|w| Box::new(w) as _)
+                }
+            },
+        };
+        let mut watcher = match watcher {
+            Ok(watcher) => watcher,
             Err(e) => {
-                tracing::error!("Failed to create file watcher: {}", e);
+                tracing::error!("Failed to create polling file watcher: {}", e);
                 return;
             }
         };
 
-        // Watch all server directories
-        let (servers, base_path) = {
-            let config_read = self.config.read().await;
-            (config_read.servers.clone(), config_read.server.base_path.clone())
-        };
+        // Watch all enabled server directories (re-evaluated on config reload)
+        let mut reloads = self.config_reloaded.subscribe();
+        let mut watched: HashMap<String, PathBuf> = HashMap::new();
+        self.sync_watched_servers(watcher.as_mut(), &mut watched).await;
 
-        for server in &servers {
-            if !server.enabled {
-                continue;
-            }
-            let server_path = PathBuf::from(base_path.as_ref()).join(server.name.as_ref());
-            if server_path.exists() {
-                if let Err(e) = watcher.watch(&server_path, RecursiveMode::Recursive) {
-                    tracing::warn!("Failed to watch server folder {}: {}", server.name, e);
+        // Optionally watch base_path itself (top level only) to detect new server folders
+        let base_root = if watch_base_path {
+            match watcher.watch(&self.base_path, RecursiveMode::NonRecursive) {
+                Ok(()) => {
+                    tracing::debug!("Watching base path for new server folders: {}", self.base_path.display());
+                    Some(self.base_path.clone())
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to watch base path {}: {}", self.base_path.display(), e);
+                    None
                 }
             }
-        }
+        } else {
+            None
+        };
 
         // Debounce settings: wait after last event before rescanning
         let debounce_duration = Duration::from_millis(debounce_ms);
@@ -176,12 +473,25 @@ impl RescanOrchestrator {
                     }
 
                     // Determine which server(s) are affected using O(1) cache lookup
+                    let created = matches!(event.kind, EventKind::Create(_));
+                    let mut new_folders = Vec::new();
                     for path in event.paths {
+                        let is_top_level = base_root.as_deref().is_some_and(|root| path.parent() == Some(root));
+                        if created && is_top_level && path.is_dir() {
+                            if let Some(name) = path.file_name() {
+                                new_folders.push(name.to_string_lossy().into_owned());
+                            }
+                        }
                         if let Some(server_name) = self.server_path_cache.find_server(&path) {
                             pending_servers.insert(server_name);
                         }
                     }
 
+                    if !new_folders.is_empty() {
+                        self.handle_new_base_folders(new_folders, &mut pending_servers).await;
+                        self.sync_watched_servers(watcher.as_mut(), &mut watched).await;
+                    }
+
                     // Reset debounce timer
                     debounce_timer = Some(tokio::time::Instant::now() + debounce_duration);
                 }
@@ -218,31 +528,142 @@ impl RescanOrchestrator {
 
                     debounce_timer = None;
                 }
+
+                // Config reloaded: servers may have been added, removed or toggled
+                Ok(()) = reloads.changed() => {
+                    self.sync_watched_servers(watcher.as_mut(), &mut watched).await;
+                }
+            }
+        }
+    }
+
+    /// Queues a scan for new top-level folders matching an enabled server, logs the others
+    async fn handle_new_base_folders(&self, folders: Vec<String>, pending_servers: &mut HashSet<String>) {
+        let config = self.config.read().await;
+        for folder in folders {
+            match config.servers.iter().find(|server| server.name.as_ref() == folder.as_str()) {
+                Some(server) if server.enabled => {
+                    tracing::info!("Server folder created for configured server {}, scanning", folder);
+                    pending_servers.insert(folder);
+                }
+                Some(_) => tracing::debug!("Server folder created for disabled server {}, ignoring", folder),
+                None => tracing::info!("Unconfigured folder created in base path: {}", folder),
             }
         }
     }
 
+    /// Watches the folders of enabled servers and unwatches disabled or removed ones
+    async fn sync_watched_servers(&self, watcher: &mut (dyn Watcher + Send), watched: &mut HashMap<String, PathBuf>) {
+        let desired: HashMap<String, PathBuf> = {
+            let config_read = self.config.read().await;
+            config_read
+                .servers
+                .iter()
+                .filter(|server| server.enabled)
+                .map(|server| {
+                    let path = PathBuf::from(config_read.server.base_path.as_ref()).join(server.name.as_ref());
+                    (server.name.to_string(), path)
+                })
+                .collect()
+        };
+
+        watched.retain(|name, path| {
+            if desired.get(name) == Some(path) {
+                return true;
+            }
+            if let Err(e) = watcher.unwatch(path) {
+                tracing::debug!("Failed to unwatch server folder {}: {}", name, e);
+            }
+            tracing::debug!("Stopped watching server folder: {}", name);
+            false
+        });
+
+        for (name, path) in desired {
+            if watched.contains_key(&name) || !path.exists() {
+                continue;
+            }
+            match watcher.watch(&path, RecursiveMode::Recursive) {
+                Ok(()) => {
+                    tracing::debug!("Watching server folder: {}", name);
+                    watched.insert(name, path);
+                }
+                Err(e) => tracing::warn!("Failed to watch server folder {}: {}", name, e),
+            }
+        }
+    }
+
+    /// Makes the rescan loop re-read its settings (call after config reload)
+    pub fn notify_config_reloaded(&self) {
+        self.config_reloaded.send_replace(());
+    }
+
     /// Rescans a single server and updates cache if changed
+    /// Returns whether the scan completed (false = previous version kept, retry later)
     async fn rescan_server(
         &self,
         server_config: &ServerConfig,
         base_path: &str,
-    ) {
-        let (batch_config, buffer_size) = {
+    ) -> bool {
+        let (batch_config, scan_options, loader_templates, local_categories) = {
             let config = self.config.read().await;
-            (config.cache.batch.clone(), config.cache.checksum_buffer_size)
+            (
+                config.cache.batch.clone(),
+                ScanOptions::from(&config.cache),
+                config.loader_templates.clone(),
+                LocalCategories::from_config(&config),
+            )
         };
 
-        if let Some(storage) = &self.storage {
-            match ServerScanner::scan_server_silent(server_config, storage, base_path, &batch_config, buffer_size).await {
-                Ok(builder) => {
-                    self.update_cache_if_changed(server_config, builder).await;
-                }
-                Err(_) => {
-                    // Silent error - server may be incomplete or removed
+        let Some(storage) = &self.storage else {
+            tracing::warn!("Skipping rescan of {}: storage backend not initialized", server_config.name);
+            return false;
+        };
+
+        let start = Instant::now();
+        let result = ServerScanner::scan_server_silent(
+            server_config,
+            storage,
+            base_path,
+            &batch_config,
+            &scan_options,
+            &loader_templates,
+        )
+        .await;
+        self.record_scan_duration(&server_config.name, &result, start.elapsed());
+
+        match result {
+            Ok(scan) => {
+                self.report_scan_failures(&server_config.name, &scan.failures, &scan.empty_files);
+                let mut builder = scan.builder;
+                if local_categories.publish(&mut builder, storage.as_ref()) {
+                    builder.build_url_map();
                 }
+                self.update_cache_if_changed(server_config, builder).await;
+                return true;
+            }
+            Err(e @ ScanError::FileFailed { .. }) => {
+                // Strict scan: keep the previous version rather than an incomplete one
+                self.events.emit(AppEvent::Error {
+                    context: format!("Strict scan of server {} failed", server_config.name),
+                    error: e.to_string(),
+                });
+            }
+            Err(ScanError::FileUnstable(path)) => {
+                // Keep the previous version, the end of the write triggers another rescan
+                tracing::info!("Postponing rescan of {}, file still being written: {}", server_config.name, path);
+            }
+            Err(e @ (ScanError::Timeout { .. } | ScanError::TooManyFiles { .. })) => {
+                // Keep the previous version, retried on the next rescan
+                self.events.emit(AppEvent::Error {
+                    context: format!("Rescan of server {}", server_config.name),
+                    error: e.to_string(),
+                });
+            }
+            Err(_) => {
+                // Silent error - server may be incomplete or removed
             }
         }
+        false
     }
 
     /// Updates cache if changes are detected
@@ -263,12 +684,34 @@ impl RescanOrchestrator {
         let has_changes = !diff.added.is_empty()
             || !diff.modified.is_empty()
             || !diff.removed.is_empty();
+        // e.g. a new file that can't be read: no file changed, but launchers should learn about it
+        let warnings_changed = old_builder
+            .as_ref()
+            .is_some_and(|old| old.scan_warnings != new_builder.scan_warnings);
+
+        // Frozen: keep serving the pinned version, only remember what a promote would publish
+        if let Some(mut frozen) = self.frozen.get_mut(server_config.name.as_ref()) {
+            frozen.status.pending_added = diff.added.len();
+            frozen.status.pending_modified = diff.modified.len();
+            frozen.status.pending_removed = diff.removed.len();
+            frozen.staged = staged_paths(&frozen.version, &new_builder);
+            if has_changes {
+                tracing::info!(
+                    "Server {} is frozen, holding {} added, {} modified, {} removed file(s) until promoted",
+                    server_config.name,
+                    diff.added.len(),
+                    diff.modified.len(),
+                    diff.removed.len()
+                );
+            }
+            return;
+        }
 
         if has_changes {
             // Sync with cloud storage if configured
             if let Some(storage) = &self.storage {
                 if storage.is_remote() {
-                    if let Err(e) = self.sync_cloud_storage(&server_config.name, &diff).await {
+                    if let Err(e) = self.sync_cloud_storage_coalesced(&server_config.name, &diff).await {
                         tracing::error!(
                             "Failed to sync cloud storage for server {}: {}",
                             server_config.name,
@@ -276,19 +719,23 @@ impl RescanOrchestrator {
                         );
                     }
 
-                    // Purge CDN cache for storage files
+                    // Purge CDN cache for storage files (at the URLs launchers actually hit)
                     if let Some(cdn) = &self.cdn {
+                        let url_rewrite = self.cache.url_rewrite();
                         let file_urls: Vec<String> = diff
                             .added
                             .iter()
                             .chain(diff.modified.iter())
                             .chain(diff.removed.iter())
-                            .map(|change| change.url.clone())
+                            .map(|change| match &url_rewrite {
+                                Some(rewrite) => rewrite.apply(&change.url),
+                                None => change.url.clone(),
+                            })
                             .filter(|url| !url.is_empty())
                             .collect();
 
                         if !file_urls.is_empty() {
-                            if let Err(e) = cdn.purge_files(file_urls).await {
+                            if let Err(e) = cdn.purge_files(&server_config.name, file_urls).await {
                                 tracing::warn!(
                                     "Failed to purge CDN cache for server {}: {}",
                                     server_config.name,
@@ -302,29 +749,24 @@ impl RescanOrchestrator {
 
             let is_new = old_builder.is_none();
 
-            // Update URL map incrementally (more efficient than full rebuild)
-            let mut new_builder_mut = new_builder;
-            if is_new {
-                // First scan: build full URL map
-                new_builder_mut.build_url_map();
-            } else {
-                // Incremental update: apply only the changes
-                diff.apply_to_url_map(&mut new_builder_mut);
-            }
+            // Complete the new version before publishing it: readers holding the previous Arc keep a
+            // consistent snapshot, and the single insert below swaps metadata and lookup maps together
+            let new_version = {
+                let mut builder = new_builder;
+                if is_new {
+                    // First scan: build full URL map
+                    builder.build_url_map();
+                } else {
+                    // Incremental update: apply only the changes (more efficient than full rebuild)
+                    diff.apply_to_url_map(&mut builder);
+                }
+                Arc::new(builder)
+            };
 
-            self.cache.insert(server_config.name.to_string(), Arc::new(new_builder_mut));
+            self.cache.insert(server_config.name.to_string(), new_version);
             self.last_updated.insert(server_config.name.to_string(), get_current_timestamp());
 
-            // Purge Cloudflare cache
-            if let Some(cloudflare) = &self.cloudflare {
-                if let Err(e) = cloudflare.purge_cache(&server_config.name).await {
-                    tracing::warn!(
-                        "Failed to purge Cloudflare cache for {}: {}",
-                        server_config.name,
-                        e
-                    );
-                }
-            }
+            self.purge_metadata(&server_config.name).await;
 
             if is_new {
                 self.events.emit(AppEvent::CacheNew {
@@ -342,6 +784,20 @@ impl RescanOrchestrator {
                     changes: vec![change_summary],
                 });
             }
+        } else if warnings_changed || diff.retimed > 0 {
+            // Same files: nothing to sync, but the metadata JSON changes and edge copies must go
+            let change_summary = if warnings_changed {
+                format!("{} scan warning(s)", new_builder.scan_warnings.len())
+            } else {
+                format!("{} mtime(s) changed", diff.retimed)
+            };
+            self.cache.insert(server_config.name.to_string(), Arc::new(new_builder));
+            self.last_updated.insert(server_config.name.to_string(), get_current_timestamp());
+            self.purge_metadata(&server_config.name).await;
+            self.events.emit(AppEvent::CacheUpdated {
+                server: server_config.name.to_string(),
+                changes: vec![change_summary],
+            });
         } else {
             self.events.emit(AppEvent::CacheUnchanged {
                 server: server_config.name.to_string(),
@@ -349,50 +805,152 @@ impl RescanOrchestrator {
         }
     }
 
+    /// Purges the metadata JSON of a server from the Cloudflare cache, if configured
+    async fn purge_metadata(&self, server_name: &str) {
+        if let Some(cloudflare) = &self.cloudflare {
+            match cloudflare.purge_cache(server_name).await {
+                Ok(()) => {}
+                // Logged once when the circuit opened
+                Err(e @ CacheError::CircuitOpen(_)) => {
+                    tracing::debug!("Skipped Cloudflare purge for {}: {}", server_name, e);
+                }
+                Err(e) => tracing::warn!("Failed to purge Cloudflare cache for {}: {}", server_name, e),
+            }
+        }
+    }
+
+    /// Synchronizes a diff with cloud storage, coalescing overlapping syncs per server
+    /// If a sync is already in flight, the changes are queued and reconciled by a single
+    /// follow-up sync once it finishes, instead of uploading intermediate states concurrently
+    async fn sync_cloud_storage_coalesced(
+        &self,
+        server_name: &str,
+        diff: &super::file_diff::FileDiff,
+    ) -> Result<()> {
+        // Categories kept on this server (`[storage] upload_* = false`) are neither uploaded nor deleted
+        let synced = {
+            let config = self.config.read().await;
+            diff.filter_categories(|category| config.storage.uploads(category))
+        };
+        if synced.added.is_empty() && synced.modified.is_empty() && synced.removed.is_empty() {
+            return Ok(());
+        }
+
+        match self.in_flight_syncs.entry(server_name.to_string()) {
+            dashmap::mapref::entry::Entry::Occupied(mut entry) => {
+                entry.get_mut().merge(&synced);
+                tracing::debug!("Cloud sync already in flight for {}, queued changes for follow-up", server_name);
+                return Ok(());
+            }
+            dashmap::mapref::entry::Entry::Vacant(entry) => {
+                entry.insert(PendingSync::default());
+            }
+        }
+        // Releases the marker if this future is dropped mid-sync (e.g. the rescan loop restarting)
+        let mut in_flight = InFlightSync {
+            syncs: &self.in_flight_syncs,
+            server_name,
+            released: false,
+        };
+
+        let mut result = self.sync_cloud_storage(server_name, &PendingSync::from_diff(&synced)).await;
+
+        loop {
+            // Atomically release the in-flight marker if nothing was queued meanwhile
+            if self.in_flight_syncs.remove_if(server_name, |_, pending| pending.is_empty()).is_some() {
+                in_flight.released = true;
+                break;
+            }
+
+            let pending = match self.in_flight_syncs.get_mut(server_name) {
+                Some(mut entry) => std::mem::take(entry.value_mut()),
+                None => {
+                    in_flight.released = true;
+                    break;
+                }
+            };
+
+            tracing::debug!("Running follow-up cloud sync for {}", server_name);
+            let follow_up = self.sync_cloud_storage(server_name, &pending).await;
+            if result.is_ok() {
+                result = follow_up;
+            }
+        }
+
+        result
+    }
+
+    /// File on disk of a change, through the folder layout of its server (`extra_dir`)
+    fn change_local_path(&self, server_config: Option<&ServerConfig>, change: &FileChange) -> PathBuf {
+        match (server_config, change.local_path.split_once('/')) {
+            (Some(config), Some((server, path))) => self.base_path.join(server).join(config.local_path(path)),
+            _ => self.base_path.join(&change.local_path),
+        }
+    }
+
     /// Synchronizes files with cloud storage (upload added/modified, delete removed)
     async fn sync_cloud_storage(
         &self,
         server_name: &str,
-        diff: &super::file_diff::FileDiff,
+        changes: &PendingSync,
     ) -> Result<()> {
+        if self.in_maintenance() {
+            tracing::info!("Maintenance mode, skipping cloud sync for {}", server_name);
+            return Ok(());
+        }
+
         let storage = self.storage.as_ref().unwrap();
+        let (skip_unchanged, server_config) = {
+            let config = self.config.read().await;
+            let server_config = config.servers.iter().find(|server| server.name.as_ref() == server_name).cloned();
+            (config.storage.skip_unchanged_uploads, server_config)
+        };
 
         tracing::info!(
-            "Syncing cloud storage for {}: {} added, {} modified, {} removed",
+            "Syncing cloud storage for {}: {} uploads, {} deletes",
             server_name,
-            diff.added.len(),
-            diff.modified.len(),
-            diff.removed.len()
+            changes.uploads.len(),
+            changes.deletes.len()
         );
 
         // Upload added and modified files in parallel
-        let upload_tasks: Vec<_> = diff
-            .added
-            .iter()
-            .chain(diff.modified.iter())
+        let upload_tasks: Vec<_> = changes
+            .uploads
+            .values()
             .map(|change| {
                 let storage = Arc::clone(storage);
-                let local_path = self.base_path.join(&change.local_path);
-                let remote_key = change.remote_key.clone();
+                let local_path = self.change_local_path(server_config.as_deref(), change);
+                let change = change.clone();
+                let check_remote = skip_unchanged && changes.added.contains(&change.remote_key);
 
                 tokio::spawn(async move {
-                    tracing::debug!("Uploading: {}", remote_key);
-                    storage.upload_file(&local_path, &remote_key).await
+                    if check_remote && remote_matches(storage.as_ref(), &change).await {
+                        tracing::debug!("Skipping upload, remote already up to date: {}", change.remote_key);
+                        return Ok(false);
+                    }
+                    tracing::debug!("Uploading: {}", change.remote_key);
+                    storage.upload_file(&local_path, &change.remote_key).await.map(|_| true)
                 })
             })
             .collect();
 
+        let mut skipped = 0;
         for task in upload_tasks {
-            task.await??;
+            if !task.await?? {
+                skipped += 1;
+            }
+        }
+        if skipped > 0 {
+            tracing::info!("Skipped {} uploads already present in remote storage for {}", skipped, server_name);
         }
 
         // Delete removed files in parallel
-        let delete_tasks: Vec<_> = diff
-            .removed
+        let delete_tasks: Vec<_> = changes
+            .deletes
             .iter()
-            .map(|change| {
+            .map(|remote_key| {
                 let storage = Arc::clone(storage);
-                let remote_key = change.remote_key.clone();
+                let remote_key = remote_key.clone();
 
                 tokio::spawn(async move {
                     tracing::debug!("Deleting: {}", remote_key);
@@ -409,22 +967,138 @@ impl RescanOrchestrator {
         Ok(())
     }
 
+    /// Uploads every file of a cached server to remote storage, regardless of diff state
+    /// Used after switching buckets or recovering from a failed sync
+    pub async fn reupload_server(&self, server_name: &str, version: &VersionBuilder) -> Result<ReuploadSummary> {
+        use futures::stream::{self, StreamExt};
+
+        self.ensure_not_in_maintenance("Re-upload")?;
+        let storage = self.storage.as_ref()
+            .ok_or(CacheError::StorageNotInitialized)?;
+
+        if !storage.is_remote() {
+            return Err(CacheError::CacheOperationFailed("Remote storage is not configured".to_string()));
+        }
+
+        let (concurrency, server_config, storage_settings) = {
+            let config = self.config.read().await;
+            let server_config = config.servers.iter().find(|server| server.name.as_ref() == server_name).cloned();
+            (config.storage.upload_concurrency.max(1), server_config, config.storage.clone())
+        };
+
+        // Diffing against nothing lists every file as added, minus the categories kept on this server
+        let files = super::file_diff::FileDiff::compute(server_name, None, version)
+            .filter_categories(|category| storage_settings.uploads(category))
+            .added;
+        tracing::info!("Re-uploading {} files for {}", files.len(), server_name);
+
+        let results: Vec<_> = stream::iter(files)
+            .map(|change| {
+                let storage = Arc::clone(storage);
+                let local_path = self.change_local_path(server_config.as_deref(), &change);
+                async move {
+                    let result = storage.upload_file(&local_path, &change.remote_key).await;
+                    if let Err(e) = &result {
+                        tracing::warn!("Failed to re-upload {}: {}", change.remote_key, e);
+                    }
+                    result
+                }
+            })
+            .buffer_unordered(concurrency)
+            .collect()
+            .await;
+
+        let failed = results.iter().filter(|result| result.is_err()).count();
+        let summary = ReuploadSummary {
+            server: server_name.to_string(),
+            uploaded: results.len() - failed,
+            failed,
+        };
+
+        tracing::info!(
+            "Re-upload complete for {}: {} uploaded, {} failed",
+            server_name,
+            summary.uploaded,
+            summary.failed
+        );
+        Ok(summary)
+    }
+
+    /// Purges a server from the edge cache on demand: metadata JSON through `[cloudflare]`,
+    /// every file URL through `[cdn]`
+    pub async fn purge_server(&self, server_name: &str, version: &VersionBuilder) -> Result<PurgeSummary> {
+        // Cloudflare accepts at most 30 URLs per purge request
+        const PURGE_BATCH_SIZE: usize = 30;
+
+        if self.cloudflare.is_none() && self.cdn.is_none() {
+            return Err(CacheError::PurgeNotConfigured);
+        }
+
+        let mut summary = PurgeSummary {
+            server: server_name.to_string(),
+            metadata_purged: false,
+            files_purged: 0,
+            errors: Vec::new(),
+        };
+
+        if let Some(cloudflare) = &self.cloudflare {
+            match cloudflare.purge_cache(server_name).await {
+                Ok(()) => summary.metadata_purged = true,
+                Err(e) => summary.errors.push(format!("metadata: {}", e)),
+            }
+        }
+
+        if let Some(cdn) = &self.cdn {
+            let url_rewrite = self.cache.url_rewrite();
+            let file_urls: Vec<String> = super::file_diff::FileDiff::compute(server_name, None, version)
+                .added
+                .iter()
+                .map(|change| match &url_rewrite {
+                    Some(rewrite) => rewrite.apply(&change.url),
+                    None => change.url.clone(),
+                })
+                .filter(|url| !url.is_empty())
+                .collect();
+
+            for batch in file_urls.chunks(PURGE_BATCH_SIZE) {
+                match cdn.purge_files(server_name, batch.to_vec()).await {
+                    Ok(()) => summary.files_purged += batch.len(),
+                    Err(e) => summary.errors.push(format!("{} files: {}", batch.len(), e)),
+                }
+            }
+        }
+
+        tracing::info!(
+            "Manual purge for {}: metadata {}, {} files purged, {} errors",
+            server_name,
+            if summary.metadata_purged { "purged" } else { "skipped" },
+            summary.files_purged,
+            summary.errors.len()
+        );
+        Ok(summary)
+    }
+
     /// Scans all enabled servers initially
     pub async fn scan_all_servers(&self) -> Result<()> {
+        use futures::stream::{FuturesUnordered, StreamExt};
+
         let storage = self.storage.as_ref()
-            .ok_or_else(|| CacheError::CacheOperationFailed("Storage backend not initialized".to_string()))?;
+            .ok_or(CacheError::StorageNotInitialized)?;
 
-        let (servers, base_path, batch_config, buffer_size) = {
+        let (servers, base_path, batch_config, scan_options, strict_startup, loader_templates, local_categories) = {
             let config = self.config.read().await;
             (
                 config.servers.clone(),
                 config.server.base_path.clone(),
                 config.cache.batch.clone(),
-                config.cache.checksum_buffer_size,
+                ScanOptions::from(&config.cache),
+                config.cache.strict_startup,
+                Arc::new(config.loader_templates.clone()),
+                LocalCategories::from_config(&config),
             )
         };
 
-        let scan_futures: Vec<_> = servers
+        let mut scan_futures: FuturesUnordered<_> = servers
             .iter()
             .filter(|server_config| server_config.enabled)
             .map(|server_config| {
@@ -432,28 +1106,92 @@ impl RescanOrchestrator {
                 let storage = Arc::clone(storage);
                 let base_path = base_path.clone();
                 let batch_config = batch_config.clone();
-                let buffer_size = buffer_size;
+                let loader_templates = Arc::clone(&loader_templates);
                 async move {
-                    let result = ServerScanner::scan_server(&config, &storage, base_path.as_ref(), &batch_config, buffer_size).await;
-                    (config.name.clone(), result)
+                    let start = Instant::now();
+                    let result = ServerScanner::scan_server(
+                        &config,
+                        &storage,
+                        base_path.as_ref(),
+                        &batch_config,
+                        &scan_options,
+                        &loader_templates,
+                    )
+                    .await;
+                    (config.name.clone(), result, start.elapsed())
                 }
             })
             .collect();
 
-        let results = futures::future::join_all(scan_futures).await;
+        // Collect results as each server finishes, reporting progress
+        let total = scan_futures.len();
+        let mut results = Vec::with_capacity(total);
+        while let Some((server_name, result, elapsed)) = scan_futures.next().await {
+            self.record_scan_duration(&server_name, &result, elapsed);
+            results.push((server_name, result));
+            self.events.emit(AppEvent::ScanProgress {
+                completed: results.len(),
+                total,
+            });
+        }
+
+        // Build URL maps and pre-serialize metadata JSON in parallel (off the request path)
+        let url_rewrite = self.cache.url_rewrite();
+        let encodings = self.cache.metadata_encodings();
+        let storage = Arc::clone(storage);
+        let results = tokio::task::spawn_blocking(move || {
+            use rayon::prelude::*;
+            results
+                .into_par_iter()
+                .map(|(server_name, result)| {
+                    let result = result.map(|scan| {
+                        let mut builder = scan.builder;
+                        local_categories.publish(&mut builder, storage.as_ref());
+                        builder.build_url_map();
+                        let json = super::models::serialize_version(&builder, url_rewrite.as_ref(), encodings);
+                        (Arc::new(builder), json, scan.failures, scan.empty_files)
+                    });
+                    (server_name, result)
+                })
+                .collect::<Vec<_>>()
+        })
+        .await?;
 
         // Update cache with results
         for (server_name, result) in results {
             match result {
-                Ok(mut builder) => {
-                    // Build URL map for initial scan
-                    builder.build_url_map();
-                    self.cache.insert(server_name.to_string(), Arc::new(builder));
+                Ok((builder, json, failures, empty_files)) => {
+                    self.report_scan_failures(&server_name, &failures, &empty_files);
+                    self.cache.insert_serialized(server_name.to_string(), builder, json);
                     self.last_updated.insert(server_name.to_string(), get_current_timestamp());
                     self.events.emit(AppEvent::CacheNew { server: server_name.to_string() });
                 }
+                Err(ScanError::FileUnstable(path)) => {
+                    // Left out until the write completes and triggers a rescan
+                    tracing::warn!("Server {} has a file still being written, it will be added on the next rescan: {}", server_name, path);
+                }
+                Err(e) if strict_startup => {
+                    // Strict startup: refuse to serve an empty or incomplete version
+                    return Err(CacheError::CacheOperationFailed(format!(
+                        "Initial scan of server {} failed (cache.strict_startup): {}",
+                        server_name, e
+                    )));
+                }
+                Err(e @ ScanError::FileFailed { .. }) => {
+                    // Strict scan: leave the server out rather than serving an incomplete version
+                    self.events.emit(AppEvent::Error {
+                        context: format!("Strict scan of server {} failed", server_name),
+                        error: e.to_string(),
+                    });
+                }
                 Err(e) => {
                     // Server scan failed (probably empty folders), add empty version to cache anyway
+                    if matches!(e, ScanError::Timeout { .. } | ScanError::TooManyFiles { .. }) {
+                        self.events.emit(AppEvent::Error {
+                            context: format!("Initial scan of server {}", server_name),
+                            error: e.to_string(),
+                        });
+                    }
                     tracing::warn!("Server {} initial scan failed (probably empty), adding empty version to cache: {}", server_name, e);
 
                     // Get server config to create empty builder
@@ -473,16 +1211,17 @@ impl RescanOrchestrator {
                             java_version: lighty_models::JavaVersion {
                                 major_version: config.java_version,
                             },
-                            arguments: lighty_models::Arguments {
-                                game: config.game_args.clone(),
-                                jvm: config.jvm_args.clone(),
-                            },
+                            arguments: ServerScanner::launch_arguments(&config, &loader_templates),
                             libraries: Vec::new(),
                             mods: Vec::new(),
                             natives: None,
                             client: None,
+                            extra_files: Vec::new(),
                             assets: Vec::new(),
+                            scan_warnings: Vec::new(),
                             url_to_path_map: std::collections::HashMap::new(),
+                            path_to_meta_map: std::collections::HashMap::new(),
+                            sha1_to_path_map: std::collections::HashMap::new(),
                         };
                         empty_builder.build_url_map();
                         self.cache.insert(server_name.to_string(), Arc::new(empty_builder));
@@ -501,12 +1240,124 @@ impl RescanOrchestrator {
         Ok(())
     }
 
+    /// Pins a server to its current version, rescans no longer publish changes
+    pub fn freeze(&self, server_name: &str, version: Arc<VersionBuilder>) -> FreezeStatus {
+        self.frozen
+            .entry(server_name.to_string())
+            .or_insert_with(|| {
+                tracing::info!("Freezing server {}", server_name);
+                FrozenServer {
+                    version,
+                    status: FreezeStatus {
+                        server: server_name.to_string(),
+                        frozen_since: get_current_timestamp(),
+                        pending_added: 0,
+                        pending_modified: 0,
+                        pending_removed: 0,
+                    },
+                    staged: HashSet::new(),
+                }
+            })
+            .status
+            .clone()
+    }
+
+    /// Unfreezes a server and publishes its current files in one cache swap
+    /// Returns the freeze state as it was right before the promote
+    pub async fn unfreeze_and_promote(&self, server_name: &str) -> Result<FreezeStatus> {
+        self.ensure_not_in_maintenance("Promote")?;
+        let (server_config, base_path) = {
+            let config = self.config.read().await;
+            let server_config = config
+                .servers
+                .iter()
+                .find(|s| s.name.as_ref() == server_name)
+                .ok_or_else(|| CacheError::ServerNotFound(server_name.to_string()))?
+                .clone();
+            (server_config, config.server.base_path.clone())
+        };
+
+        let (_, frozen) = self
+            .frozen
+            .remove(server_name)
+            .ok_or_else(|| CacheError::NotFrozen(server_name.to_string()))?;
+
+        tracing::info!("Promoting server {}", server_name);
+        if !self.rescan_server(&server_config, base_path.as_ref()).await {
+            // Nothing was published: stay pinned to the frozen version until the promote is retried
+            let status = frozen.status.clone();
+            self.frozen.entry(server_name.to_string()).or_insert(frozen);
+            tracing::warn!("Promote of server {} did not complete, it stays frozen since {}", server_name, status.frozen_since);
+            return Err(CacheError::PromoteFailed(server_name.to_string()));
+        }
+
+        Ok(frozen.status)
+    }
+
+    /// Freeze state of every frozen server
+    pub fn frozen_servers(&self) -> Vec<FreezeStatus> {
+        self.frozen.iter().map(|entry| entry.status.clone()).collect()
+    }
+
+    /// Whether a file of a frozen server differs on disk from its pinned version
+    /// False for servers that aren't frozen
+    pub fn is_staged(&self, server_name: &str, path: &str) -> bool {
+        self.frozen
+            .get(server_name)
+            .is_some_and(|frozen| frozen.staged.contains(path))
+    }
+
+    /// Rescans a server through change detection, so files whose URL moved are synced, republished and purged
+    /// For reloads changing how files are published without touching them (`upload_*`, `include_mtime`)
+    pub async fn republish_server(&self, server_name: &str) -> Result<()> {
+        if self.in_maintenance() {
+            tracing::info!("Maintenance mode, skipping republish of {}", server_name);
+            return Ok(());
+        }
+
+        let (server_config, base_path) = {
+            let config = self.config.read().await;
+            let server_config = config
+                .servers
+                .iter()
+                .find(|s| s.name.as_ref() == server_name)
+                .ok_or_else(|| CacheError::ServerNotFound(server_name.to_string()))?
+                .clone();
+            (server_config, config.server.base_path.clone())
+        };
+
+        if !self.rescan_server(&server_config, &base_path).await {
+            // The folder may look untouched to the next poll: drop its fingerprint so it is scanned again
+            self.fingerprints.remove(server_name);
+            return Err(CacheError::CacheOperationFailed(format!(
+                "Rescan of server {} did not complete, retried on the next poll",
+                server_name
+            )));
+        }
+        Ok(())
+    }
+
     /// Forces a rescan of a specific server
+    /// A frozen server is not rescanned, its pinned version is restored if it was evicted
+    /// In maintenance mode only servers missing from the cache (evicted, new) are scanned, so they can be served
     pub async fn force_rescan_server(&self, server_name: &str) -> Result<()> {
+        if let Some(frozen) = self.frozen.get(server_name) {
+            if self.cache.get(server_name).is_none() {
+                self.cache.insert(server_name.to_string(), Arc::clone(&frozen.version));
+            }
+            tracing::info!("Server {} is frozen, skipping forced rescan", server_name);
+            return Ok(());
+        }
+
+        if self.in_maintenance() && self.cache.get(server_name).is_some() {
+            tracing::info!("Maintenance mode, skipping forced rescan of {}", server_name);
+            return Ok(());
+        }
+
         let storage = self.storage.as_ref()
-            .ok_or_else(|| CacheError::CacheOperationFailed("Storage backend not initialized".to_string()))?;
+            .ok_or(CacheError::StorageNotInitialized)?;
 
-        let (server_config, base_path, batch_config, buffer_size) = {
+        let (server_config, base_path, batch_config, scan_options, loader_templates, local_categories) = {
             let config = self.config.read().await;
             let server_config = config
                 .servers
@@ -518,19 +1369,48 @@ impl RescanOrchestrator {
                 server_config,
                 config.server.base_path.clone(),
                 config.cache.batch.clone(),
-                config.cache.checksum_buffer_size,
+                ScanOptions::from(&config.cache),
+                config.loader_templates.clone(),
+                LocalCategories::from_config(&config),
             )
         };
 
         // Try to scan the server, but add it to cache even if scan fails (empty server)
-        match ServerScanner::scan_server(&server_config, storage, base_path.as_ref(), &batch_config, buffer_size).await {
-            Ok(mut builder) => {
+        let start = Instant::now();
+        let result = ServerScanner::scan_server(
+            &server_config,
+            storage,
+            base_path.as_ref(),
+            &batch_config,
+            &scan_options,
+            &loader_templates,
+        )
+        .await;
+        self.record_scan_duration(server_name, &result, start.elapsed());
+
+        match result {
+            Ok(scan) => {
+                self.report_scan_failures(server_name, &scan.failures, &scan.empty_files);
+
                 // Build URL map for forced rescan
+                let mut builder = scan.builder;
+                local_categories.publish(&mut builder, storage.as_ref());
                 builder.build_url_map();
                 self.cache.insert(server_name.to_string(), Arc::new(builder));
                 self.last_updated.insert(server_name.to_string(), get_current_timestamp());
                 tracing::info!("✓ Successfully rescanned server: {}", server_name);
             }
+            Err(e @ ScanError::FileFailed { .. }) => {
+                // Strict scan: keep the previous version rather than an incomplete one
+                return Err(CacheError::CacheOperationFailed(format!(
+                    "Strict scan of server {} failed: {}",
+                    server_name, e
+                )));
+            }
+            Err(e @ (ScanError::Timeout { .. } | ScanError::TooManyFiles { .. } | ScanError::FileUnstable(_))) => {
+                // Keep the previous version rather than replacing it with an empty one
+                return Err(CacheError::CacheOperationFailed(e.to_string()));
+            }
             Err(e) => {
                 // Server scan failed (probably empty folders), add empty version to cache anyway
                 tracing::warn!("Server {} scan failed (probably empty), adding empty version to cache: {}", server_name, e);
@@ -541,16 +1421,17 @@ impl RescanOrchestrator {
                     java_version: lighty_models::JavaVersion {
                         major_version: server_config.java_version,
                     },
-                    arguments: lighty_models::Arguments {
-                        game: server_config.game_args.clone(),
-                        jvm: server_config.jvm_args.clone(),
-                    },
+                    arguments: ServerScanner::launch_arguments(&server_config, &loader_templates),
                     libraries: Vec::new(),
                     mods: Vec::new(),
                     natives: None,
                     client: None,
+                    extra_files: Vec::new(),
                     assets: Vec::new(),
+                    scan_warnings: Vec::new(),
                     url_to_path_map: std::collections::HashMap::new(),
+                    path_to_meta_map: std::collections::HashMap::new(),
+                    sha1_to_path_map: std::collections::HashMap::new(),
                 };
                 empty_builder.build_url_map();
                 self.cache.insert(server_name.to_string(), Arc::new(empty_builder));
@@ -560,4 +1441,229 @@ impl RescanOrchestrator {
 
         Ok(())
     }
+
+    /// Reports files skipped by a lenient scan (one event per file, then a summary error) and empty files
+    /// Empty files are reported once, not on every rescan while they stay empty
+    fn report_scan_failures(&self, server_name: &str, failures: &[ScanFailure], empty_files: &[PathBuf]) {
+        let known = self.empty_files.remove(server_name).map(|(_, known)| known).unwrap_or_default();
+        let mut seen = HashSet::new();
+        let mut report_empty = |path: &PathBuf, skipped: bool| {
+            if !known.contains(path) {
+                self.events.emit(AppEvent::EmptyFileScanned {
+                    server: server_name.to_string(),
+                    path: path.display().to_string(),
+                    skipped,
+                });
+            }
+            seen.insert(path.clone());
+        };
+
+        for path in empty_files {
+            report_empty(path, false);
+        }
+
+        let mut failed = 0;
+        for failure in failures {
+            if matches!(failure.error, ScanError::EmptyFile(_)) {
+                report_empty(&failure.path, true);
+                continue;
+            }
+
+            failed += 1;
+            self.events.emit(AppEvent::ScanFileFailed {
+                server: server_name.to_string(),
+                path: failure.path.display().to_string(),
+                reason: failure.error.to_string(),
+            });
+        }
+
+        if !seen.is_empty() {
+            self.empty_files.insert(server_name.to_string(), seen);
+        }
+
+        if failed > 0 {
+            self.events.emit(AppEvent::Error {
+                context: format!("Scan of server {}", server_name),
+                error: format!("{} file(s) failed to scan and were left out of the metadata", failed),
+            });
+        }
+    }
+}
+
+/// Files of the pinned version whose scanned copy changed or disappeared
+fn staged_paths(pinned: &VersionBuilder, scanned: &VersionBuilder) -> HashSet<String> {
+    pinned
+        .path_to_meta_map
+        .iter()
+        .filter(|(path, meta)| scanned.file_meta(path).is_none_or(|current| current.sha1 != meta.sha1))
+        .map(|(path, _)| path.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::models::{CacheStore, MetadataEncodings};
+    use super::super::server_path_cache::ServerPathCache;
+
+    const SURVIVAL: &str = r#"
+[[servers]]
+name = "survival"
+loader = "vanilla"
+loader_version = ""
+minecraft_version = "1.21.1"
+main_class = "Main"
+java_version = 21
+enable_client = true
+enable_mods = true
+"#;
+
+    /// Orchestrator over a temporary base path holding a `survival` server with a client and two mods
+    struct Fixture {
+        orchestrator: RescanOrchestrator,
+        server: ServerConfig,
+        base_path: PathBuf,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let base_path = std::env::temp_dir().join(format!("lighty-rescan-test-{}-{}", std::process::id(), fastrand::u64(..)));
+            for (path, content) in [("client/client.jar", "client"), ("mods/a.jar", "a"), ("mods/b.jar", "b")] {
+                let path = base_path.join("survival").join(path);
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(path, content).unwrap();
+            }
+
+            let mut config = Config::from_toml_str(&format!("{}\n{}", lighty_config::scaffold_template(None), SURVIVAL)).unwrap();
+            config.server.base_path = Arc::from(base_path.to_string_lossy().as_ref());
+            config.cache.stability_delay_ms = 0;
+            let server = config.servers[0].as_ref().clone();
+
+            let cache = Arc::new(CacheStore::new(0, None, MetadataEncodings { brotli: false, gzip: false }));
+            let storage: Arc<dyn StorageBackend> =
+                Arc::new(lighty_storage::LocalBackend::new("http://localhost:8080".to_string(), base_path.clone()));
+            let orchestrator = RescanOrchestrator::new(
+                cache,
+                Arc::new(DashMap::new()),
+                Arc::new(RwLock::new(config)),
+                EventBus::new(true),
+                Some(storage),
+                None,
+                None,
+                base_path.clone(),
+                Arc::new(ServerPathCache::new()),
+            );
+
+            Self { orchestrator, server, base_path }
+        }
+
+        /// Scans the server without publishing anything
+        async fn scan(&self) -> std::result::Result<lighty_scanner::ServerScan, ScanError> {
+            let config = self.orchestrator.config.read().await.clone();
+            ServerScanner::scan_server_silent(
+                &self.server,
+                self.orchestrator.storage.as_ref().unwrap(),
+                &self.base_path.to_string_lossy(),
+                &config.cache.batch,
+                &ScanOptions::from(&config.cache),
+                &config.loader_templates,
+            )
+            .await
+        }
+
+        async fn rescan(&self) -> bool {
+            self.orchestrator.rescan_server(&self.server, &self.base_path.to_string_lossy()).await
+        }
+
+        fn version(&self) -> Arc<VersionBuilder> {
+            self.orchestrator.cache.get("survival").unwrap()
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.base_path);
+        }
+    }
+
+    #[tokio::test]
+    async fn slow_scan_times_out_and_keeps_the_previous_version() {
+        let fixture = Fixture::new().await;
+        assert!(fixture.rescan().await);
+        let previous = fixture.version();
+
+        {
+            // Freshly written files wait out the stability delay, longer than the scan may take
+            let mut config = fixture.orchestrator.config.write().await;
+            config.cache.scan_timeout_secs = 1;
+            config.cache.stability_delay_ms = 3000;
+        }
+        std::fs::write(fixture.base_path.join("survival/mods/c.jar"), "c").unwrap();
+
+        assert!(matches!(fixture.scan().await, Err(ScanError::Timeout { secs: 1, .. })));
+
+        assert!(!fixture.rescan().await);
+        assert!(Arc::ptr_eq(&fixture.version(), &previous));
+        assert_eq!(fixture.version().mods.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn scan_over_the_file_limit_keeps_the_previous_version() {
+        let fixture = Fixture::new().await;
+        fixture.orchestrator.config.write().await.cache.max_files_per_server = 3;
+        assert!(fixture.rescan().await);
+        let previous = fixture.version();
+
+        // A fourth file: refused before hashing anything
+        std::fs::write(fixture.base_path.join("survival/mods/c.jar"), "c").unwrap();
+        assert!(matches!(fixture.scan().await, Err(ScanError::TooManyFiles { limit: 3, .. })));
+
+        assert!(!fixture.rescan().await);
+        assert!(Arc::ptr_eq(&fixture.version(), &previous));
+        assert_eq!(fixture.version().mods.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn poll_skips_unchanged_folders() {
+        let fixture = Fixture::new().await;
+        let base_path = fixture.base_path.to_string_lossy().to_string();
+        let scans = || fixture.orchestrator.scan_stats.snapshot().first().map_or(0, |stats| stats.scans);
+
+        fixture.orchestrator.poll_server(&fixture.server, &base_path).await;
+        assert_eq!(scans(), 1);
+        fixture.orchestrator.poll_server(&fixture.server, &base_path).await;
+        assert_eq!(scans(), 1);
+
+        // Touched without changing its size: the newer mtime alone triggers a rescan
+        let touched = std::fs::File::options().write(true).open(fixture.base_path.join("survival/mods/a.jar")).unwrap();
+        touched.set_modified(SystemTime::now() + Duration::from_secs(10)).unwrap();
+        fixture.orchestrator.poll_server(&fixture.server, &base_path).await;
+        assert_eq!(scans(), 2);
+    }
+
+    #[test]
+    fn jittered_stays_within_the_spread() {
+        let interval = Duration::from_secs(30);
+        let mut rng = fastrand::Rng::with_seed(7);
+
+        assert_eq!(jittered(interval, 0, &mut rng), interval);
+        for _ in 0..1000 {
+            let delay = jittered(interval, 20, &mut rng);
+            assert!(delay >= Duration::from_secs(24) && delay <= Duration::from_secs(36), "{:?}", delay);
+        }
+    }
+
+    #[test]
+    fn jittered_is_never_zero() {
+        let interval = Duration::from_secs(30);
+        let mut rng = fastrand::Rng::with_seed(7);
+
+        // Capped at 100%: at most twice the interval, never an instant re-poll
+        for percent in [100, 250] {
+            for _ in 0..1000 {
+                let delay = jittered(interval, percent, &mut rng);
+                assert!(delay > Duration::ZERO && delay <= interval * 2, "{:?}", delay);
+            }
+        }
+    }
 }
@@ -0,0 +1,64 @@
+use dashmap::DashMap;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Number of recent scans the rolling average is computed over
+const ROLLING_WINDOW: usize = 10;
+
+/// Scan duration figures of a server, as exposed by the stats endpoint
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ScanDurationStats {
+    pub server: String,
+    pub last_ms: u64,
+    /// Average over the last scans (up to 10)
+    pub average_ms: u64,
+    pub scans: u64,
+}
+
+#[derive(Default)]
+struct ScanTimings {
+    recent_ms: VecDeque<u64>,
+    scans: u64,
+}
+
+/// Per-server scan durations, recorded by the initial scan and every rescan
+#[derive(Default)]
+pub struct ScanStats {
+    servers: DashMap<String, ScanTimings>,
+}
+
+impl ScanStats {
+    pub fn record(&self, server: &str, duration: Duration) {
+        let ms = duration.as_millis().min(u64::MAX as u128) as u64;
+        let mut timings = self.servers.entry(server.to_string()).or_default();
+        if timings.recent_ms.len() == ROLLING_WINDOW {
+            timings.recent_ms.pop_front();
+        }
+        timings.recent_ms.push_back(ms);
+        timings.scans += 1;
+    }
+
+    /// Drops the figures of servers no longer configured
+    pub fn retain(&self, mut keep: impl FnMut(&str) -> bool) {
+        self.servers.retain(|server, _| keep(server));
+    }
+
+    /// Figures of every scanned server, sorted by name
+    pub fn snapshot(&self) -> Vec<ScanDurationStats> {
+        let mut stats: Vec<_> = self
+            .servers
+            .iter()
+            .map(|entry| {
+                let recent = &entry.value().recent_ms;
+                ScanDurationStats {
+                    server: entry.key().clone(),
+                    last_ms: recent.back().copied().unwrap_or_default(),
+                    average_ms: recent.iter().sum::<u64>() / recent.len().max(1) as u64,
+                    scans: entry.value().scans,
+                }
+            })
+            .collect();
+        stats.sort_by(|a, b| a.server.cmp(&b.server));
+        stats
+    }
+}
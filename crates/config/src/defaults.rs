@@ -1,9 +1,17 @@
-/// Default values for configuration fields
+//! Default values for configuration fields
+
+pub fn base_path_root() -> super::models::BasePathRoot {
+    super::models::BasePathRoot::Executable
+}
 
 pub fn tcp_nodelay() -> bool {
     true
 }
 
+pub fn keep_alive() -> bool {
+    true
+}
+
 pub fn timeout_secs() -> u64 {
     60
 }
@@ -24,6 +32,18 @@ pub fn max_memory_cache_gb() -> u64 {
     0  // 0 = unlimited
 }
 
+pub fn max_cached_versions() -> u64 {
+    0  // 0 = unlimited
+}
+
+pub fn scan_timeout_secs() -> u64 {
+    600  // 0 = no timeout
+}
+
+pub fn stability_delay_ms() -> u64 {
+    500  // 0 = no partial-write check
+}
+
 pub fn batch_size_default() -> usize {
     100
 }
@@ -38,6 +58,17 @@ pub fn batch_config() -> super::models::BatchConfig {
     }
 }
 
+pub fn warm_categories() -> Vec<super::models::FileCategory> {
+    use super::models::FileCategory;
+    vec![
+        FileCategory::Client,
+        FileCategory::Libraries,
+        FileCategory::Mods,
+        FileCategory::Natives,
+        FileCategory::Assets,
+    ]
+}
+
 pub fn allowed_origins() -> Vec<String> {
     vec!["*".to_string()]
 }
@@ -46,14 +77,58 @@ pub fn server_enabled() -> bool {
     true
 }
 
+pub fn client_dir() -> String {
+    "client".to_string()
+}
+
+pub fn libraries_dir() -> String {
+    "libraries".to_string()
+}
+
+pub fn mods_dir() -> String {
+    "mods".to_string()
+}
+
+pub fn natives_dir() -> String {
+    "natives".to_string()
+}
+
+pub fn assets_dir() -> String {
+    "assets".to_string()
+}
+
+pub fn extra_dir() -> String {
+    "extra".to_string()
+}
+
 pub fn streaming_threshold_mb() -> u64 {
     100  // Files larger than 100MB will be streamed instead of loaded into memory
 }
 
+pub fn max_assets_per_page() -> usize {
+    10000
+}
+
+pub fn hashed_file_max_age_secs() -> u64 {
+    31_536_000  // One year: a hash always names the same bytes
+}
+
 pub fn file_watcher_debounce_ms() -> u64 {
     300  // Wait 300ms after last file change before rescanning
 }
 
+pub fn file_watcher_poll_interval_ms() -> u64 {
+    2000  // Polling interval used when the native file watcher is unavailable
+}
+
+pub fn file_watcher_backend() -> super::models::WatcherBackend {
+    super::models::WatcherBackend::Native
+}
+
+pub fn watch_base_path() -> bool {
+    false
+}
+
 pub fn checksum_buffer_size() -> usize {
     8192  // 8KB buffer for SHA1 calculation
 }
@@ -70,6 +145,14 @@ pub fn enable_compression() -> bool {
     true  // Enable HTTP compression (gzip/brotli) by default
 }
 
+pub fn expose_server_list() -> bool {
+    true  // Compatibility: `GET /` has always listed the servers
+}
+
+pub fn redirect_bare_server_name() -> bool {
+    true  // `/{server}` is a common mistake for `/{server}.json`
+}
+
 // Storage defaults
 pub fn storage_backend() -> super::models::StorageBackend {
     super::models::StorageBackend::Local
@@ -83,15 +166,42 @@ pub fn auto_upload() -> bool {
     true
 }
 
+pub fn upload_concurrency() -> usize {
+    16
+}
+
+pub fn skip_unchanged_uploads() -> bool {
+    true
+}
+
+pub fn init_retries() -> u32 {
+    5
+}
+
+pub fn upload_category() -> bool {
+    true
+}
+
 pub fn storage_settings() -> super::models::StorageSettings {
     super::models::StorageSettings {
         backend: storage_backend(),
         keep_local_backup: keep_local_backup(),
         auto_upload: auto_upload(),
+        upload_concurrency: upload_concurrency(),
+        skip_unchanged_uploads: skip_unchanged_uploads(),
+        init_retries: init_retries(),
+        upload_client: upload_category(),
+        upload_libraries: upload_category(),
+        upload_mods: upload_category(),
+        upload_natives: upload_category(),
+        upload_assets: upload_category(),
+        upload_extra: upload_category(),
         s3: s3_settings(),
+        gcs: gcs_settings(),
     }
 }
 
+#[allow(dead_code)]
 pub fn s3_region() -> String {
     "auto".to_string()
 }
@@ -100,6 +210,7 @@ pub fn s3_region_arc() -> std::sync::Arc<str> {
     std::sync::Arc::from("auto")
 }
 
+#[allow(dead_code)]
 pub fn s3_bucket_name() -> String {
     "lighty-updater".to_string()
 }
@@ -121,17 +232,65 @@ pub fn s3_settings() -> super::models::S3Settings {
     }
 }
 
+pub fn gcs_settings() -> super::models::GcsSettings {
+    super::models::GcsSettings {
+        enabled: false,
+        bucket_name: s3_bucket_name_arc(),
+        credentials_path: String::new(),
+        public_url: std::sync::Arc::from(""),
+        bucket_prefix: std::sync::Arc::from(""),
+    }
+}
+
 // CDN defaults
 pub fn cdn_provider() -> String {
     "cloudflare".to_string()
 }
 
+pub fn tls_settings() -> super::models::TlsSettings {
+    super::models::TlsSettings {
+        enabled: false,
+        cert_path: String::new(),
+        key_path: String::new(),
+    }
+}
+
+pub fn mime_settings() -> super::models::MimeSettings {
+    super::models::MimeSettings {
+        overrides: mime_overrides(),
+    }
+}
+
+pub fn mime_overrides() -> std::collections::HashMap<String, String> {
+    std::collections::HashMap::from([("jar".to_string(), "application/java-archive".to_string())])
+}
+
+pub fn remote_servers_settings() -> super::models::RemoteServersSettings {
+    super::models::RemoteServersSettings {
+        url: String::new(),
+        refresh_interval_secs: remote_servers_refresh_interval_secs(),
+        timeout_secs: remote_servers_timeout_secs(),
+        bearer_token: String::new(),
+        required: false,
+    }
+}
+
+pub fn remote_servers_refresh_interval_secs() -> u64 {
+    300
+}
+
+pub fn remote_servers_timeout_secs() -> u64 {
+    10
+}
+
 pub fn cdn_settings() -> super::models::CdnSettings {
     super::models::CdnSettings {
         enabled: false,
         provider: cdn_provider(),
         zone_id: String::new(),
         api_token: String::new(),
+        public_base_url: String::new(),
+        zones: Vec::new(),
     }
 }
 
@@ -142,9 +301,25 @@ pub fn cloudflare_settings() -> super::models::CloudflareSettings {
         zone_id: String::new(),
         api_token: String::new(),
         base_url: std::sync::Arc::from(""),
+        max_retries: cloudflare_max_retries(),
+        circuit_breaker_threshold: cloudflare_circuit_breaker_threshold(),
+        circuit_breaker_cooldown_secs: cloudflare_circuit_breaker_cooldown_secs(),
+        zones: Vec::new(),
     }
 }
 
+pub fn cloudflare_max_retries() -> u32 {
+    3
+}
+
+pub fn cloudflare_circuit_breaker_threshold() -> u32 {
+    5
+}
+
+pub fn cloudflare_circuit_breaker_cooldown_secs() -> u64 {
+    300
+}
+
 // Hot-reload defaults
 pub fn hot_reload_config_enabled() -> bool {
     true
@@ -165,6 +340,9 @@ pub fn hot_reload_files_settings() -> super::models::HotReloadFilesSettings {
     super::models::HotReloadFilesSettings {
         enabled: hot_reload_files_enabled(),
         debounce_ms: file_watcher_debounce_ms(),
+        poll_interval_ms: file_watcher_poll_interval_ms(),
+        watcher: file_watcher_backend(),
+        watch_base_path: watch_base_path(),
     }
 }
 
@@ -184,29 +362,68 @@ pub const DEFAULT_CONFIG_TEMPLATE: &str = r#"# =================================
 host = "0.0.0.0"                     # Server bind address (0.0.0.0 = all interfaces)
 port = 8080                          # Server port
 base_url = "http://localhost:8080"   # Public base URL for file downloads
-base_path = "updater"                # Base directory for server files (relative to executable if not absolute)
+base_path = "updater"                # Base directory for server files (relative to base_path_root if not absolute)
+base_path_root = "executable"        # Root of a relative base_path: "executable" (binary's folder) | "working_dir"
 
 # Performance
 tcp_nodelay = true                   # Disable Nagle's algorithm (lower latency)
+keep_alive = true                    # Reuse connections for several requests (HTTP/1.1 keep-alive)
+keep_alive_timeout_secs = 0          # Close connections idle or sending headers for longer than this (0 = no timeout)
+http2 = false                        # Also accept HTTP/2 (h2c, or ALPN h2 with [tls]), multiplexing downloads on one connection
+http2_keep_alive_interval_secs = 0   # Send HTTP/2 PINGs this often to detect dead connections (0 = disabled)
+max_connections_per_ip = 0           # Max open connections per client IP, extra ones are closed (0 = unlimited)
 timeout_secs = 60                    # Request timeout in seconds
 max_concurrent_requests = 1000       # Max simultaneous connections
 max_body_size_mb = 100               # Max request body size in MB
 streaming_threshold_mb = 100         # Files >100MB streamed, <100MB cached in RAM
+streaming_thresholds_mb = {}         # Per MIME type, e.g. { "application/java-archive" = 0, "application/json" = 1000, "image/*" = 20 }
+max_download_kbps = 0                # Per-download bandwidth cap in KB/s for streamed files (0 = unlimited)
+max_assets_per_page = 10000          # Upper bound for ?assets_per_page on /{server}.json (also the default page size)
+file_max_age_secs = 0                # Cache-Control max-age of files served by path (0 = no Cache-Control header)
+hashed_file_max_age_secs = 31536000  # Cache-Control max-age of /{server}/by-hash/ files, sent as immutable
+metadata_max_age_secs = 0            # Cache-Control max-age of /{server}.json, launch, manifest and tree (0 = no header)
 enable_compression = true            # HTTP compression (gzip/brotli/deflate)
+case_insensitive_paths = false       # Retry missed file lookups ignoring case (e.g. Mods/Foo.jar -> mods/foo.jar)
+servable_extensions = []             # Only serve files with these extensions, others 404 (e.g. ["jar", "json", "png", ""], "" = no extension, [] = all)
+redirect_bare_server_name = true     # GET /{server} answers 308 to /{server}.json instead of 404
+expose_server_list = true            # GET / lists the servers (false = 404, /{server}.json still works)
+available_servers = "similar"        # Servers named in unknown-server 404s: "all" | "similar" (close names only) | "none"
 
 # CORS
 allowed_origins = ["*"]              # "*" = all origins | ["https://example.com"] for production
 
+# Admin
+admin_token = ""                     # Bearer token for /admin/* endpoints (empty = admin endpoints disabled)
+maintenance_mode = false             # Keep serving files but pause rescans and uploads (also via /admin/maintenance)
+
 [cache]
 # Core settings
 enabled = true                       # Enable in-memory file caching
 auto_scan = true                     # Scan servers on startup
 rescan_interval = 30                 # Rescan interval in seconds (0 = file watcher only)
+rescan_jitter_percent = 0            # Randomize each polling interval by up to ±this percent, per instance (0 = exact interval)
 max_memory_cache_gb = 0              # Max RAM for cache in GB (0 = unlimited)
+max_cached_versions = 0              # Max server metadata trees kept in RAM, LRU evicted (0 = unlimited)
+max_cached_file_mb = 0               # Files larger than this are never loaded into RAM, always served from disk (0 = no limit)
 
 # Performance
 checksum_buffer_size = 8192          # SHA1 calculation buffer (bytes)
 hash_concurrency = 100               # Max concurrent hash computations
+file_load_threads = 0                # Threads for the startup RAM cache load (0 = all cores)
+warm_categories = ["client", "libraries", "mods", "natives", "assets"]  # Folders preloaded into RAM at startup, others are served from disk
+cache_warming = "eager"              # "eager" = preload at startup | "lazy" = cache on first request | "metadata_only" = preload .json, rest on first request
+compute_crc32 = false                # Also compute CRC32 (same read as SHA1) for legacy launchers
+include_mtime = false                # Also publish each file's modification time (Unix seconds) as "mtime"
+strict_scan = false                  # Fail a server scan if any file fails (false = skip the file and report it)
+strict_startup = false               # Abort startup if an enabled server fails its initial scan (false = serve an empty version)
+max_files_per_server = 0             # Refuse to scan a server with more files than this, e.g. a world save copied by mistake (0 = no limit)
+skip_mismatched_natives = false      # Leave out natives whose extension contradicts their OS folder, e.g. a .dll in natives/linux (false = warn only)
+skip_empty_files = false             # Leave out zero-byte files, usually incomplete transfers (false = publish them with a warning)
+scan_timeout_secs = 600              # Abort a single server scan after this long, e.g. hung network mount (0 = no timeout)
+stability_delay_ms = 500             # Re-stat recently modified files after this delay, postponing the scan while one is still being written (0 = off)
+verify_on_serve = false              # Check RAM-cached files against the metadata SHA1 before serving (evicts stale entries)
+precompress_brotli = false           # Keep brotli variants of metadata and RAM-cached text files, served to clients accepting "br"
+precompress_gzip = false             # Keep a gzip variant of the metadata JSON, served to clients accepting "gzip"
 config_reload_channel_size = 100     # Config reload event channel buffer size
 
 # Batch processing
@@ -227,14 +444,26 @@ debounce_ms = 300                    # Delay after config.toml changes before re
 [hot-reload.files]
 enabled = true                       # Enable automatic server files rescan on changes
 debounce_ms = 300                    # Delay after server files changes (client/mods/libs) before rescan (milliseconds)
+poll_interval_ms = 2000              # Polling interval for the "poll" watcher, or if the native watcher is unavailable
+watcher = "native"                   # "native" (inotify/FSEvents) | "poll" (reliable on NFS/SMB mounts), also used for config.toml
+watch_base_path = false              # Detect new top-level folders in base_path (scans configured servers, logs unknown ones)
 
 # ===============================================================================
 # STORAGE BACKEND
 # ===============================================================================
 [storage]
-backend = "local"                    # Storage backend: "local" or "s3"
-keep_local_backup = true             # Keep local files when using S3
+backend = "local"                    # Storage backend: "local", "s3" or "gcs"
+keep_local_backup = true             # Keep local files when using S3 (without them, downloads are proxied from the bucket)
 auto_upload = true                   # Auto-upload to S3 on file changes
+upload_concurrency = 16              # Max parallel uploads for a full re-upload (/admin/reupload)
+skip_unchanged_uploads = true        # Skip uploading a new file the bucket already holds with the same SHA1 (S3 only)
+init_retries = 5                     # Retries with exponential backoff when the storage endpoint is unreachable at startup
+upload_client = true                 # Sync each category to S3/GCS; false = keep it on this server, published under server.base_url
+upload_libraries = true
+upload_mods = true
+upload_natives = true
+upload_assets = true                 # e.g. false to keep bandwidth-heavy assets off a paid bucket
+upload_extra = true
 
 # S3 Configuration (only used if backend = "s3")
 [storage.s3]
@@ -247,6 +476,28 @@ bucket_name = "lighty-updater"       # S3 bucket name
 public_url = ""                      # Public URL for file downloads (optional)
 bucket_prefix = ""                   # Prefix for all S3 keys (optional)
 
+# Google Cloud Storage configuration (only used if backend = "gcs")
+[storage.gcs]
+enabled = false                      # Enable GCS storage backend
+bucket_name = "lighty-updater"       # GCS bucket name
+credentials_path = ""                # Service account JSON key file
+public_url = ""                      # Public URL for file downloads (default: https://storage.googleapis.com/<bucket>)
+bucket_prefix = ""                   # Prefix for all object names (optional)
+
+# ===============================================================================
+# TLS (native HTTPS, without a reverse proxy)
+# ===============================================================================
+[tls]
+enabled = false                      # Serve HTTPS directly (read at startup)
+cert_path = ""                       # Certificate chain (PEM), e.g. /etc/letsencrypt/live/example.com/fullchain.pem
+key_path = ""                        # Private key (PEM); both files are reloaded when they change
+
+# ===============================================================================
+# CONTENT TYPES
+# ===============================================================================
+[mime]
+overrides = { jar = "application/java-archive" }  # Extension → Content-Type, before the built-in guess (read at startup), e.g. { mcmeta = "application/json", nbt = "application/octet-stream" }
+
 # ===============================================================================
 # CDN CACHE PURGE (for storage files)
 # ===============================================================================
@@ -255,6 +506,12 @@ enabled = false                      # Enable CDN cache purging for storage file
 provider = "cloudflare"              # CDN provider: "cloudflare" or "cloudfront"
 zone_id = ""                         # Cloudflare Zone ID (cloudflare only)
 api_token = ""                       # Cloudflare API Token (requires Cache Purge permission)
+public_base_url = ""                 # Public CDN base replacing the storage origin in file URLs (e.g. https://cdn.example.com)
+# Additional zones, e.g. one per group of servers (zone_id above can then stay empty)
+#[[cdn.zones]]
+#zone_id = ""
+#api_token = ""
+#servers = ["survival"]              # Servers whose files are purged in this zone (empty = all)
 
 # ===============================================================================
 # CLOUDFLARE API CACHE PURGE (for API JSON responses)
@@ -264,17 +521,68 @@ enabled = false                      # Enable Cloudflare cache purging for API J
 zone_id = ""                         # Cloudflare Zone ID
 api_token = ""                       # Cloudflare API Token (requires Cache Purge permission)
 base_url = ""                        # API base URL (e.g., https://api.example.com)
+max_retries = 3                      # Attempts per purge, with exponential backoff
+circuit_breaker_threshold = 5        # Failed purges in a row before pausing purges for a zone (0 = never pause)
+circuit_breaker_cooldown_secs = 300  # Pause length, then a single trial purge decides whether to resume
+# Additional zones, e.g. one per group of servers (zone_id above can then stay empty)
+#[[cloudflare.zones]]
+#zone_id = ""
+#api_token = ""
+#servers = ["survival"]              # Servers whose metadata is purged in this zone (empty = all)
+
+# ===============================================================================
+# REMOTE SERVER LIST
+# ===============================================================================
+[remote_servers]
+url = ""                             # JSON or TOML server list merged into [[servers]] (empty = disabled), local entries win on name clashes
+refresh_interval_secs = 300          # Re-fetch period, reloading the config when the list changed (0 = startup and config reloads only)
+timeout_secs = 10                    # Request timeout
+bearer_token = ""                    # Sent as Authorization: Bearer when set
+required = false                     # Fail startup and reloads if the list can't be fetched (false = warn, keep the local or last fetched servers)
+
+# ===============================================================================
+# SERVER DEFAULTS
+# ===============================================================================
+# Fields applied to every [[servers]] entry that omits them (any server field except name)
+
+#[server_defaults]
+#main_class = "net.minecraft.client.main.Main"
+#java_version = 21
+#jvm_args = ["-Xmx4G"]
+
+# ===============================================================================
+# LOADER ARGUMENT TEMPLATES
+# ===============================================================================
+# Loader-standard arguments placed before each server's game_args/jvm_args
+# Only loaders with a section get one, remove a section to emit the server's own arguments only
+# To have none, keep an empty [loader_templates] table (a missing one is added back by the migration)
+# ${loader_version} and ${minecraft_version} are filled per server
+# Legacy Forge (1.12 and older): game = ["--tweakClass", "net.minecraftforge.fml.common.launcher.FMLTweaker"]
+
+[loader_templates.fabric]
+game = []
+jvm = ["-DFabricMcEmu= net.minecraft.client.main.Main"]
+
+[loader_templates.forge]
+game = ["--launchTarget", "forgeclient", "--fml.forgeVersion", "${loader_version}", "--fml.mcVersion", "${minecraft_version}", "--fml.forgeGroup", "net.minecraftforge"]
+jvm = []
+
+[loader_templates.neoforge]
+game = ["--launchTarget", "forgeclient", "--fml.neoForgeVersion", "${loader_version}", "--fml.mcVersion", "${minecraft_version}"]
+jvm = []
 
 # ===============================================================================
 # SERVER DEFINITIONS
 # ===============================================================================
 # Expected folder structure: {base_path}/{name}/client/*.jar, libraries/*.jar,
-# mods/*.jar, natives/*.dll|.so|.dylib, assets/*
+# mods/*.jar, natives/*.dll|.so|.dylib, assets/*, extra/* (any file, with enable_extra)
+# Each folder can be renamed per server with the *_dir settings
 # You can duplicate this [[servers]] section to add multiple servers
 
 #[[servers]]
 #name = "example"                    # Server ID (used in URLs and folder name)
 #enabled = true                      # Enable this server
+#unlisted = false                    # Hide from the / listing, still reachable by name (e.g. beta modpacks)
 #loader = "vanilla"                  # Loader type: vanilla | forge | fabric | quilt
 #loader_version = ""                 # Loader version (empty for vanilla)
 #minecraft_version = "1.21"          # Minecraft version
@@ -285,6 +593,17 @@ base_url = ""                        # API base URL (e.g., https://api.example.c
 #enable_mods = true                  # Include mods
 #enable_natives = true               # Include native libraries
 #enable_assets = true                # Include assets
+#enable_extra = false                # Include every file of extra_dir as generic resources (served under extra/)
+#client_dir = "client"               # Folder of each category in the server folder, for existing layouts
+#libraries_dir = "libraries"         # e.g. "libs" (URLs and manifest paths keep the default names)
+#mods_dir = "mods"
+#natives_dir = "natives"             # Still split into windows/, linux/, macos/
+#assets_dir = "assets"
+#extra_dir = "extra"                 # Folder of the extra files, e.g. "config" to publish options.txt and friends
+#enable_bundle = false               # Serve /{name}/bundle.zip with all files (zipped per request, CPU-heavy)
 #game_args = []                      # Additional game arguments
 #jvm_args = []                       # Additional JVM arguments
+#rescan_interval = 300               # Polling interval override in seconds, e.g. lower for busy servers (0 = never polled)
+#natives_naming = "lighty"           # Native names: lighty (natives:{os}:{file}) | classifier ({file}-natives-{os})
+#natives_extract_exclude = ["META-INF/"]  # Entries launchers skip when extracting natives archives
 "#;
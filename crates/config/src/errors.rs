@@ -19,4 +19,10 @@ pub enum ConfigError {
 
     #[error("Migration failed: {0}")]
     MigrationError(String),
+
+    #[error("Remote server list error: {0}")]
+    RemoteServers(String),
+
+    #[error("Config does not match the expected schema (auto-migration disabled): {0}")]
+    SchemaMismatch(String),
 }
@@ -3,6 +3,10 @@ mod defaults;
 mod loader;
 mod migration;
 mod errors;
+mod scaffold;
+mod remote;
 
 pub use models::*;
 pub use errors::ConfigError;
+pub use remote::RemoteServerList;
+pub use scaffold::{scaffold_server_block, scaffold_template};
@@ -1,7 +1,10 @@
 use super::defaults::DEFAULT_CONFIG_TEMPLATE;
-use super::migration::migrate_config_if_needed;
-use super::models::Config;
+use super::migration::{check_config_schema, migrate_config_if_needed};
+use super::models::{BasePathRoot, Config, ServerConfig};
+use super::remote::RemoteServerList;
 use super::errors::ConfigError;
+use serde::Deserialize;
+use std::collections::HashSet;
 use std::path::Path;
 use std::sync::Arc;
 
@@ -31,9 +34,22 @@ impl Config {
 
         // Read and parse config
         let content = tokio::fs::read_to_string(path).await?;
-        let config: Config = toml::from_str(&content)?;
+        parse_config(&content)
+    }
+
+    /// Loads configuration from a file, failing instead of migrating if fields are missing or deprecated
+    /// The file is never written, not even created: for configs kept under version control
+    pub async fn from_file_strict<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+
+        if !path.exists() {
+            return Err(ConfigError::ConfigNotFound(path.display().to_string()));
+        }
+
+        check_config_schema(path).await?;
 
-        Ok(config)
+        let content = tokio::fs::read_to_string(path).await?;
+        parse_config(&content)
     }
 
     /// Loads configuration from a file WITHOUT migration
@@ -43,9 +59,125 @@ impl Config {
 
         // Read and parse config directly (no migration)
         let content = tokio::fs::read_to_string(path).await?;
-        let config: Config = toml::from_str(&content)?;
+        parse_config(&content)
+    }
 
-        Ok(config)
+    /// Parses config text without touching the disk or network (no migration)
+    pub fn from_toml_str(content: &str) -> Result<Self> {
+        parse_config(content)
+    }
+
+    /// Appends servers fetched from `[remote_servers]`, filling their omitted fields from `[server_defaults]`
+    /// A server defined in the file wins over a remote one of the same name
+    pub fn add_remote_servers(&mut self, remote: RemoteServerList) -> Result<()> {
+        #[derive(Deserialize)]
+        struct RemoteServers {
+            servers: Vec<ServerConfig>,
+        }
+
+        let mut table = toml::Table::new();
+        table.insert("servers".to_string(), toml::Value::Array(merge_remote_servers(&self.servers, &remote)));
+        if let Some(defaults) = &self.server_defaults {
+            table.insert("server_defaults".to_string(), toml::Value::Table(defaults.clone()));
+        }
+        apply_server_defaults(&mut table)?;
+
+        let RemoteServers { servers } = table.try_into()?;
+        self.servers.extend(servers.into_iter().map(Arc::new));
+        self.remote_server_list = Some(remote);
+        Ok(())
+    }
+}
+
+/// Parses the config, filling fields omitted by `[[servers]]` entries from `[server_defaults]`
+/// `[server_defaults]` is kept for the servers of `[remote_servers]`, appended after the fetch
+fn parse_config(content: &str) -> Result<Config> {
+    let mut table: toml::Table = toml::from_str(content)?;
+    let mut config: Config = match table.get("server_defaults") {
+        Some(defaults) => {
+            let defaults = defaults.as_table().cloned();
+            apply_server_defaults(&mut table)?;
+            let mut config: Config = table.try_into()?;
+            config.server_defaults = defaults;
+            config
+        }
+        // Parsed straight from the text, keeping line numbers in error messages
+        None => toml::from_str(content)?,
+    };
+    resolve_base_path(&mut config);
+    Ok(config)
+}
+
+/// Remote servers to append, skipping names already defined (in the file or earlier in the list)
+fn merge_remote_servers(servers: &[Arc<ServerConfig>], remote: &RemoteServerList) -> Vec<toml::Value> {
+    let mut names: HashSet<&str> = servers.iter().map(|server| server.name.as_ref()).collect();
+    let mut merged = Vec::with_capacity(remote.0.len());
+    for server in &remote.0 {
+        if let Some(name) = server.get("name").and_then(toml::Value::as_str) {
+            if !names.insert(name) {
+                tracing::warn!("Remote server '{}' is already defined, keeping the first definition", name);
+                continue;
+            }
+        }
+        merged.push(toml::Value::Table(server.clone()));
+    }
+    merged
+}
+
+/// Copies each `[server_defaults]` field into the servers that don't set it
+/// Fields set on a server always win
+fn apply_server_defaults(table: &mut toml::Table) -> Result<()> {
+    let Some(defaults) = table.remove("server_defaults") else {
+        return Ok(());
+    };
+    let toml::Value::Table(defaults) = defaults else {
+        return Err(ConfigError::InvalidConfig("[server_defaults] must be a table".to_string()));
+    };
+    if defaults.contains_key("name") {
+        return Err(ConfigError::InvalidConfig("[server_defaults] cannot set a server name".to_string()));
+    }
+
+    if let Some(toml::Value::Array(servers)) = table.get_mut("servers") {
+        for server in servers.iter_mut().filter_map(toml::Value::as_table_mut) {
+            for (key, value) in &defaults {
+                server.entry(key.as_str()).or_insert_with(|| value.clone());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Makes a relative `base_path` absolute against `base_path_root`
+/// (systemd services often run with a working directory other than the binary's)
+/// Always absolute so file watcher event paths match the server folders
+fn resolve_base_path(config: &mut Config) {
+    let base_path = Path::new(config.server.base_path.as_ref());
+    if base_path.is_absolute() {
+        return;
+    }
+
+    let root = match config.server.base_path_root {
+        BasePathRoot::Executable => std::env::current_exe()
+            .and_then(|exe| {
+                exe.parent()
+                    .map(Path::to_path_buf)
+                    .ok_or_else(|| std::io::Error::other("executable has no parent directory"))
+            })
+            .or_else(|e| {
+                tracing::warn!("Failed to locate executable, resolving base_path from working directory: {}", e);
+                std::env::current_dir()
+            }),
+        BasePathRoot::WorkingDir => std::env::current_dir(),
+    };
+
+    match root {
+        Ok(root) => {
+            config.server.base_path = Arc::from(root.join(base_path).to_string_lossy().as_ref());
+        }
+        Err(e) => {
+            tracing::warn!("Failed to resolve base_path {}: {}", base_path.display(), e);
+        }
     }
 }
 
@@ -54,3 +186,89 @@ async fn create_default_config<P: AsRef<Path>>(path: P) -> Result<()> {
     tokio::fs::write(path, DEFAULT_CONFIG_TEMPLATE).await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(extra: &str) -> Config {
+        let content = format!("{}\n{}", crate::scaffold_template(None), extra);
+        Config::from_toml_str(&content).unwrap()
+    }
+
+    fn remote(servers: &str) -> RemoteServerList {
+        let table: toml::Table = toml::from_str(servers).unwrap();
+        let servers = table["servers"].as_array().unwrap();
+        RemoteServerList(servers.iter().map(|server| server.as_table().unwrap().clone()).collect())
+    }
+
+    const SURVIVAL: &str = r#"
+[[servers]]
+name = "survival"
+loader = "fabric"
+loader_version = "0.16.9"
+minecraft_version = "1.21.1"
+"#;
+
+    #[test]
+    fn merge_skips_servers_already_defined() {
+        let config = config(&format!("[server_defaults]\nmain_class = \"Main\"\njava_version = 21\n{}", SURVIVAL));
+        let remote = remote(
+            r#"
+[[servers]]
+name = "survival"
+loader = "forge"
+[[servers]]
+name = "creative"
+loader = "vanilla"
+[[servers]]
+name = "creative"
+loader = "forge"
+"#,
+        );
+
+        let merged = merge_remote_servers(&config.servers, &remote);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0]["name"].as_str(), Some("creative"));
+        assert_eq!(merged[0]["loader"].as_str(), Some("vanilla"));
+    }
+
+    #[test]
+    fn remote_servers_get_the_file_defaults() {
+        let mut config = config(&format!("[server_defaults]\nmain_class = \"Main\"\njava_version = 17\n{}", SURVIVAL));
+        let remote = remote(
+            r#"
+[[servers]]
+name = "survival"
+loader = "forge"
+loader_version = "47.3.0"
+minecraft_version = "1.20.1"
+[[servers]]
+name = "creative"
+loader = "vanilla"
+loader_version = ""
+minecraft_version = "1.21.1"
+java_version = 21
+"#,
+        );
+        config.add_remote_servers(remote.clone()).unwrap();
+
+        let names: Vec<&str> = config.servers.iter().map(|server| server.name.as_ref()).collect();
+        assert_eq!(names, ["survival", "creative"]);
+        // The file's definition wins
+        assert_eq!(config.servers[0].loader, "fabric");
+        // Omitted fields come from [server_defaults], set ones are kept
+        assert_eq!(config.servers[1].main_class, "Main");
+        assert_eq!(config.servers[1].java_version, 21);
+        assert_eq!(config.remote_server_list, Some(remote));
+    }
+
+    #[test]
+    fn remote_servers_without_defaults_must_be_complete() {
+        let mut config = config("");
+        let incomplete = remote("[[servers]]\nname = \"creative\"\nloader = \"vanilla\"\n");
+        assert!(config.add_remote_servers(incomplete).is_err());
+        assert!(config.servers.is_empty());
+        assert_eq!(config.remote_server_list, None);
+    }
+}
@@ -1,7 +1,7 @@
 use super::errors::ConfigError;
 use std::path::Path;
 use std::sync::Arc;
-use toml_edit::{Array, DocumentMut, Item, Table, Value};
+use toml_edit::{Array, DocumentMut, InlineTable, Item, Table, TableLike, Value};
 
 type Result<T> = std::result::Result<T, ConfigError>;
 
@@ -12,21 +12,7 @@ pub async fn migrate_config_if_needed<P: AsRef<Path>>(
 ) -> Result<()> {
     let content = tokio::fs::read_to_string(path.as_ref()).await?;
     let mut doc = content.parse::<DocumentMut>()?;
-    let mut added_fields = Vec::new();
-
-    migrate_server_section(&mut doc, &mut added_fields)?;
-    migrate_cache_section(&mut doc, &mut added_fields)?;
-    migrate_hot_reload_section(&mut doc, &mut added_fields)?;
-    migrate_storage_section(&mut doc, &mut added_fields)?;
-    migrate_cdn_section(&mut doc, &mut added_fields)?;
-    migrate_cloudflare_section(&mut doc, &mut added_fields)?;
-    migrate_servers_array(&mut doc, &mut added_fields)?;
-
-    // Remove deprecated [metrics] section
-    if doc.contains_key("metrics") {
-        doc.remove("metrics");
-        added_fields.push("removed deprecated [metrics] section".to_string());
-    }
+    let added_fields = migrate_document(&mut doc)?;
 
     // Only write if we added fields
     if !added_fields.is_empty() {
@@ -46,6 +32,102 @@ pub async fn migrate_config_if_needed<P: AsRef<Path>>(
     Ok(())
 }
 
+/// Checks the config file against the expected schema without modifying it
+/// Fails with every field the migration would add or remove
+pub async fn check_config_schema<P: AsRef<Path>>(path: P) -> Result<()> {
+    let content = tokio::fs::read_to_string(path.as_ref()).await?;
+    let original = content.parse::<DocumentMut>()?;
+    let mut migrated = original.clone();
+    if migrate_document(&mut migrated)?.is_empty() {
+        return Ok(());
+    }
+
+    let mut missing = Vec::new();
+    let mut deprecated = Vec::new();
+    schema_differences(original.as_table(), migrated.as_table(), "", &mut missing, &mut deprecated);
+
+    let mut details = Vec::new();
+    if !missing.is_empty() {
+        details.push(format!("missing fields: {}", missing.join(", ")));
+    }
+    if !deprecated.is_empty() {
+        details.push(format!("deprecated fields: {}", deprecated.join(", ")));
+    }
+    Err(ConfigError::SchemaMismatch(details.join("; ")))
+}
+
+/// Applies every migration step to the document, returning the changes made
+fn migrate_document(doc: &mut DocumentMut) -> Result<Vec<String>> {
+    let mut added_fields = Vec::new();
+
+    migrate_server_section(doc, &mut added_fields)?;
+    migrate_cache_section(doc, &mut added_fields)?;
+    migrate_hot_reload_section(doc, &mut added_fields)?;
+    migrate_storage_section(doc, &mut added_fields)?;
+    migrate_tls_section(doc, &mut added_fields)?;
+    migrate_mime_section(doc, &mut added_fields)?;
+    migrate_remote_servers_section(doc, &mut added_fields)?;
+    migrate_cdn_section(doc, &mut added_fields)?;
+    migrate_cloudflare_section(doc, &mut added_fields)?;
+    migrate_loader_templates_section(doc, &mut added_fields);
+    migrate_servers_array(doc, &mut added_fields)?;
+
+    // Remove deprecated [metrics] section
+    if doc.contains_key("metrics") {
+        doc.remove("metrics");
+        added_fields.push("removed deprecated [metrics] section".to_string());
+    }
+
+    Ok(added_fields)
+}
+
+/// Collects the dotted paths of keys only in the migrated document (missing)
+/// or only in the original one (deprecated)
+fn schema_differences(
+    original: &dyn TableLike,
+    migrated: &dyn TableLike,
+    prefix: &str,
+    missing: &mut Vec<String>,
+    deprecated: &mut Vec<String>,
+) {
+    let path_of = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", prefix, key)
+        }
+    };
+
+    for (key, migrated_item) in migrated.iter() {
+        let path = path_of(key);
+        let Some(original_item) = original.get(key) else {
+            missing.push(path);
+            continue;
+        };
+
+        if let (Some(original_table), Some(migrated_table)) =
+            (original_item.as_table_like(), migrated_item.as_table_like())
+        {
+            schema_differences(original_table, migrated_table, &path, missing, deprecated);
+        } else if let (Some(original_array), Some(migrated_array)) =
+            (original_item.as_array_of_tables(), migrated_item.as_array_of_tables())
+        {
+            for (idx, (original_table, migrated_table)) in
+                original_array.iter().zip(migrated_array.iter()).enumerate()
+            {
+                let path = format!("{}[{}]", path, idx);
+                schema_differences(original_table, migrated_table, &path, missing, deprecated);
+            }
+        }
+    }
+
+    for (key, _) in original.iter() {
+        if !migrated.contains_key(key) {
+            deprecated.push(path_of(key));
+        }
+    }
+}
+
 fn migrate_server_section(
     doc: &mut DocumentMut,
     added_fields: &mut Vec<String>,
@@ -87,7 +169,13 @@ fn migrate_server_section(
         Value::from("updater"),
         added_fields,
     );
+    ensure_field(server, "base_path_root", Value::from("executable"), added_fields);
     ensure_field(server, "tcp_nodelay", Value::from(true), added_fields);
+    ensure_field(server, "keep_alive", Value::from(true), added_fields);
+    ensure_field(server, "keep_alive_timeout_secs", Value::from(0), added_fields);
+    ensure_field(server, "http2", Value::from(false), added_fields);
+    ensure_field(server, "http2_keep_alive_interval_secs", Value::from(0), added_fields);
+    ensure_field(server, "max_connections_per_ip", Value::from(0), added_fields);
     ensure_field(server, "timeout_secs", Value::from(60), added_fields);
     ensure_field(
         server,
@@ -101,12 +189,25 @@ fn migrate_server_section(
         Value::from(100),
         added_fields,
     );
+    ensure_field(server, "streaming_thresholds_mb", Value::InlineTable(InlineTable::new()), added_fields);
     ensure_field(
         server,
         "enable_compression",
         Value::from(true),
         added_fields,
     );
+    ensure_field(server, "case_insensitive_paths", Value::from(false), added_fields);
+    ensure_field(server, "servable_extensions", Value::Array(Array::new()), added_fields);
+    ensure_field(server, "redirect_bare_server_name", Value::from(true), added_fields);
+    ensure_field(server, "expose_server_list", Value::from(true), added_fields);
+    ensure_field(server, "available_servers", Value::from("similar"), added_fields);
+    ensure_field(server, "max_download_kbps", Value::from(0), added_fields);
+    ensure_field(server, "max_assets_per_page", Value::from(10000), added_fields);
+    ensure_field(server, "file_max_age_secs", Value::from(0), added_fields);
+    ensure_field(server, "hashed_file_max_age_secs", Value::from(31_536_000), added_fields);
+    ensure_field(server, "metadata_max_age_secs", Value::from(0), added_fields);
+    ensure_field(server, "admin_token", Value::from(""), added_fields);
+    ensure_field(server, "maintenance_mode", Value::from(false), added_fields);
 
     // Add allowed_origins field
     if !server.contains_key("allowed_origins") {
@@ -148,12 +249,20 @@ fn migrate_cache_section(
     ensure_field(cache, "enabled", Value::from(true), added_fields);
     ensure_field(cache, "auto_scan", Value::from(true), added_fields);
     ensure_field(cache, "rescan_interval", Value::from(30), added_fields);
+    ensure_field(cache, "rescan_jitter_percent", Value::from(0), added_fields);
     ensure_field(
         cache,
         "max_memory_cache_gb",
         Value::from(0),
         added_fields,
     );
+    ensure_field(
+        cache,
+        "max_cached_versions",
+        Value::from(0),
+        added_fields,
+    );
+    ensure_field(cache, "max_cached_file_mb", Value::from(0), added_fields);
     ensure_field(
         cache,
         "checksum_buffer_size",
@@ -166,12 +275,34 @@ fn migrate_cache_section(
         Value::from(100),
         added_fields,
     );
+    ensure_field(cache, "file_load_threads", Value::from(0), added_fields);
+    ensure_field(cache, "cache_warming", Value::from("eager"), added_fields);
+    if !cache.contains_key("warm_categories") {
+        let mut arr = Array::new();
+        for category in ["client", "libraries", "mods", "natives", "assets"] {
+            arr.push(category);
+        }
+        cache["warm_categories"] = Item::Value(Value::Array(arr));
+        added_fields.push("warm_categories".to_string());
+    }
     ensure_field(
         cache,
         "config_reload_channel_size",
         Value::from(100),
         added_fields,
     );
+    ensure_field(cache, "compute_crc32", Value::from(false), added_fields);
+    ensure_field(cache, "include_mtime", Value::from(false), added_fields);
+    ensure_field(cache, "strict_scan", Value::from(false), added_fields);
+    ensure_field(cache, "strict_startup", Value::from(false), added_fields);
+    ensure_field(cache, "max_files_per_server", Value::from(0), added_fields);
+    ensure_field(cache, "skip_mismatched_natives", Value::from(false), added_fields);
+    ensure_field(cache, "skip_empty_files", Value::from(false), added_fields);
+    ensure_field(cache, "scan_timeout_secs", Value::from(600), added_fields);
+    ensure_field(cache, "stability_delay_ms", Value::from(500), added_fields);
+    ensure_field(cache, "precompress_brotli", Value::from(false), added_fields);
+    ensure_field(cache, "precompress_gzip", Value::from(false), added_fields);
+    ensure_field(cache, "verify_on_serve", Value::from(false), added_fields);
 
     // Migrate deprecated scan_batch_size to cache.batch.*
     let old_batch_size = if let Some(Item::Value(Value::Integer(val))) = cache.get("scan_batch_size") {
@@ -273,6 +404,9 @@ fn migrate_hot_reload_section(
     // Use old value if exists, otherwise default to 300
     let files_debounce = old_files_debounce.unwrap_or(300);
     ensure_field(files, "debounce_ms", Value::from(files_debounce), added_fields);
+    ensure_field(files, "poll_interval_ms", Value::from(2000), added_fields);
+    ensure_field(files, "watcher", Value::from("native"), added_fields);
+    ensure_field(files, "watch_base_path", Value::from(false), added_fields);
 
     // Remove old fields from [cache] if they exist
     if let Some(cache) = doc.get_mut("cache").and_then(|c| c.as_table_mut()) {
@@ -308,6 +442,15 @@ fn migrate_storage_section(
     ensure_field(storage, "backend", Value::from("local"), added_fields);
     ensure_field(storage, "keep_local_backup", Value::from(true), added_fields);
     ensure_field(storage, "auto_upload", Value::from(true), added_fields);
+    ensure_field(storage, "upload_concurrency", Value::from(16), added_fields);
+    ensure_field(storage, "skip_unchanged_uploads", Value::from(true), added_fields);
+    ensure_field(storage, "init_retries", Value::from(5), added_fields);
+    ensure_field(storage, "upload_client", Value::from(true), added_fields);
+    ensure_field(storage, "upload_libraries", Value::from(true), added_fields);
+    ensure_field(storage, "upload_mods", Value::from(true), added_fields);
+    ensure_field(storage, "upload_natives", Value::from(true), added_fields);
+    ensure_field(storage, "upload_assets", Value::from(true), added_fields);
+    ensure_field(storage, "upload_extra", Value::from(true), added_fields);
 
     // Ensure [storage.s3] section
     if !storage.contains_key("s3") {
@@ -330,6 +473,95 @@ fn migrate_storage_section(
     ensure_field(s3, "public_url", Value::from(""), added_fields);
     ensure_field(s3, "bucket_prefix", Value::from(""), added_fields);
 
+    // Ensure [storage.gcs] section
+    if !storage.contains_key("gcs") {
+        let mut gcs_table = Table::new();
+        gcs_table.set_implicit(true);
+        storage["gcs"] = Item::Table(gcs_table);
+        added_fields.push("storage.gcs".to_string());
+    }
+
+    let gcs = storage["gcs"]
+        .as_table_mut()
+        .ok_or_else(|| ConfigError::InvalidConfig("Invalid [storage.gcs] section in config".to_string()))?;
+
+    ensure_field(gcs, "enabled", Value::from(false), added_fields);
+    ensure_field(gcs, "bucket_name", Value::from("lighty-updater"), added_fields);
+    ensure_field(gcs, "credentials_path", Value::from(""), added_fields);
+    ensure_field(gcs, "public_url", Value::from(""), added_fields);
+    ensure_field(gcs, "bucket_prefix", Value::from(""), added_fields);
+
+    Ok(())
+}
+
+fn migrate_tls_section(
+    doc: &mut DocumentMut,
+    added_fields: &mut Vec<String>,
+) -> Result<()> {
+    // Ensure [tls] section exists
+    if !doc.contains_key("tls") {
+        let mut table = Table::new();
+        table.set_implicit(true);
+        doc["tls"] = Item::Table(table);
+        added_fields.push("tls".to_string());
+    }
+
+    let tls = doc["tls"]
+        .as_table_mut()
+        .ok_or_else(|| ConfigError::InvalidConfig("Invalid [tls] section in config".to_string()))?;
+
+    ensure_field(tls, "enabled", Value::from(false), added_fields);
+    ensure_field(tls, "cert_path", Value::from(""), added_fields);
+    ensure_field(tls, "key_path", Value::from(""), added_fields);
+
+    Ok(())
+}
+
+fn migrate_mime_section(
+    doc: &mut DocumentMut,
+    added_fields: &mut Vec<String>,
+) -> Result<()> {
+    // Ensure [mime] section exists
+    if !doc.contains_key("mime") {
+        let mut table = Table::new();
+        table.set_implicit(true);
+        doc["mime"] = Item::Table(table);
+        added_fields.push("mime".to_string());
+    }
+
+    let mime = doc["mime"]
+        .as_table_mut()
+        .ok_or_else(|| ConfigError::InvalidConfig("Invalid [mime] section in config".to_string()))?;
+
+    let mut overrides = InlineTable::new();
+    overrides.insert("jar", Value::from("application/java-archive"));
+    ensure_field(mime, "overrides", Value::InlineTable(overrides), added_fields);
+
+    Ok(())
+}
+
+fn migrate_remote_servers_section(
+    doc: &mut DocumentMut,
+    added_fields: &mut Vec<String>,
+) -> Result<()> {
+    // Ensure [remote_servers] section exists
+    if !doc.contains_key("remote_servers") {
+        let mut table = Table::new();
+        table.set_implicit(true);
+        doc["remote_servers"] = Item::Table(table);
+        added_fields.push("remote_servers".to_string());
+    }
+
+    let remote_servers = doc["remote_servers"]
+        .as_table_mut()
+        .ok_or_else(|| ConfigError::InvalidConfig("Invalid [remote_servers] section in config".to_string()))?;
+
+    ensure_field(remote_servers, "url", Value::from(""), added_fields);
+    ensure_field(remote_servers, "refresh_interval_secs", Value::from(300), added_fields);
+    ensure_field(remote_servers, "timeout_secs", Value::from(10), added_fields);
+    ensure_field(remote_servers, "bearer_token", Value::from(""), added_fields);
+    ensure_field(remote_servers, "required", Value::from(false), added_fields);
+
     Ok(())
 }
 
@@ -353,6 +585,7 @@ fn migrate_cdn_section(
     ensure_field(cdn, "provider", Value::from("cloudflare"), added_fields);
     ensure_field(cdn, "zone_id", Value::from(""), added_fields);
     ensure_field(cdn, "api_token", Value::from(""), added_fields);
+    ensure_field(cdn, "public_base_url", Value::from(""), added_fields);
 
     Ok(())
 }
@@ -377,6 +610,9 @@ fn migrate_cloudflare_section(
     ensure_field(cloudflare, "zone_id", Value::from(""), added_fields);
     ensure_field(cloudflare, "api_token", Value::from(""), added_fields);
     ensure_field(cloudflare, "base_url", Value::from(""), added_fields);
+    ensure_field(cloudflare, "max_retries", Value::from(3), added_fields);
+    ensure_field(cloudflare, "circuit_breaker_threshold", Value::from(5), added_fields);
+    ensure_field(cloudflare, "circuit_breaker_cooldown_secs", Value::from(300), added_fields);
 
     // Remove deprecated purge_on_update field
     if cloudflare.contains_key("purge_on_update") {
@@ -387,10 +623,61 @@ fn migrate_cloudflare_section(
     Ok(())
 }
 
+/// Default `[loader_templates]`: loader, game arguments, JVM arguments
+const DEFAULT_LOADER_TEMPLATES: [(&str, &[&str], &[&str]); 3] = [
+    ("fabric", &[], &["-DFabricMcEmu= net.minecraft.client.main.Main"]),
+    (
+        "forge",
+        &[
+            "--launchTarget", "forgeclient",
+            "--fml.forgeVersion", "${loader_version}",
+            "--fml.mcVersion", "${minecraft_version}",
+            "--fml.forgeGroup", "net.minecraftforge",
+        ],
+        &[],
+    ),
+    (
+        "neoforge",
+        &[
+            "--launchTarget", "forgeclient",
+            "--fml.neoForgeVersion", "${loader_version}",
+            "--fml.mcVersion", "${minecraft_version}",
+        ],
+        &[],
+    ),
+];
+
+fn migrate_loader_templates_section(
+    doc: &mut DocumentMut,
+    added_fields: &mut Vec<String>,
+) {
+    // Only a missing table: removed loaders (or an empty [loader_templates]) are the user's choice
+    if doc.contains_key("loader_templates") {
+        return;
+    }
+
+    let mut templates = Table::new();
+    templates.set_implicit(true);
+    for (loader, game, jvm) in DEFAULT_LOADER_TEMPLATES {
+        let mut template = Table::new();
+        template["game"] = Item::Value(Value::Array(game.iter().copied().collect::<Array>()));
+        template["jvm"] = Item::Value(Value::Array(jvm.iter().copied().collect::<Array>()));
+        templates.insert(loader, Item::Table(template));
+    }
+    doc["loader_templates"] = Item::Table(templates);
+    added_fields.push("loader_templates".to_string());
+}
+
 fn migrate_servers_array(
     doc: &mut DocumentMut,
     added_fields: &mut Vec<String>,
 ) -> Result<()> {
+    // A shared `enabled` in [server_defaults] must not be shadowed by per-server values
+    let default_enabled = doc
+        .get("server_defaults")
+        .and_then(|defaults| defaults.as_table_like())
+        .is_some_and(|defaults| defaults.contains_key("enabled"));
+
     // Only migrate existing servers array - don't create empty one
     // This prevents "servers = []" from being added to the config file
     if let Some(servers_array) = doc
@@ -399,7 +686,7 @@ fn migrate_servers_array(
     {
         // Migrate enabled field for each server
         for (idx, server_table) in servers_array.iter_mut().enumerate() {
-            if !server_table.contains_key("enabled") {
+            if !default_enabled && !server_table.contains_key("enabled") {
                 server_table.insert("enabled", Item::Value(Value::from(true)));
                 added_fields.push(format!("servers[{}].enabled", idx));
             }
@@ -420,3 +707,35 @@ fn ensure_field(
         added_fields.push(key.to_string());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds_missing_loader_templates() {
+        let mut doc = "[server]\nport = 8080\n".parse::<DocumentMut>().unwrap();
+        let added_fields = migrate_document(&mut doc).unwrap();
+        assert!(added_fields.contains(&"loader_templates".to_string()));
+
+        let config = crate::Config::from_toml_str(&doc.to_string()).unwrap();
+        let mut loaders: Vec<&str> = config.loader_templates.keys().map(String::as_str).collect();
+        loaders.sort_unstable();
+        assert_eq!(loaders, ["fabric", "forge", "neoforge"]);
+        assert_eq!(config.loader_templates["forge"].game[3], "${loader_version}");
+
+        // The template file's sections are what a fresh config gets
+        let scaffold = crate::Config::from_toml_str(&crate::scaffold_template(None)).unwrap();
+        assert_eq!(config.loader_templates, scaffold.loader_templates);
+    }
+
+    #[test]
+    fn keeps_an_empty_loader_templates_table() {
+        let mut doc = "[loader_templates]\n".parse::<DocumentMut>().unwrap();
+        let added_fields = migrate_document(&mut doc).unwrap();
+        assert!(!added_fields.contains(&"loader_templates".to_string()));
+
+        let config = crate::Config::from_toml_str(&doc.to_string()).unwrap();
+        assert!(config.loader_templates.is_empty());
+    }
+}
@@ -1,11 +1,13 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Config {
     pub server: ServerSettings,
     pub cache: CacheSettings,
-    #[serde(default = "super::defaults::hot_reload_settings")]
+    /// Read from `[hot-reload]`, the section the template and migration write (`[hot_reload]` still accepted)
+    #[serde(rename = "hot-reload", alias = "hot_reload", default = "super::defaults::hot_reload_settings")]
     pub hot_reload: HotReloadSettings,
     #[serde(default = "super::defaults::storage_settings")]
     pub storage: StorageSettings,
@@ -13,12 +15,39 @@ pub struct Config {
     pub cdn: CdnSettings,
     #[serde(default = "super::defaults::cloudflare_settings")]
     pub cloudflare: CloudflareSettings,
+    #[serde(default = "super::defaults::tls_settings")]
+    pub tls: TlsSettings,
+    #[serde(default = "super::defaults::mime_settings")]
+    pub mime: MimeSettings,
+    #[serde(default = "super::defaults::remote_servers_settings")]
+    pub remote_servers: RemoteServersSettings,
+    /// Launch arguments per loader name, replacing the built-in template of that loader
+    #[serde(default)]
+    pub loader_templates: LoaderTemplates,
     #[serde(default)]
     #[serde(deserialize_with = "deserialize_arc_servers")]
     #[serde(serialize_with = "serialize_arc_servers")]
     pub servers: Vec<Arc<ServerConfig>>,
+    /// Server list fetched from `[remote_servers]` by the last load (not part of the file)
+    #[serde(skip)]
+    pub remote_server_list: Option<super::RemoteServerList>,
+    /// `[server_defaults]` of the file, applied to the remote servers too
+    #[serde(skip)]
+    pub(crate) server_defaults: Option<toml::Table>,
+}
+
+/// Loader-standard launch arguments, merged before a server's `game_args`/`jvm_args`
+/// `${loader_version}` and `${minecraft_version}` are replaced with the server's values
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct LoaderTemplate {
+    #[serde(default)]
+    pub game: Vec<String>,
+    #[serde(default)]
+    pub jvm: Vec<String>,
 }
 
+pub type LoaderTemplates = HashMap<String, LoaderTemplate>;
+
 // Custom deserializer to wrap ServerConfig in Arc
 fn deserialize_arc_servers<'de, D>(deserializer: D) -> Result<Vec<Arc<ServerConfig>>, D::Error>
 where
@@ -77,8 +106,20 @@ pub struct ServerSettings {
     #[serde(deserialize_with = "deserialize_arc_str")]
     #[serde(serialize_with = "serialize_arc_str")]
     pub base_path: Arc<str>,
+    #[serde(default = "super::defaults::base_path_root")]
+    pub base_path_root: BasePathRoot,
     #[serde(default = "super::defaults::tcp_nodelay")]
     pub tcp_nodelay: bool,
+    #[serde(default = "super::defaults::keep_alive")]
+    pub keep_alive: bool,
+    #[serde(default)]
+    pub keep_alive_timeout_secs: u64,
+    #[serde(default)]
+    pub http2: bool,
+    #[serde(default)]
+    pub http2_keep_alive_interval_secs: u64,
+    #[serde(default)]
+    pub max_connections_per_ip: usize,
     #[serde(default = "super::defaults::timeout_secs")]
     pub timeout_secs: u64,
     #[serde(default = "super::defaults::max_body_size")]
@@ -89,8 +130,42 @@ pub struct ServerSettings {
     pub max_concurrent_requests: usize,
     #[serde(default = "super::defaults::streaming_threshold_mb")]
     pub streaming_threshold_mb: u64,
+    /// Thresholds per MIME type (`application/json`) or top-level type (`image/*`), overriding `streaming_threshold_mb`
+    #[serde(default)]
+    pub streaming_thresholds_mb: HashMap<String, u64>,
     #[serde(default = "super::defaults::enable_compression")]
     pub enable_compression: bool,
+    #[serde(default)]
+    pub case_insensitive_paths: bool,
+    /// Extensions `serve_file` may serve, without dot ("" = no extension); empty = all
+    #[serde(default)]
+    pub servable_extensions: Vec<String>,
+    /// `GET /{server}` redirects (308) to `/{server}.json` instead of a file lookup
+    #[serde(default = "super::defaults::redirect_bare_server_name")]
+    pub redirect_bare_server_name: bool,
+    /// `GET /` lists the servers (false = 404, servers stay reachable by name)
+    #[serde(default = "super::defaults::expose_server_list")]
+    pub expose_server_list: bool,
+    /// Server names listed in the 404 of an unknown server
+    #[serde(default)]
+    pub available_servers: AvailableServers,
+    #[serde(default)]
+    pub max_download_kbps: u64,
+    #[serde(default = "super::defaults::max_assets_per_page")]
+    pub max_assets_per_page: usize,
+    /// `Cache-Control` max-age of files served by path (0 = no header)
+    #[serde(default)]
+    pub file_max_age_secs: u64,
+    /// `Cache-Control` max-age of files served by hash (`/by-hash/`), marked `immutable`
+    #[serde(default = "super::defaults::hashed_file_max_age_secs")]
+    pub hashed_file_max_age_secs: u64,
+    /// `Cache-Control` max-age of `/{server}.json`, launch, manifest and tree (0 = no header)
+    #[serde(default)]
+    pub metadata_max_age_secs: u64,
+    #[serde(default)]
+    pub admin_token: String,
+    #[serde(default)]
+    pub maintenance_mode: bool,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -98,18 +173,86 @@ pub struct CacheSettings {
     pub enabled: bool,
     pub auto_scan: bool,
     pub rescan_interval: u64,
+    /// Spread of each polling interval, ±percent (capped at 100) so instances don't scan in lockstep
+    #[serde(default)]
+    pub rescan_jitter_percent: u64,
     #[serde(default = "super::defaults::max_memory_cache_gb")]
     pub max_memory_cache_gb: u64,
+    #[serde(default = "super::defaults::max_cached_versions")]
+    pub max_cached_versions: u64,
+    #[serde(default)]
+    pub max_cached_file_mb: u64,
     #[serde(default = "super::defaults::batch_config")]
     pub batch: BatchConfig,
     #[serde(default = "super::defaults::checksum_buffer_size")]
     pub checksum_buffer_size: usize,
     #[serde(default = "super::defaults::hash_concurrency")]
     pub hash_concurrency: usize,
+    #[serde(default)]
+    pub file_load_threads: usize,
+    #[serde(default = "super::defaults::warm_categories")]
+    pub warm_categories: Vec<FileCategory>,
+    #[serde(default)]
+    pub cache_warming: CacheWarming,
+    #[serde(default)]
+    pub compute_crc32: bool,
+    /// Publish each file's modification time (Unix seconds) as `mtime`
+    #[serde(default)]
+    pub include_mtime: bool,
+    #[serde(default)]
+    pub strict_scan: bool,
+    #[serde(default)]
+    pub strict_startup: bool,
+    #[serde(default)]
+    pub max_files_per_server: usize,
+    #[serde(default)]
+    pub skip_mismatched_natives: bool,
+    /// Leave zero-byte files out of the metadata (they are reported either way)
+    #[serde(default)]
+    pub skip_empty_files: bool,
+    #[serde(default = "super::defaults::scan_timeout_secs")]
+    pub scan_timeout_secs: u64,
+    #[serde(default)]
+    pub verify_on_serve: bool,
+    #[serde(default)]
+    pub precompress_brotli: bool,
+    #[serde(default)]
+    pub precompress_gzip: bool,
+    #[serde(default = "super::defaults::stability_delay_ms")]
+    pub stability_delay_ms: u64,
     #[serde(default = "super::defaults::config_reload_channel_size")]
     pub config_reload_channel_size: usize,
 }
 
+/// Top-level folder of a server, used to pick which files are preloaded into RAM
+pub use lighty_models::FileCategory;
+
+/// Servers listed in the 404 of an unknown server name (`available_servers`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AvailableServers {
+    /// Every listed server
+    All,
+    /// Listed servers with a name close to the requested one (typos, case)
+    #[default]
+    Similar,
+    /// None, the deployment is never enumerated
+    None,
+}
+
+/// When files of the `warm_categories` folders are loaded into RAM
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheWarming {
+    /// All at startup
+    #[default]
+    Eager,
+    /// On first request, by the disk fallback of `serve_file`
+    Lazy,
+    /// `.json` files at startup, the others on first request
+    MetadataOnly,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct BatchConfig {
     #[serde(default = "super::defaults::batch_size_default")]
@@ -131,6 +274,9 @@ pub struct ServerConfig {
     pub name: Arc<str>,
     #[serde(default = "super::defaults::server_enabled")]
     pub enabled: bool,
+    /// Hidden from the `/` listing, still served by name (e.g. staging modpacks)
+    #[serde(default)]
+    pub unlisted: bool,
     pub loader: String,
     pub loader_version: String,
     pub minecraft_version: String,
@@ -146,10 +292,118 @@ pub struct ServerConfig {
     pub enable_natives: bool,
     #[serde(default)]
     pub enable_assets: bool,
+    /// Publish every file of `extra_dir` as generic resources (`extra_files` in the metadata)
+    #[serde(default)]
+    pub enable_extra: bool,
+    /// Folder of each category, relative to the server folder (URLs and manifest paths keep the
+    /// default names, e.g. `libraries_dir = "libs"` is still served under `libraries/`)
+    #[serde(default = "super::defaults::client_dir")]
+    pub client_dir: String,
+    #[serde(default = "super::defaults::libraries_dir")]
+    pub libraries_dir: String,
+    #[serde(default = "super::defaults::mods_dir")]
+    pub mods_dir: String,
+    #[serde(default = "super::defaults::natives_dir")]
+    pub natives_dir: String,
+    #[serde(default = "super::defaults::assets_dir")]
+    pub assets_dir: String,
+    #[serde(default = "super::defaults::extra_dir")]
+    pub extra_dir: String,
+    /// Serve `/{name}/bundle.zip` (all files in one archive, zipped per request)
+    #[serde(default)]
+    pub enable_bundle: bool,
     #[serde(default)]
     pub game_args: Vec<String>,
     #[serde(default)]
     pub jvm_args: Vec<String>,
+    /// Polling rescan interval for this server in seconds (None = `cache.rescan_interval`, 0 = never polled)
+    #[serde(default)]
+    pub rescan_interval: Option<u64>,
+    #[serde(default)]
+    pub natives_naming: NativesNaming,
+    /// Archive entries launchers must not extract from natives (e.g. `META-INF/`)
+    #[serde(default)]
+    pub natives_extract_exclude: Vec<String>,
+}
+
+impl StorageSettings {
+    /// Whether files of `category` are synced to remote storage (`upload_*`)
+    pub fn uploads(&self, category: FileCategory) -> bool {
+        match category {
+            FileCategory::Client => self.upload_client,
+            FileCategory::Libraries => self.upload_libraries,
+            FileCategory::Mods => self.upload_mods,
+            FileCategory::Natives => self.upload_natives,
+            FileCategory::Assets => self.upload_assets,
+            FileCategory::Extra => self.upload_extra,
+        }
+    }
+
+    /// Categories kept off remote storage
+    pub fn local_categories(&self) -> Vec<FileCategory> {
+        FileCategory::ALL.into_iter().filter(|&category| !self.uploads(category)).collect()
+    }
+}
+
+impl ServerConfig {
+    /// Whether the `enable_*` flag of a category is set
+    pub fn category_enabled(&self, category: FileCategory) -> bool {
+        match category {
+            FileCategory::Client => self.enable_client,
+            FileCategory::Libraries => self.enable_libraries,
+            FileCategory::Mods => self.enable_mods,
+            FileCategory::Natives => self.enable_natives,
+            FileCategory::Assets => self.enable_assets,
+            FileCategory::Extra => self.enable_extra,
+        }
+    }
+
+    /// Folder of a category in the server directory (`*_dir`)
+    pub fn category_dir(&self, category: FileCategory) -> &str {
+        let dir = match category {
+            FileCategory::Client => &self.client_dir,
+            FileCategory::Libraries => &self.libraries_dir,
+            FileCategory::Mods => &self.mods_dir,
+            FileCategory::Natives => &self.natives_dir,
+            FileCategory::Assets => &self.assets_dir,
+            FileCategory::Extra => &self.extra_dir,
+        };
+        dir.trim_matches('/')
+    }
+
+    /// Path in the server directory of a category-prefixed path (e.g. "libraries/a.jar" → "libs/a.jar")
+    pub fn local_path(&self, path: &str) -> String {
+        match (FileCategory::from_path(path), path.split_once('/')) {
+            (Some(category), Some((_, rest))) if self.category_dir(category) != category.dir() => {
+                match self.category_dir(category) {
+                    "" => rest.to_string(),
+                    dir => format!("{}/{}", dir, rest),
+                }
+            }
+            _ => path.to_string(),
+        }
+    }
+
+    /// Whether a path relative to the server directory lies in the folder of another category
+    /// An `extra_dir` of "" publishes the server root, minus the other category folders
+    pub fn in_other_category_dir(&self, category: FileCategory, relative: &std::path::Path) -> bool {
+        FileCategory::ALL
+            .into_iter()
+            .filter(|other| *other != category)
+            .map(|other| self.category_dir(other))
+            .any(|dir| !dir.is_empty() && relative.starts_with(dir))
+    }
+}
+
+/// Naming scheme of the emitted `Native.name`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NativesNaming {
+    /// `natives:{os}:{file_name}`
+    #[default]
+    Lighty,
+    /// File name with a `-natives-{os}` classifier suffix (`lwjgl.jar` → `lwjgl-natives-linux.jar`)
+    Classifier,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -160,8 +414,39 @@ pub struct StorageSettings {
     pub keep_local_backup: bool,
     #[serde(default = "super::defaults::auto_upload")]
     pub auto_upload: bool,
+    #[serde(default = "super::defaults::upload_concurrency")]
+    pub upload_concurrency: usize,
+    #[serde(default = "super::defaults::skip_unchanged_uploads")]
+    pub skip_unchanged_uploads: bool,
+    #[serde(default = "super::defaults::init_retries")]
+    pub init_retries: u32,
+    /// Per category: sync to remote storage, or keep on this server and serve from `server.base_url`
+    #[serde(default = "super::defaults::upload_category")]
+    pub upload_client: bool,
+    #[serde(default = "super::defaults::upload_category")]
+    pub upload_libraries: bool,
+    #[serde(default = "super::defaults::upload_category")]
+    pub upload_mods: bool,
+    #[serde(default = "super::defaults::upload_category")]
+    pub upload_natives: bool,
+    #[serde(default = "super::defaults::upload_category")]
+    pub upload_assets: bool,
+    #[serde(default = "super::defaults::upload_category")]
+    pub upload_extra: bool,
     #[serde(default = "super::defaults::s3_settings")]
     pub s3: S3Settings,
+    #[serde(default = "super::defaults::gcs_settings")]
+    pub gcs: GcsSettings,
+}
+
+/// Directory a relative `base_path` is resolved against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BasePathRoot {
+    /// Directory containing the server binary
+    Executable,
+    /// Current working directory of the process
+    WorkingDir,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -169,6 +454,7 @@ pub struct StorageSettings {
 pub enum StorageBackend {
     Local,
     S3,
+    Gcs,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -201,6 +487,65 @@ pub struct S3Settings {
     pub bucket_prefix: Arc<str>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GcsSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "super::defaults::s3_bucket_name_arc")]
+    #[serde(deserialize_with = "deserialize_arc_str_default")]
+    #[serde(serialize_with = "serialize_arc_str")]
+    pub bucket_name: Arc<str>,
+    /// Path to the service account JSON key
+    #[serde(default)]
+    pub credentials_path: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_arc_str_default")]
+    #[serde(serialize_with = "serialize_arc_str")]
+    pub public_url: Arc<str>,
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_arc_str_default")]
+    #[serde(serialize_with = "serialize_arc_str")]
+    pub bucket_prefix: Arc<str>,
+}
+
+/// Native HTTPS: certificate and private key (PEM), reloaded when the files change
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TlsSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub cert_path: String,
+    #[serde(default)]
+    pub key_path: String,
+}
+
+/// Content types of served files
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MimeSettings {
+    /// File extension (without the dot) → MIME type, checked before the built-in guess
+    #[serde(default = "super::defaults::mime_overrides")]
+    pub overrides: HashMap<String, String>,
+}
+
+/// Servers fetched from a central config service, merged into `[[servers]]`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RemoteServersSettings {
+    /// JSON or TOML server list (empty = disabled)
+    #[serde(default)]
+    pub url: String,
+    /// Re-fetch period, reloading the config when the list changed (0 = startup and config reloads only)
+    #[serde(default = "super::defaults::remote_servers_refresh_interval_secs")]
+    pub refresh_interval_secs: u64,
+    #[serde(default = "super::defaults::remote_servers_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Sent as `Authorization: Bearer` when set
+    #[serde(default)]
+    pub bearer_token: String,
+    /// Fail startup and reloads when the list can't be fetched (false = carry on with the local or last fetched servers)
+    #[serde(default)]
+    pub required: bool,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct CdnSettings {
     #[serde(default)]
@@ -211,6 +556,27 @@ pub struct CdnSettings {
     pub zone_id: String,
     #[serde(default)]
     pub api_token: String,
+    #[serde(default)]
+    pub public_base_url: String,
+    /// Additional zones purged besides `zone_id` (same provider)
+    #[serde(default)]
+    pub zones: Vec<PurgeZone>,
+}
+
+/// A purge target, optionally limited to some servers (multi-zone setups)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PurgeZone {
+    pub zone_id: String,
+    pub api_token: String,
+    /// Servers purged in this zone (empty = every server)
+    #[serde(default)]
+    pub servers: Vec<String>,
+}
+
+impl PurgeZone {
+    pub fn covers(&self, server_name: &str) -> bool {
+        self.servers.is_empty() || self.servers.iter().any(|name| name == server_name)
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -225,6 +591,15 @@ pub struct CloudflareSettings {
     #[serde(deserialize_with = "deserialize_arc_str_default")]
     #[serde(serialize_with = "serialize_arc_str")]
     pub base_url: Arc<str>,
+    #[serde(default = "super::defaults::cloudflare_max_retries")]
+    pub max_retries: u32,
+    #[serde(default = "super::defaults::cloudflare_circuit_breaker_threshold")]
+    pub circuit_breaker_threshold: u32,
+    #[serde(default = "super::defaults::cloudflare_circuit_breaker_cooldown_secs")]
+    pub circuit_breaker_cooldown_secs: u64,
+    /// Additional zones purged besides `zone_id`
+    #[serde(default)]
+    pub zones: Vec<PurgeZone>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -249,4 +624,21 @@ pub struct HotReloadFilesSettings {
     pub enabled: bool,
     #[serde(default = "super::defaults::file_watcher_debounce_ms")]
     pub debounce_ms: u64,
+    #[serde(default = "super::defaults::file_watcher_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+    #[serde(default = "super::defaults::file_watcher_backend")]
+    pub watcher: WatcherBackend,
+    /// Also watch `base_path` itself to pick up newly created server folders
+    #[serde(default = "super::defaults::watch_base_path")]
+    pub watch_base_path: bool,
+}
+
+/// File change detection backend used by the config and server file watchers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WatcherBackend {
+    /// inotify/FSEvents/ReadDirectoryChanges, falling back to polling if unavailable
+    Native,
+    /// Periodic polling every `poll_interval_ms` (reliable on NFS/SMB mounts)
+    Poll,
 }
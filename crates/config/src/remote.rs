@@ -0,0 +1,195 @@
+use super::errors::ConfigError;
+use super::models::{Config, RemoteServersSettings};
+use std::time::Duration;
+
+type Result<T> = std::result::Result<T, ConfigError>;
+
+/// `[[servers]]` tables fetched from `[remote_servers] url`, compared between refreshes
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteServerList(pub(crate) Vec<toml::Table>);
+
+impl RemoteServerList {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl RemoteServersSettings {
+    pub fn enabled(&self) -> bool {
+        !self.url.trim().is_empty()
+    }
+
+    /// Fetches the server list: a JSON array, or a JSON/TOML document with a `servers` array
+    pub async fn fetch(&self) -> Result<RemoteServerList> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(self.timeout_secs))
+            .build()
+            .map_err(|e| ConfigError::RemoteServers(e.to_string()))?;
+
+        let mut request = client.get(self.url.trim());
+        if !self.bearer_token.is_empty() {
+            request = request.bearer_auth(&self.bearer_token);
+        }
+
+        let body = request
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| ConfigError::RemoteServers(format!("{}: {}", self.url, e)))?
+            .text()
+            .await
+            .map_err(|e| ConfigError::RemoteServers(format!("{}: {}", self.url, e)))?;
+
+        parse_server_list(&body).map_err(|e| ConfigError::RemoteServers(format!("{}: {}", self.url, e)))
+    }
+}
+
+impl Config {
+    /// Fetches the servers of `[remote_servers]` and appends them (nothing to do without a URL)
+    /// Kept out of the file loaders, so parsing a config never waits on the network
+    /// Unless `required`, a failed fetch falls back to `previous` (the list of the config being reloaded), or to the local servers only
+    pub async fn fetch_remote_servers(&mut self, previous: Option<&RemoteServerList>) -> Result<()> {
+        if !self.remote_servers.enabled() {
+            return Ok(());
+        }
+        let servers = match self.remote_servers.fetch().await {
+            Ok(servers) => {
+                tracing::info!("Fetched {} server(s) from {}", servers.len(), self.remote_servers.url);
+                servers
+            }
+            Err(e) if self.remote_servers.required => return Err(e),
+            Err(e) => match previous {
+                Some(previous) => {
+                    tracing::warn!("{}, keeping the {} previously fetched server(s)", e, previous.len());
+                    previous.clone()
+                }
+                None => {
+                    tracing::warn!("{}, continuing with the local servers only", e);
+                    return Ok(());
+                }
+            },
+        };
+        self.add_remote_servers(servers)
+    }
+}
+
+/// Reads the body as JSON first, then as TOML (a TOML document can also start with `[`)
+fn parse_server_list(body: &str) -> std::result::Result<RemoteServerList, String> {
+    let document = match serde_json::from_str::<toml::Value>(body) {
+        Ok(document) => document,
+        Err(json_error) => toml::from_str::<toml::Table>(body)
+            .map(toml::Value::Table)
+            .map_err(|toml_error| format!("neither JSON ({}) nor TOML ({})", json_error, toml_error))?,
+    };
+
+    let servers = match document {
+        toml::Value::Array(servers) => servers,
+        toml::Value::Table(mut table) => match table.remove("servers") {
+            Some(toml::Value::Array(servers)) => servers,
+            Some(_) => return Err("`servers` must be an array".to_string()),
+            None => Vec::new(),
+        },
+        _ => return Err("expected an array of servers or a `servers` array".to_string()),
+    };
+
+    servers
+        .into_iter()
+        .map(|server| match server {
+            toml::Value::Table(table) => Ok(table),
+            _ => Err("each server must be a table".to_string()),
+        })
+        .collect::<std::result::Result<_, _>>()
+        .map(RemoteServerList)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Config with an unreachable `[remote_servers]` URL (nothing listens on the discard port)
+    fn unreachable_config(required: bool) -> Config {
+        let mut config = Config::from_toml_str(&crate::scaffold_template(None)).unwrap();
+        config.remote_servers.url = "http://127.0.0.1:9/servers.json".to_string();
+        config.remote_servers.timeout_secs = 2;
+        config.remote_servers.required = required;
+        config
+    }
+
+    fn remote_list() -> RemoteServerList {
+        parse_server_list(
+            r#"[{"name": "creative", "loader": "vanilla", "loader_version": "", "minecraft_version": "1.21.1", "main_class": "Main", "java_version": 21}]"#,
+        )
+        .unwrap()
+    }
+
+    fn names(list: &RemoteServerList) -> Vec<&str> {
+        list.0.iter().filter_map(|server| server.get("name")?.as_str()).collect()
+    }
+
+    #[test]
+    fn parses_json_array() {
+        let list = parse_server_list(r#"[{"name": "survival", "java_version": 21}, {"name": "creative"}]"#).unwrap();
+        assert_eq!(names(&list), ["survival", "creative"]);
+        assert_eq!(list.0[0]["java_version"].as_integer(), Some(21));
+    }
+
+    #[test]
+    fn parses_json_servers_field() {
+        let list = parse_server_list(r#"{"version": 2, "servers": [{"name": "survival"}]}"#).unwrap();
+        assert_eq!(names(&list), ["survival"]);
+    }
+
+    #[test]
+    fn parses_toml_servers_array() {
+        // Starts with `[` like a JSON array, but only parses as TOML
+        let list = parse_server_list("[[servers]]\nname = \"survival\"\n\n[[servers]]\nname = \"creative\"\n").unwrap();
+        assert_eq!(names(&list), ["survival", "creative"]);
+    }
+
+    #[test]
+    fn document_without_servers_is_empty() {
+        assert!(parse_server_list("{}").unwrap().is_empty());
+        assert!(parse_server_list("title = \"no servers\"").unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_malformed_lists() {
+        assert!(parse_server_list(r#"{"servers": {"name": "survival"}}"#).is_err());
+        assert!(parse_server_list(r#"["survival"]"#).is_err());
+        assert!(parse_server_list("42").is_err());
+        assert!(parse_server_list("not a list").is_err());
+    }
+
+    #[tokio::test]
+    async fn unreachable_list_falls_back_to_local_servers() {
+        let mut config = unreachable_config(false);
+        assert!(config.remote_servers.enabled());
+
+        config.fetch_remote_servers(None).await.unwrap();
+        assert!(config.servers.is_empty());
+        assert_eq!(config.remote_server_list, None);
+    }
+
+    #[tokio::test]
+    async fn unreachable_list_keeps_the_previous_servers() {
+        let mut config = unreachable_config(false);
+        let previous = remote_list();
+
+        config.fetch_remote_servers(Some(&previous)).await.unwrap();
+        let names: Vec<&str> = config.servers.iter().map(|server| server.name.as_ref()).collect();
+        assert_eq!(names, ["creative"]);
+        assert_eq!(config.remote_server_list, Some(previous));
+    }
+
+    #[tokio::test]
+    async fn unreachable_required_list_fails() {
+        let mut config = unreachable_config(true);
+
+        let result = config.fetch_remote_servers(Some(&remote_list())).await;
+        assert!(matches!(result, Err(ConfigError::RemoteServers(_))));
+    }
+}
@@ -0,0 +1,44 @@
+use super::defaults::DEFAULT_CONFIG_TEMPLATE;
+use super::models::FileCategory;
+use std::fmt::Write;
+
+/// Default config template, with `base_path` set to `base_path` if given
+pub fn scaffold_template(base_path: Option<&str>) -> String {
+    let Some(base_path) = base_path else {
+        return DEFAULT_CONFIG_TEMPLATE.to_string();
+    };
+
+    let value = toml_edit::Value::from(base_path).to_string();
+    let mut template = String::with_capacity(DEFAULT_CONFIG_TEMPLATE.len());
+    for line in DEFAULT_CONFIG_TEMPLATE.lines() {
+        match line.strip_prefix("base_path = \"updater\"") {
+            Some(comment) => {
+                let setting = format!("base_path = {}", value.trim());
+                let _ = writeln!(template, "{:<36} {}", setting, comment.trim_start());
+            }
+            None => {
+                let _ = writeln!(template, "{}", line);
+            }
+        }
+    }
+    template
+}
+
+/// `[[servers]]` block for a server folder, `enable_*` set for the category folders it has
+/// Loader and versions are placeholders the operator has to review
+pub fn scaffold_server_block(name: &str, categories: &[FileCategory]) -> String {
+    let mut block = String::new();
+    let _ = writeln!(block);
+    let _ = writeln!(block, "[[servers]]");
+    let _ = writeln!(block, "name = {}", toml_edit::Value::from(name).to_string().trim());
+    let _ = writeln!(block, "enabled = true");
+    let _ = writeln!(block, "loader = \"vanilla\"                  # TODO: vanilla | forge | fabric | quilt | neoforge");
+    let _ = writeln!(block, "loader_version = \"\"                 # TODO: empty for vanilla");
+    let _ = writeln!(block, "minecraft_version = \"1.21\"          # TODO");
+    let _ = writeln!(block, "main_class = \"net.minecraft.client.main.Main\"");
+    let _ = writeln!(block, "java_version = 21");
+    for category in FileCategory::ALL {
+        let _ = writeln!(block, "enable_{} = {}", category.dir(), categories.contains(&category));
+    }
+    block
+}
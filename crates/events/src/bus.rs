@@ -71,6 +71,20 @@ impl EventBus {
             AppEvent::InitialScanStarted => {
                 println!("  {} Scanning servers...", "→".dimmed());
             }
+            AppEvent::ScanFileFailed { server, path, reason } => {
+                tracing::warn!("Scan of server {}: skipped {}: {}", server, path, reason);
+            }
+            AppEvent::EmptyFileScanned { server, path, skipped } => {
+                let action = if skipped { "skipped" } else { "published" };
+                tracing::warn!("Scan of server {}: {} is empty (0 bytes), possibly an incomplete transfer, {}", server, path, action);
+            }
+            AppEvent::ScanProgress { completed, total } => {
+                println!("  {} {}/{} servers scanned",
+                    "→".dimmed(),
+                    completed.to_string().cyan(),
+                    total
+                );
+            }
 
             // Cache events
             AppEvent::CacheNew { server } => {
@@ -100,6 +114,13 @@ impl EventBus {
             AppEvent::ContinuousScanEnabled => {
                 println!("  {} Continuous scan", "↻".blue());
             }
+            AppEvent::FileWatcherDegraded { error, poll_interval_ms } => {
+                println!("  {} Native file watcher unavailable ({}), polling every {}ms",
+                    "⚠".yellow(),
+                    error.dimmed(),
+                    poll_interval_ms.to_string().cyan()
+                );
+            }
 
             // Errors
             AppEvent::Error { context, error } => {
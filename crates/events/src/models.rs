@@ -25,6 +25,11 @@ pub enum AppEvent {
     ScanStarted { server: String },
     ScanCompleted { server: String, duration: Duration },
     InitialScanStarted,
+    ScanProgress { completed: usize, total: usize },
+    /// A file left out of the metadata because it could not be read or hashed
+    ScanFileFailed { server: String, path: String, reason: String },
+    /// A zero-byte file, published or left out by `cache.skip_empty_files`
+    EmptyFileScanned { server: String, path: String, skipped: bool },
 
     // Cache events
     CacheNew { server: String },
@@ -38,6 +43,7 @@ pub enum AppEvent {
     // Auto-scan
     AutoScanEnabled { interval: u64 },
     ContinuousScanEnabled,
+    FileWatcherDegraded { error: String, poll_interval_ms: u64 },
 
     // Errors
     Error { context: String, error: String },
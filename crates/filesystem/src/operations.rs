@@ -1,28 +1,32 @@
 use super::models::FileSystem;
 use anyhow::Result;
+use lighty_models::FileCategory;
 use std::path::{Path, PathBuf};
 use tokio::fs;
 
 impl FileSystem {
-    pub async fn ensure_server_structure(
+    /// Creates the server folder and its category folders, `category_dir` naming each one
+    /// (the server's layout, e.g. `libs` for libraries); the opt-in extra folder is left out
+    pub async fn ensure_server_structure<'a>(
         base_path: &str,
         server_folder: &str,
+        category_dir: impl Fn(FileCategory) -> &'a str,
     ) -> Result<PathBuf> {
         let full_path = PathBuf::from(base_path).join(server_folder);
         let abs_path = Self::get_absolute_path(&full_path)?;
 
         Self::create_directory(&abs_path, "Root directory").await?;
-        Self::create_directory(&abs_path.join("client"), "Client directory").await?;
-        Self::create_directory(&abs_path.join("libraries"), "Libraries directory").await?;
-        Self::create_directory(&abs_path.join("mods"), "Mods directory").await?;
+        Self::create_directory(&abs_path.join(category_dir(FileCategory::Client)), "Client directory").await?;
+        Self::create_directory(&abs_path.join(category_dir(FileCategory::Libraries)), "Libraries directory").await?;
+        Self::create_directory(&abs_path.join(category_dir(FileCategory::Mods)), "Mods directory").await?;
 
-        let natives_path = abs_path.join("natives");
+        let natives_path = abs_path.join(category_dir(FileCategory::Natives));
         Self::create_directory(&natives_path, "Natives directory").await?;
         Self::create_directory(&natives_path.join("windows"), "Natives/Windows").await?;
         Self::create_directory(&natives_path.join("linux"), "Natives/Linux").await?;
         Self::create_directory(&natives_path.join("macos"), "Natives/MacOS").await?;
 
-        let assets_path = abs_path.join("assets");
+        let assets_path = abs_path.join(category_dir(FileCategory::Assets));
         Self::create_directory(&assets_path, "Assets directory").await?;
 
         Ok(abs_path)
@@ -32,6 +36,20 @@ impl FileSystem {
         PathBuf::from(base_path).join(server_folder)
     }
 
+    /// Names of the folders directly under `path`, sorted (hidden ones like `.git` skipped)
+    pub async fn list_subdirectories(path: &Path) -> Result<Vec<String>> {
+        let mut names = Vec::new();
+        let mut entries = fs::read_dir(path).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.starts_with('.') && entry.file_type().await?.is_dir() {
+                names.push(name);
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
     async fn create_directory(path: &Path, description: &str) -> Result<()> {
         if !path.exists() {
             fs::create_dir_all(path).await?;
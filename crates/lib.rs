@@ -10,6 +10,9 @@ pub use lighty_cache::*;
 pub use lighty_watcher::*;
 pub use lighty_api::*;
 
+// Both lighty_utils and lighty_api have a public `errors` module: keep the utils one at the root
+pub use lighty_utils::errors;
+
 /// Prelude module for convenient imports
 pub mod prelude {
     // Core models
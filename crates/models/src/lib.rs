@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VersionBuilder {
@@ -10,9 +10,22 @@ pub struct VersionBuilder {
     pub mods: Vec<Mod>,
     pub natives: Option<Vec<Native>>,
     pub client: Option<Client>,
+    /// Supplementary files outside the launcher categories (config files, pack metadata...)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_files: Vec<ExtraFile>,
+    /// Files left out by the last scan (`path: reason`), so launchers can warn of an incomplete pack
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scan_warnings: Vec<String>,
+    /// Serialized last: asset pages are cut out of the pre-serialized JSON (`AssetIndex`)
     pub assets: Vec<Asset>,
     #[serde(skip)]
     pub url_to_path_map: HashMap<String, String>,
+    /// Category-prefixed path → file metadata, built alongside `url_to_path_map`
+    #[serde(skip)]
+    pub path_to_meta_map: HashMap<String, FileMeta>,
+    /// Lowercased SHA1 → category-prefixed paths of every file with that content, for content-addressed lookups
+    #[serde(skip)]
+    pub sha1_to_path_map: HashMap<String, BTreeSet<String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,6 +55,11 @@ pub struct Library {
     pub sha1: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub size: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crc32: Option<String>,
+    /// Modification time in Unix seconds (`cache.include_mtime`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mtime: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -52,6 +70,19 @@ pub struct Native {
     pub sha1: String,
     pub size: u64,
     pub os: String,  // "windows", "linux", or "macos"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crc32: Option<String>,
+    /// Modification time in Unix seconds (`cache.include_mtime`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mtime: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extract: Option<NativeExtract>,
+}
+
+/// Extraction rules of a natives archive (same shape as Mojang's `extract`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NativeExtract {
+    pub exclude: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -61,6 +92,11 @@ pub struct Client {
     pub path: String,
     pub sha1: String,
     pub size: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crc32: Option<String>,
+    /// Modification time in Unix seconds (`cache.include_mtime`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mtime: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -71,6 +107,25 @@ pub struct Asset {
     pub url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crc32: Option<String>,
+    /// Modification time in Unix seconds (`cache.include_mtime`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mtime: Option<u64>,
+}
+
+/// File of the extra category, `path` relative to the server's extra folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtraFile {
+    pub url: String,
+    pub path: String,
+    pub sha1: String,
+    pub size: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crc32: Option<String>,
+    /// Modification time in Unix seconds (`cache.include_mtime`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mtime: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -84,61 +139,440 @@ pub struct Mod {
     pub sha1: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub size: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crc32: Option<String>,
+    /// Modification time in Unix seconds (`cache.include_mtime`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mtime: Option<u64>,
+}
+
+/// Flat manifest entry, across all file categories
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub path: String,  // Category-prefixed path (e.g. "mods/mod.jar")
+    pub url: String,
+    pub sha1: String,
+    pub size: u64,
+}
+
+/// Node of a server's file tree, folders nested by the segments of category-prefixed paths
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum TreeNode {
+    Directory {
+        name: String,
+        /// Total size of the files below this folder
+        size: u64,
+        children: Vec<TreeNode>,
+    },
+    File {
+        name: String,
+        url: String,
+        sha1: String,
+        size: u64,
+    },
+}
+
+/// Folder being assembled by `VersionBuilder::file_tree`, sorted by name
+#[derive(Default)]
+struct TreeFolder {
+    folders: std::collections::BTreeMap<String, TreeFolder>,
+    files: Vec<TreeNode>,
+}
+
+impl TreeFolder {
+    fn insert(&mut self, path: &str, entry: &ManifestEntry) {
+        match path.split_once('/') {
+            Some((folder, rest)) => self.folders.entry(folder.to_string()).or_default().insert(rest, entry),
+            None => self.files.push(TreeNode::File {
+                name: path.to_string(),
+                url: entry.url.clone(),
+                sha1: entry.sha1.clone(),
+                size: entry.size,
+            }),
+        }
+    }
+
+    /// Folders first, then files, each in name order (the manifest is already sorted by path)
+    fn into_children(self) -> Vec<TreeNode> {
+        let mut children: Vec<TreeNode> = self
+            .folders
+            .into_iter()
+            .map(|(name, folder)| {
+                let children = folder.into_children();
+                TreeNode::Directory {
+                    name,
+                    size: children.iter().map(TreeNode::size).sum(),
+                    children,
+                }
+            })
+            .collect();
+        children.extend(self.files);
+        children
+    }
+}
+
+impl TreeNode {
+    pub fn size(&self) -> u64 {
+        match self {
+            Self::Directory { size, .. } | Self::File { size, .. } => *size,
+        }
+    }
+}
+
+/// Top-level folder of a server, the first segment of a category-prefixed path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileCategory {
+    Client,
+    Libraries,
+    Mods,
+    Natives,
+    Assets,
+    Extra,
+}
+
+impl FileCategory {
+    pub const ALL: [Self; 6] = [Self::Client, Self::Libraries, Self::Mods, Self::Natives, Self::Assets, Self::Extra];
+
+    /// Prefix of the category's paths, and its folder in a server directory unless the server
+    /// config points the category elsewhere (`libraries_dir`, ...)
+    pub fn dir(self) -> &'static str {
+        match self {
+            Self::Client => "client",
+            Self::Libraries => "libraries",
+            Self::Mods => "mods",
+            Self::Natives => "natives",
+            Self::Assets => "assets",
+            Self::Extra => "extra",
+        }
+    }
+
+    /// Category of a category-prefixed path (e.g. "mods/mod.jar")
+    pub fn from_path(path: &str) -> Option<Self> {
+        let dir = path.split('/').next()?;
+        Self::ALL.into_iter().find(|category| category.dir() == dir)
+    }
 }
 
+/// Expected checksum and size of a file, looked up by its category-prefixed path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileMeta {
+    pub url: String,
+    pub sha1: String,
+    pub size: u64,
+    pub category: FileCategory,
+}
 
 impl VersionBuilder {
-    /// Builds the url→path lookup map for O(1) file resolution
+    /// Builds the url→path, path→metadata and sha1→path lookup maps for O(1) file resolution
     pub fn build_url_map(&mut self) {
-        let mut map = HashMap::new();
+        let mut url_map = HashMap::new();
+        let mut meta_map = HashMap::new();
+        let mut add = |url: &str, category, path: &str, sha1: Option<&String>, size| {
+            insert_file(&mut url_map, &mut meta_map, url, category, path, sha1, size);
+        };
 
         // Add client
         if let Some(ref client) = self.client {
-            map.insert(client.url.clone(), format!("client/{}", client.path));
+            add(&client.url, FileCategory::Client, &client.path, Some(&client.sha1), Some(client.size));
         }
 
         // Add libraries
         for lib in &self.libraries {
             if let (Some(ref url), Some(ref path)) = (&lib.url, &lib.path) {
-                map.insert(url.clone(), format!("libraries/{}", path));
+                add(url, FileCategory::Libraries, path, lib.sha1.as_ref(), lib.size);
             }
         }
 
         // Add mods
         for mod_item in &self.mods {
             if let (Some(ref url), Some(ref path)) = (&mod_item.url, &mod_item.path) {
-                map.insert(url.clone(), format!("mods/{}", path));
+                add(url, FileCategory::Mods, path, mod_item.sha1.as_ref(), mod_item.size);
             }
         }
 
         // Add natives
         if let Some(ref natives) = self.natives {
             for native in natives {
-                map.insert(native.url.clone(), format!("natives/{}", native.path));
+                add(&native.url, FileCategory::Natives, &native.path, Some(&native.sha1), Some(native.size));
             }
         }
 
         // Add assets
         for asset in &self.assets {
             if let (Some(ref url), Some(ref path)) = (&asset.url, &asset.path) {
-                map.insert(url.clone(), format!("assets/{}", path));
+                add(url, FileCategory::Assets, path, Some(&asset.hash), Some(asset.size));
+            }
+        }
+
+        // Add extra files
+        for extra in &self.extra_files {
+            add(&extra.url, FileCategory::Extra, &extra.path, Some(&extra.sha1), Some(extra.size));
+        }
+
+        // Identical files share a hash: any of their paths serves the same content
+        let mut sha1_map: HashMap<String, BTreeSet<String>> = HashMap::new();
+        for (path, meta) in &meta_map {
+            sha1_map.entry(meta.sha1.to_lowercase()).or_default().insert(path.clone());
+        }
+        self.sha1_to_path_map = sha1_map;
+        self.url_to_path_map = url_map;
+        self.path_to_meta_map = meta_map;
+    }
+
+    /// Looks up the metadata of a file by its category-prefixed path (e.g. "mods/mod.jar")
+    pub fn file_meta(&self, path: &str) -> Option<&FileMeta> {
+        self.path_to_meta_map.get(path)
+    }
+
+    /// Looks up the category-prefixed paths of every file with this SHA1 (case-insensitive), sorted
+    pub fn paths_for_sha1(&self, sha1: &str) -> impl Iterator<Item = &String> {
+        self.sha1_to_path_map.get(&sha1.to_lowercase()).into_iter().flatten()
+    }
+
+    /// Builds a flat list of all files, sorted by path, for clients diffing their local install
+    pub fn manifest(&self) -> Vec<ManifestEntry> {
+        let mut entries = Vec::new();
+
+        if let Some(ref client) = self.client {
+            entries.push(ManifestEntry {
+                path: format!("client/{}", client.path),
+                url: client.url.clone(),
+                sha1: client.sha1.clone(),
+                size: client.size,
+            });
+        }
+
+        for lib in &self.libraries {
+            if let (Some(url), Some(path), Some(sha1), Some(size)) = (&lib.url, &lib.path, &lib.sha1, lib.size) {
+                entries.push(ManifestEntry {
+                    path: format!("libraries/{}", path),
+                    url: url.clone(),
+                    sha1: sha1.clone(),
+                    size,
+                });
+            }
+        }
+
+        for mod_item in &self.mods {
+            if let (Some(url), Some(path), Some(sha1), Some(size)) = (&mod_item.url, &mod_item.path, &mod_item.sha1, mod_item.size) {
+                entries.push(ManifestEntry {
+                    path: format!("mods/{}", path),
+                    url: url.clone(),
+                    sha1: sha1.clone(),
+                    size,
+                });
+            }
+        }
+
+        if let Some(ref natives) = self.natives {
+            for native in natives {
+                entries.push(ManifestEntry {
+                    path: format!("natives/{}", native.path),
+                    url: native.url.clone(),
+                    sha1: native.sha1.clone(),
+                    size: native.size,
+                });
             }
         }
 
-        self.url_to_path_map = map;
+        for asset in &self.assets {
+            if let (Some(url), Some(path)) = (&asset.url, &asset.path) {
+                entries.push(ManifestEntry {
+                    path: format!("assets/{}", path),
+                    url: url.clone(),
+                    sha1: asset.hash.clone(),
+                    size: asset.size,
+                });
+            }
+        }
+
+        for extra in &self.extra_files {
+            entries.push(ManifestEntry {
+                path: format!("extra/{}", extra.path),
+                url: extra.url.clone(),
+                sha1: extra.sha1.clone(),
+                size: extra.size,
+            });
+        }
+
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        entries
     }
 
-    /// Incrementally add a URL mapping
-    pub fn add_url_mapping(&mut self, url: String, path: String) {
+    /// Builds the files as a folder hierarchy (one top-level folder per category) from the manifest
+    pub fn file_tree(&self) -> Vec<TreeNode> {
+        let mut root = TreeFolder::default();
+        for entry in self.manifest() {
+            root.insert(&entry.path, &entry);
+        }
+        root.into_children()
+    }
+
+    /// Returns a copy with every file URL starting with `origin` re-based onto `public_base`
+    /// The lookup maps are left empty (the copy is only meant to be published)
+    pub fn with_rebased_urls(&self, origin: &str, public_base: &str) -> VersionBuilder {
+        let mut rebased = VersionBuilder {
+            main_class: self.main_class.clone(),
+            java_version: self.java_version.clone(),
+            arguments: self.arguments.clone(),
+            libraries: self.libraries.clone(),
+            mods: self.mods.clone(),
+            natives: self.natives.clone(),
+            client: self.client.clone(),
+            extra_files: self.extra_files.clone(),
+            assets: self.assets.clone(),
+            scan_warnings: self.scan_warnings.clone(),
+            url_to_path_map: HashMap::new(),
+            path_to_meta_map: HashMap::new(),
+            sha1_to_path_map: HashMap::new(),
+        };
+        rebased.rebase_urls(&FileCategory::ALL, origin, public_base);
+        rebased
+    }
+
+    /// Returns a copy holding only the files of `categories` (other categories are emptied)
+    /// The lookup maps are left empty (the copy is only meant to be published)
+    pub fn with_categories(&self, categories: &[FileCategory]) -> VersionBuilder {
+        let keeps = |category| categories.contains(&category);
+        VersionBuilder {
+            main_class: self.main_class.clone(),
+            java_version: self.java_version.clone(),
+            arguments: self.arguments.clone(),
+            libraries: if keeps(FileCategory::Libraries) { self.libraries.clone() } else { Vec::new() },
+            mods: if keeps(FileCategory::Mods) { self.mods.clone() } else { Vec::new() },
+            natives: self.natives.clone().filter(|_| keeps(FileCategory::Natives)),
+            client: self.client.clone().filter(|_| keeps(FileCategory::Client)),
+            extra_files: if keeps(FileCategory::Extra) { self.extra_files.clone() } else { Vec::new() },
+            assets: if keeps(FileCategory::Assets) { self.assets.clone() } else { Vec::new() },
+            scan_warnings: self.scan_warnings.clone(),
+            url_to_path_map: HashMap::new(),
+            path_to_meta_map: HashMap::new(),
+            sha1_to_path_map: HashMap::new(),
+        }
+    }
+
+    /// Re-bases the URLs of `categories` starting with `origin` onto `base`, in place
+    /// The lookup maps are not updated: rebuild them with `build_url_map`
+    pub fn rebase_urls(&mut self, categories: &[FileCategory], origin: &str, base: &str) {
+        let rebase = |url: &mut String| {
+            if let Some(rest) = url.strip_prefix(origin) {
+                *url = format!("{}{}", base, rest);
+            }
+        };
+
+        for category in categories {
+            match category {
+                FileCategory::Client => {
+                    if let Some(ref mut client) = self.client {
+                        rebase(&mut client.url);
+                    }
+                }
+                FileCategory::Libraries => {
+                    for lib in &mut self.libraries {
+                        if let Some(ref mut url) = lib.url {
+                            rebase(url);
+                        }
+                    }
+                }
+                FileCategory::Mods => {
+                    for mod_item in &mut self.mods {
+                        if let Some(ref mut url) = mod_item.url {
+                            rebase(url);
+                        }
+                    }
+                }
+                FileCategory::Natives => {
+                    for native in self.natives.iter_mut().flatten() {
+                        rebase(&mut native.url);
+                    }
+                }
+                FileCategory::Assets => {
+                    for asset in &mut self.assets {
+                        if let Some(ref mut url) = asset.url {
+                            rebase(url);
+                        }
+                    }
+                }
+                FileCategory::Extra => {
+                    for extra in &mut self.extra_files {
+                        rebase(&mut extra.url);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Incrementally add a file (`path` relative to its category folder, as in the model)
+    /// Mapped exactly like `build_url_map` does, so incremental updates match a full rebuild
+    pub fn add_url_mapping(&mut self, url: &str, category: FileCategory, path: &str, sha1: Option<&String>, size: Option<u64>) {
         if !url.is_empty() {
-            self.url_to_path_map.insert(url, path);
+            let prefixed_path = format!("{}/{}", category.dir(), path);
+            // A file published under another URL (e.g. its category moved off remote storage) drops the old one
+            if let Some(previous) = self.path_to_meta_map.get(&prefixed_path).filter(|meta| meta.url != url) {
+                self.url_to_path_map.remove(&previous.url);
+            }
+            self.unindex_sha1(&prefixed_path);
+            insert_file(&mut self.url_to_path_map, &mut self.path_to_meta_map, url, category, path, sha1, size);
+            if let Some(meta) = self.path_to_meta_map.get(&prefixed_path) {
+                self.sha1_to_path_map.entry(meta.sha1.to_lowercase()).or_default().insert(prefixed_path);
+            }
         }
     }
 
-    /// Incrementally remove a URL mapping
+    /// Incrementally remove a URL mapping (and the metadata of the path it pointed to)
     pub fn remove_url_mapping(&mut self, url: &str) {
         if !url.is_empty() {
-            self.url_to_path_map.remove(url);
+            if let Some(path) = self.url_to_path_map.remove(url) {
+                self.unindex_sha1(&path);
+                self.path_to_meta_map.remove(&path);
+            }
+        }
+    }
+
+    /// Drops a path about to change or go away from the sha1→paths index
+    /// Other files with the same content stay indexed under the hash
+    fn unindex_sha1(&mut self, path: &str) {
+        let Some(meta) = self.path_to_meta_map.get(path) else {
+            return;
+        };
+        let sha1 = meta.sha1.to_lowercase();
+        if let Some(paths) = self.sha1_to_path_map.get_mut(&sha1) {
+            paths.remove(path);
+            if paths.is_empty() {
+                self.sha1_to_path_map.remove(&sha1);
+            }
+        }
+    }
+}
+
+/// Maps `url` to the category-prefixed path, and the path to its metadata when SHA1 and size are known
+fn insert_file(
+    url_map: &mut HashMap<String, String>,
+    meta_map: &mut HashMap<String, FileMeta>,
+    url: &str,
+    category: FileCategory,
+    path: &str,
+    sha1: Option<&String>,
+    size: Option<u64>,
+) {
+    let path = format!("{}/{}", category.dir(), path);
+    match (sha1, size) {
+        (Some(sha1), Some(size)) => {
+            meta_map.insert(
+                path.clone(),
+                FileMeta {
+                    url: url.to_string(),
+                    sha1: sha1.clone(),
+                    size,
+                    category,
+                },
+            );
+        }
+        _ => {
+            meta_map.remove(&path);
         }
     }
+    url_map.insert(url.to_string(), path);
 }
@@ -0,0 +1,177 @@
+use lighty_config::{LoaderTemplate, LoaderTemplates, ServerConfig};
+use lighty_models::Arguments;
+use super::models::ServerScanner;
+
+impl ServerScanner {
+    /// Launch-ready arguments of a server: its loader's template followed by `game_args`/`jvm_args`
+    /// Template options the server already sets are left out, so the server's values win
+    pub fn launch_arguments(config: &ServerConfig, templates: &LoaderTemplates) -> Arguments {
+        let Some(template) = loader_template(&config.loader, templates) else {
+            return Arguments {
+                game: config.game_args.clone(),
+                jvm: config.jvm_args.clone(),
+            };
+        };
+
+        Arguments {
+            game: merge(&template.game, &config.game_args, config),
+            jvm: merge(&template.jvm, &config.jvm_args, config),
+        }
+    }
+}
+
+/// Configured template of `loader` (loader names are case-insensitive)
+/// No template is built in, the generated config ships the fabric/forge/neoforge ones
+fn loader_template<'a>(loader: &str, templates: &'a LoaderTemplates) -> Option<&'a LoaderTemplate> {
+    templates
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(loader))
+        .map(|(_, template)| template)
+}
+
+/// JVM options given once per value, a server setting one doesn't replace the template's
+const REPEATABLE_OPTIONS: [&str; 4] = ["--add-opens", "--add-exports", "--add-reads", "--add-modules"];
+
+/// Single-dash JVM options followed by a separate value (double-dash options all are)
+const SINGLE_DASH_VALUE_OPTIONS: [&str; 4] = ["-cp", "-classpath", "-p", "-m"];
+
+/// Template arguments (placeholders filled) followed by the server's own
+fn merge(template: &[String], own: &[String], config: &ServerConfig) -> Vec<String> {
+    let template: Vec<String> = template.iter().map(|arg| fill_placeholders(arg, config)).collect();
+    let own_groups = option_groups(own);
+
+    let mut merged = Vec::with_capacity(template.len() + own.len());
+    for group in option_groups(&template) {
+        // An option and its value (`--fml.mcVersion 1.20.1`) are kept or dropped together
+        let key = option_key(&group[0]);
+        let overridden = if REPEATABLE_OPTIONS.contains(&key) {
+            own_groups.contains(&group)
+        } else {
+            own_groups.iter().any(|own_group| option_key(&own_group[0]) == key)
+        };
+        if !overridden {
+            merged.extend_from_slice(group);
+        }
+    }
+
+    merged.extend(own.iter().cloned());
+    merged
+}
+
+/// Splits arguments into options with their separate value, if any (`--launchTarget forgeclient`), and lone arguments
+fn option_groups(args: &[String]) -> Vec<&[String]> {
+    let mut groups = Vec::with_capacity(args.len());
+    let mut i = 0;
+    while i < args.len() {
+        let takes_value = takes_value(&args[i]) && args.get(i + 1).is_some_and(|next| !next.starts_with('-'));
+        let len = if takes_value { 2 } else { 1 };
+        groups.push(&args[i..i + len]);
+        i += len;
+    }
+    groups
+}
+
+/// Whether an option is followed by its value as the next argument
+/// Single-dash JVM options are flags or carry their value (`-Xmx4G`, `-Dfoo=bar`), except a few
+fn takes_value(arg: &str) -> bool {
+    !arg.contains('=') && (arg.starts_with("--") || SINGLE_DASH_VALUE_OPTIONS.contains(&arg))
+}
+
+/// Option name of an argument (`-Dfoo=bar` → `-Dfoo`)
+fn option_key(arg: &str) -> &str {
+    arg.split('=').next().unwrap_or(arg)
+}
+
+/// Replaces the server placeholders, others (e.g. `${auth_player_name}`) are left to the launcher
+fn fill_placeholders(arg: &str, config: &ServerConfig) -> String {
+    arg.replace("${loader_version}", &config.loader_version)
+        .replace("${minecraft_version}", &config.minecraft_version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lighty_config::Config;
+
+    fn server() -> ServerConfig {
+        let content = format!(
+            "{}\n[[servers]]\nname = \"survival\"\nloader = \"forge\"\nloader_version = \"47.2.0\"\nminecraft_version = \"1.20.1\"\nmain_class = \"Main\"\njava_version = 17\n",
+            lighty_config::scaffold_template(None)
+        );
+        let config = Config::from_toml_str(&content).unwrap();
+        config.servers[0].as_ref().clone()
+    }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn option_key_strips_the_value() {
+        assert_eq!(option_key("-Dfoo=bar"), "-Dfoo");
+        assert_eq!(option_key("--add-opens=java.base/java.lang=ALL-UNNAMED"), "--add-opens");
+        assert_eq!(option_key("--launchTarget"), "--launchTarget");
+        assert_eq!(option_key("forgeclient"), "forgeclient");
+    }
+
+    #[test]
+    fn fill_placeholders_leaves_launcher_ones() {
+        let server = server();
+        assert_eq!(fill_placeholders("${loader_version}", &server), "47.2.0");
+        assert_eq!(fill_placeholders("forge-${minecraft_version}-${loader_version}", &server), "forge-1.20.1-47.2.0");
+        assert_eq!(fill_placeholders("${auth_player_name}", &server), "${auth_player_name}");
+    }
+
+    #[test]
+    fn merge_lets_the_server_override_an_option_and_its_value() {
+        let template = args(&["--launchTarget", "forgeclient", "--fml.mcVersion", "${minecraft_version}"]);
+        let own = args(&["--fml.mcVersion", "1.20.2"]);
+
+        let merged = merge(&template, &own, &server());
+        assert_eq!(merged, args(&["--launchTarget", "forgeclient", "--fml.mcVersion", "1.20.2"]));
+    }
+
+    #[test]
+    fn merge_keeps_repeated_options() {
+        let template = args(&[
+            "--add-opens", "java.base/java.lang=ALL-UNNAMED",
+            "--add-opens", "java.base/java.util=ALL-UNNAMED",
+            "--add-exports", "java.base/sun.security.util=ALL-UNNAMED",
+        ]);
+        let own = args(&["--add-opens", "java.base/java.io=ALL-UNNAMED", "--add-opens", "java.base/java.util=ALL-UNNAMED"]);
+
+        let merged = merge(&template, &own, &server());
+        assert_eq!(
+            merged,
+            args(&[
+                "--add-opens", "java.base/java.lang=ALL-UNNAMED",
+                "--add-exports", "java.base/sun.security.util=ALL-UNNAMED",
+                "--add-opens", "java.base/java.io=ALL-UNNAMED",
+                "--add-opens", "java.base/java.util=ALL-UNNAMED",
+            ])
+        );
+    }
+
+    #[test]
+    fn merge_only_pairs_options_that_take_a_value() {
+        // `-XstartOnFirstThread` is a flag: the next argument stands alone
+        let template = args(&["-XstartOnFirstThread", "net.minecraft.client.main.Main", "-cp", "${classpath}"]);
+        let own = args(&["-XstartOnFirstThread"]);
+
+        let merged = merge(&template, &own, &server());
+        assert_eq!(merged, args(&["net.minecraft.client.main.Main", "-cp", "${classpath}", "-XstartOnFirstThread"]));
+
+        // `-cp` takes its value with it
+        let merged = merge(&template, &args(&["-cp", "libs/*"]), &server());
+        assert_eq!(merged, args(&["-XstartOnFirstThread", "net.minecraft.client.main.Main", "-cp", "libs/*"]));
+    }
+
+    #[test]
+    fn merge_without_overrides_keeps_the_template_first() {
+        let template = args(&["--fml.forgeVersion", "${loader_version}", "-Dfml.ignoreInvalidMinecraftCertificates=true"]);
+        let own = args(&["-Xmx4G"]);
+
+        let merged = merge(&template, &own, &server());
+        assert_eq!(merged, args(&["--fml.forgeVersion", "47.2.0", "-Dfml.ignoreInvalidMinecraftCertificates=true", "-Xmx4G"]));
+    }
+}
@@ -1,73 +1,75 @@
 use super::errors::ScanError;
+use super::models::{ScanOptions, ScanFailure};
+use super::utils::{file_checksums, file_mtime, partition_results, walk_files};
 use lighty_models::Asset;
 use lighty_storage::StorageBackend;
-use lighty_utils::{normalize_path, compute_sha1_with_size};
+use lighty_utils::normalize_path;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use walkdir::WalkDir;
 use tokio::sync::Semaphore;
 use futures::stream::{self, StreamExt};
 
 type Result<T> = std::result::Result<T, ScanError>;
 
-pub async fn scan_assets(path: &Path, server: &str, storage: &Arc<dyn StorageBackend>, concurrency: usize, buffer_size: usize) -> Result<Vec<Asset>> {
-    let assets_dir = path.join("assets");
+pub async fn scan_assets(assets_dir: &Path, server: &str, storage: &Arc<dyn StorageBackend>, concurrency: usize, options: &ScanOptions) -> Result<(Vec<Asset>, Vec<ScanFailure>)> {
+    let assets_dir = assets_dir.to_path_buf();
 
     if !assets_dir.exists() {
-        return Ok(vec![]);
+        return Ok((vec![], vec![]));
     }
 
     // Collect all file paths in assets directory
-    let file_paths: Vec<PathBuf> = WalkDir::new(&assets_dir)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().is_file())
-        .map(|e| e.path().to_path_buf())
-        .collect();
+    let (file_paths, unreadable) = walk_files(&assets_dir, Path::is_file);
 
     // Create semaphore to control concurrency
     let semaphore = Arc::new(Semaphore::new(concurrency));
     let server = server.to_string();
     let storage = Arc::clone(storage);
+    let options = *options;
 
     // Process all assets concurrently with semaphore control
-    let results: Vec<Result<Asset>> = stream::iter(file_paths)
+    let mut results: Vec<(PathBuf, Result<Asset>)> = stream::iter(file_paths)
         .map(|file_path| {
             let sem = Arc::clone(&semaphore);
             let assets_dir = assets_dir.clone();
             let server = server.clone();
             let storage = Arc::clone(&storage);
-            let buffer_size = buffer_size;
 
             async move {
                 // Acquire semaphore permit
                 let _permit = sem.acquire().await.unwrap();
 
-                let relative = file_path
-                    .strip_prefix(&assets_dir)
-                    .map_err(|e| ScanError::InvalidMetadata(format!("Failed to strip prefix: {}", e)))?;
+                let result: Result<Asset> = async {
+                    let relative = file_path
+                        .strip_prefix(&assets_dir)
+                        .map_err(|e| ScanError::InvalidMetadata(format!("Failed to strip prefix: {}", e)))?;
 
-                // Async hash computation
-                let (hash, size) = compute_sha1_with_size(&file_path, buffer_size).await?;
+                    // Async hash computation
+                    let checksums = file_checksums(&file_path, &options).await?;
+                    let mtime = file_mtime(&file_path, options.include_mtime).await?;
 
-                let url_path = normalize_path(relative);
-                let remote_key = format!("{}/{}", server, url_path);
-                let url = storage.get_url(&remote_key);
+                    let url_path = normalize_path(relative);
+                    let remote_key = format!("{}/{}", server, url_path);
+                    let url = storage.get_url(&remote_key);
 
-                Ok(Asset {
-                    hash,
-                    size,
-                    url: Some(url),
-                    path: Some(url_path),
-                })
+                    Ok(Asset {
+                        hash: checksums.sha1,
+                        size: checksums.size,
+                        url: Some(url),
+                        path: Some(url_path),
+                        crc32: checksums.crc32,
+                        mtime,
+                    })
+                }
+                .await;
+
+                (file_path, result)
             }
         })
         .buffer_unordered(concurrency)
         .collect()
         .await;
+    results.extend(unreadable.into_iter().map(|(path, error)| (path, Err(error))));
 
-    // Filter out errors and collect successful results
-    let assets: Vec<Asset> = results.into_iter().filter_map(|r| r.ok()).collect();
-
-    Ok(assets)
+    partition_results(results, options.strict)
 }
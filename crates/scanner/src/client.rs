@@ -1,27 +1,25 @@
 use super::errors::ScanError;
+use super::models::ScanOptions;
 use lighty_models::Client;
 use lighty_storage::StorageBackend;
-use lighty_utils::compute_sha1_with_size;
 use std::path::Path;
 use std::sync::Arc;
 use tokio::fs;
 
 type Result<T> = std::result::Result<T, ScanError>;
 
-pub async fn scan_client(path: &Path, server: &str, storage: &Arc<dyn StorageBackend>, buffer_size: usize) -> Result<Option<Client>> {
-    let client_dir = path.join("client");
-
+pub async fn scan_client(client_dir: &Path, server: &str, storage: &Arc<dyn StorageBackend>, options: &ScanOptions) -> Result<Option<Client>> {
     if !client_dir.exists() {
         return Ok(None);
     }
 
     // Find the first .jar file in the client directory
-    let mut entries = fs::read_dir(&client_dir).await?;
+    let mut entries = fs::read_dir(client_dir).await?;
     let mut jar_file = None;
 
     while let Some(entry) = entries.next_entry().await? {
         let entry_path = entry.path();
-        if entry_path.is_file() && entry_path.extension().map_or(false, |ext| ext == "jar") {
+        if entry_path.is_file() && entry_path.extension().is_some_and(|ext| ext == "jar") {
             jar_file = Some(entry_path);
             break;
         }
@@ -38,7 +36,9 @@ pub async fn scan_client(path: &Path, server: &str, storage: &Arc<dyn StorageBac
         .to_string_lossy()
         .to_string();
 
-    let (sha1, size) = compute_sha1_with_size(&client_path, buffer_size).await?;
+    super::utils::ensure_stable(&client_path, options.stability_delay).await?;
+    let checksums = super::utils::file_checksums(&client_path, options).await?;
+    let mtime = super::utils::file_mtime(&client_path, options.include_mtime).await?;
 
     let remote_key = format!("{}/{}", server, file_name);
     let url = storage.get_url(&remote_key);
@@ -47,7 +47,9 @@ pub async fn scan_client(path: &Path, server: &str, storage: &Arc<dyn StorageBac
         name: "client".to_string(),
         url,
         path: file_name,
-        sha1,
-        size,
+        sha1: checksums.sha1,
+        size: checksums.size,
+        crc32: checksums.crc32,
+        mtime,
     }))
 }
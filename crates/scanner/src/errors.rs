@@ -1,3 +1,5 @@
+use std::io::ErrorKind;
+use std::path::Path;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -28,4 +30,41 @@ pub enum ScanError {
 
     #[error("Invalid file metadata: {0}")]
     InvalidMetadata(String),
+
+    #[error("Scan of server {server} timed out after {secs}s")]
+    Timeout { server: String, secs: u64 },
+
+    #[error("Server {server} has more than {limit} files (cache.max_files_per_server), scan aborted")]
+    TooManyFiles { server: String, limit: usize },
+
+    #[error("File is still being written: {0}")]
+    FileUnstable(String),
+
+    #[error("Failed to scan file {path}: {reason}")]
+    FileFailed { path: String, reason: String },
+
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+
+    #[error("File disappeared during the scan: {0}")]
+    FileNotFound(String),
+
+    /// Zero-byte file left out by `cache.skip_empty_files` (not a failure, even for strict scans)
+    #[error("Empty file skipped: {0}")]
+    EmptyFile(String),
+}
+
+impl ScanError {
+    /// Names permission and missing-file errors after `path`, instead of a bare I/O error
+    pub(crate) fn for_file(self, path: &Path) -> Self {
+        let kind = match &self {
+            ScanError::IoError(e) | ScanError::UtilsError(lighty_utils::UtilsError::IoError(e)) => e.kind(),
+            _ => return self,
+        };
+        match kind {
+            ErrorKind::PermissionDenied => ScanError::PermissionDenied(path.display().to_string()),
+            ErrorKind::NotFound => ScanError::FileNotFound(path.display().to_string()),
+            _ => self,
+        }
+    }
 }
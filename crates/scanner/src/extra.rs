@@ -0,0 +1,79 @@
+use super::errors::ScanError;
+use super::models::{ScanOptions, ScanFailure};
+use super::utils::{file_checksums, file_mtime, partition_results, walk_files};
+use lighty_config::ServerConfig;
+use lighty_models::{ExtraFile, FileCategory};
+use lighty_storage::StorageBackend;
+use lighty_utils::normalize_path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use futures::stream::{self, StreamExt};
+
+type Result<T> = std::result::Result<T, ScanError>;
+
+/// Scans every file of the server's `extra_dir`, whatever its type
+/// Files of the other category folders are left out, so `extra_dir = ""` publishes the rest of the server root
+pub async fn scan_extra_files(path: &Path, config: &ServerConfig, storage: &Arc<dyn StorageBackend>, concurrency: usize, options: &ScanOptions) -> Result<(Vec<ExtraFile>, Vec<ScanFailure>)> {
+    let extra_dir = path.join(config.category_dir(FileCategory::Extra));
+
+    if !extra_dir.exists() {
+        return Ok((vec![], vec![]));
+    }
+
+    let (file_paths, unreadable) = walk_files(&extra_dir, |file| {
+        file.is_file()
+            && file
+                .strip_prefix(path)
+                .is_ok_and(|relative| !config.in_other_category_dir(FileCategory::Extra, relative))
+    });
+
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+    let server = config.name.to_string();
+    let storage = Arc::clone(storage);
+    let options = *options;
+
+    let mut results: Vec<(PathBuf, Result<ExtraFile>)> = stream::iter(file_paths)
+        .map(|file_path| {
+            let sem = Arc::clone(&semaphore);
+            let extra_dir = extra_dir.clone();
+            let server = server.clone();
+            let storage = Arc::clone(&storage);
+
+            async move {
+                let _permit = sem.acquire().await.unwrap();
+
+                let result: Result<ExtraFile> = async {
+                    let relative = file_path
+                        .strip_prefix(&extra_dir)
+                        .map_err(|e| ScanError::InvalidMetadata(format!("Failed to strip prefix: {}", e)))?;
+
+                    let checksums = file_checksums(&file_path, &options).await?;
+                    let mtime = file_mtime(&file_path, options.include_mtime).await?;
+
+                    // Prefixed, unlike the other categories: arbitrary names would collide with them
+                    let url_path = normalize_path(relative);
+                    let remote_key = format!("{}/{}/{}", server, FileCategory::Extra.dir(), url_path);
+                    let url = storage.get_url(&remote_key);
+
+                    Ok(ExtraFile {
+                        url,
+                        path: url_path,
+                        sha1: checksums.sha1,
+                        size: checksums.size,
+                        crc32: checksums.crc32,
+                        mtime,
+                    })
+                }
+                .await;
+
+                (file_path, result)
+            }
+        })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
+    results.extend(unreadable.into_iter().map(|(path, error)| (path, Err(error))));
+
+    partition_results(results, options.strict)
+}
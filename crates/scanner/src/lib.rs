@@ -1,7 +1,9 @@
 mod models;
+mod arguments;
 mod server;
 mod utils;
 mod assets;
+mod extra;
 mod client;
 mod libraries;
 mod mods;
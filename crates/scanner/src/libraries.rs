@@ -1,5 +1,6 @@
 use super::utils::JarScanner;
 use super::errors::ScanError;
+use super::models::{ScanOptions, ScanFailure};
 use lighty_models::Library;
 use lighty_storage::StorageBackend;
 use lighty_utils::path_to_maven_name;
@@ -8,11 +9,9 @@ use std::sync::Arc;
 
 type Result<T> = std::result::Result<T, ScanError>;
 
-pub async fn scan_libraries(path: &Path, server: &str, storage: &Arc<dyn StorageBackend>, batch_size: usize, buffer_size: usize) -> Result<Vec<Library>> {
-    let libraries_dir = path.join("libraries");
-
+pub async fn scan_libraries(libraries_dir: &Path, server: &str, storage: &Arc<dyn StorageBackend>, batch_size: usize, options: &ScanOptions) -> Result<(Vec<Library>, Vec<ScanFailure>)> {
     let scanner = JarScanner::new(
-        libraries_dir,
+        libraries_dir.to_path_buf(),
         server.to_string(),
         Arc::clone(storage),
         batch_size,
@@ -28,7 +27,9 @@ pub async fn scan_libraries(path: &Path, server: &str, storage: &Arc<dyn Storage
                 path: Some(info.url_path),
                 sha1: Some(info.sha1),
                 size: Some(info.size),
+                crc32: info.crc32,
+                mtime: info.mtime,
             })
-        }, buffer_size)
+        }, options)
         .await
 }
@@ -1,10 +1,67 @@
+use super::errors::ScanError;
+use lighty_config::CacheSettings;
+use lighty_models::VersionBuilder;
 use lighty_storage::StorageBackend;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// Main server scanner struct
 pub struct ServerScanner;
 
+/// Per-file hashing options read from the cache configuration
+#[derive(Debug, Clone, Copy)]
+pub struct ScanOptions {
+    pub buffer_size: usize,
+    pub compute_crc32: bool,
+    /// Record each file's modification time
+    pub include_mtime: bool,
+    /// Fail the whole scan if any file fails instead of skipping it
+    pub strict: bool,
+    /// Abort a server scan that takes longer than this
+    pub timeout: Option<Duration>,
+    /// Re-stat recently modified files after this delay to detect partial writes
+    pub stability_delay: Option<Duration>,
+    /// Abort the scan before hashing if a server has more files than this
+    pub max_files: Option<usize>,
+    /// Leave out natives whose extension belongs to another OS than their folder
+    pub skip_mismatched_natives: bool,
+    /// Leave out zero-byte files (reported as `ScanError::EmptyFile`)
+    pub skip_empty_files: bool,
+}
+
+impl From<&CacheSettings> for ScanOptions {
+    fn from(cache: &CacheSettings) -> Self {
+        Self {
+            buffer_size: cache.checksum_buffer_size,
+            compute_crc32: cache.compute_crc32,
+            include_mtime: cache.include_mtime,
+            strict: cache.strict_scan,
+            timeout: (cache.scan_timeout_secs > 0).then(|| Duration::from_secs(cache.scan_timeout_secs)),
+            stability_delay: (cache.stability_delay_ms > 0).then(|| Duration::from_millis(cache.stability_delay_ms)),
+            max_files: (cache.max_files_per_server > 0).then_some(cache.max_files_per_server),
+            skip_mismatched_natives: cache.skip_mismatched_natives,
+            skip_empty_files: cache.skip_empty_files,
+        }
+    }
+}
+
+/// A file that could not be scanned and was left out of the metadata
+#[derive(Debug)]
+pub struct ScanFailure {
+    pub path: PathBuf,
+    pub error: ScanError,
+}
+
+/// Result of a server scan, with the files that were skipped
+#[derive(Debug)]
+pub struct ServerScan {
+    pub builder: VersionBuilder,
+    pub failures: Vec<ScanFailure>,
+    /// Zero-byte files published in the metadata (skipped ones are failures)
+    pub empty_files: Vec<PathBuf>,
+}
+
 /// Common scanner for JAR files with parallel processing
 pub struct JarScanner {
     pub base_dir: PathBuf,
@@ -21,6 +78,8 @@ pub struct JarFileInfo {
     pub url_path: String,
     pub sha1: String,
     pub size: u64,
+    pub crc32: Option<String>,
+    pub mtime: Option<u64>,
 }
 
 /// Information about a scanned file
@@ -31,4 +90,6 @@ pub struct FileInfo {
     pub url_path: String,
     pub sha1: String,
     pub size: u64,
+    pub crc32: Option<String>,
+    pub mtime: Option<u64>,
 }
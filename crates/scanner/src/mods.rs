@@ -1,5 +1,6 @@
 use super::utils::JarScanner;
 use super::errors::ScanError;
+use super::models::{ScanOptions, ScanFailure};
 use lighty_models::Mod;
 use lighty_storage::StorageBackend;
 use std::path::Path;
@@ -7,11 +8,9 @@ use std::sync::Arc;
 
 type Result<T> = std::result::Result<T, ScanError>;
 
-pub async fn scan_mods(path: &Path, server: &str, storage: &Arc<dyn StorageBackend>, batch_size: usize, buffer_size: usize) -> Result<Vec<Mod>> {
-    let mods_dir = path.join("mods");
-
+pub async fn scan_mods(mods_dir: &Path, server: &str, storage: &Arc<dyn StorageBackend>, batch_size: usize, options: &ScanOptions) -> Result<(Vec<Mod>, Vec<ScanFailure>)> {
     let scanner = JarScanner::new(
-        mods_dir,
+        mods_dir.to_path_buf(),
         server.to_string(),
         Arc::clone(storage),
         batch_size,
@@ -25,7 +24,9 @@ pub async fn scan_mods(path: &Path, server: &str, storage: &Arc<dyn StorageBacke
                 path: Some(info.url_path),
                 sha1: Some(info.sha1),
                 size: Some(info.size),
+                crc32: info.crc32,
+                mtime: info.mtime,
             })
-        }, buffer_size)
+        }, options)
         .await
 }
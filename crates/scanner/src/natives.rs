@@ -1,6 +1,8 @@
 use super::utils::scan_files_parallel;
 use super::errors::ScanError;
-use lighty_models::Native;
+use super::models::{ScanOptions, ScanFailure};
+use lighty_config::{NativesNaming, ServerConfig};
+use lighty_models::{FileCategory, Native, NativeExtract};
 use lighty_storage::StorageBackend;
 use std::path::Path;
 use std::sync::Arc;
@@ -9,15 +11,29 @@ type Result<T> = std::result::Result<T, ScanError>;
 
 const NATIVE_OS_TYPES: &[&str] = &["windows", "linux", "macos"];
 
-pub async fn scan_natives(path: &Path, server: &str, storage: &Arc<dyn StorageBackend>, concurrency: usize, buffer_size: usize) -> Result<Vec<Native>> {
-    let natives_dir = path.join("natives");
+/// Native library extensions and the OS they are built for
+const NATIVE_EXTENSIONS: &[(&str, &str)] = &[
+    ("dll", "windows"),
+    ("so", "linux"),
+    ("dylib", "macos"),
+    ("jnilib", "macos"),
+];
+
+pub async fn scan_natives(path: &Path, config: &ServerConfig, storage: &Arc<dyn StorageBackend>, concurrency: usize, options: &ScanOptions) -> Result<(Vec<Native>, Vec<ScanFailure>)> {
+    let natives_dir = path.join(config.category_dir(FileCategory::Natives));
+    let server: &str = &config.name;
+    let naming = config.natives_naming;
+    let extract = (!config.natives_extract_exclude.is_empty()).then(|| NativeExtract {
+        exclude: config.natives_extract_exclude.clone(),
+    });
 
     if !natives_dir.exists() {
-        return Ok(vec![]);
+        return Ok((vec![], vec![]));
     }
 
     // Scan each OS directory separately and collect OS type
     let mut all_natives = Vec::new();
+    let mut all_failures = Vec::new();
 
     for os in NATIVE_OS_TYPES {
         let os_dir = natives_dir.join(os);
@@ -26,28 +42,130 @@ pub async fn scan_natives(path: &Path, server: &str, storage: &Arc<dyn StorageBa
         }
 
         let os_str = os.to_string();
-        let natives = scan_files_parallel(
+        let server_name = server.to_string();
+        let skip_mismatched = options.skip_mismatched_natives;
+        let extract = extract.clone();
+        let (natives, failures) = scan_files_parallel(
             os_dir,
             server.to_string(),
             Arc::clone(storage),
-            |path| path.is_file(), // Accept all files
+            move |path| {
+                if !path.is_file() {
+                    return false;
+                }
+                match extension_os(path) {
+                    Some(expected) if expected != *os => {
+                        tracing::warn!(
+                            "Native {} of '{}' is in natives/{} but its extension is for {}{}",
+                            path.display(),
+                            server_name,
+                            os,
+                            expected,
+                            if skip_mismatched { ", skipping it" } else { "" }
+                        );
+                        !skip_mismatched
+                    }
+                    _ => true, // Accept all other files (e.g. natives jars)
+                }
+            },
             move |info| {
                 Ok(Native {
-                    name: format!("natives:{}:{}", os_str, info.file_name),
+                    name: native_name(naming, &os_str, &info.file_name),
                     url: info.url,
                     path: info.url_path,
                     sha1: info.sha1,
                     size: info.size,
                     os: os_str.clone(),
+                    crc32: info.crc32,
+                    mtime: info.mtime,
+                    extract: extract.clone(),
                 })
             },
             concurrency,
-            buffer_size,
+            options,
         )
         .await?;
 
         all_natives.extend(natives);
+        all_failures.extend(failures);
+    }
+
+    Ok((all_natives, all_failures))
+}
+
+/// `Native.name` of a file in `natives/{os}/` under the server's naming scheme
+fn native_name(naming: NativesNaming, os: &str, file_name: &str) -> String {
+    match naming {
+        NativesNaming::Lighty => format!("natives:{}:{}", os, file_name),
+        NativesNaming::Classifier => {
+            let (stem, ext) = match file_name.rsplit_once('.') {
+                Some((stem, ext)) => (stem, format!(".{}", ext)),
+                None => (file_name, String::new()),
+            };
+            let classifier = format!("-natives-{}", os);
+            if stem.ends_with(&classifier) {
+                file_name.to_string()
+            } else {
+                format!("{}{}{}", stem, classifier, ext)
+            }
+        }
     }
+}
+
+/// OS a native library is built for, from its extension (`libfoo.so.1` counts as `.so`)
+fn extension_os(path: &Path) -> Option<&'static str> {
+    let file_name = path.file_name()?.to_str()?.to_ascii_lowercase();
+    file_name
+        .split('.')
+        .skip(1)
+        .find_map(|ext| NATIVE_EXTENSIONS.iter().find(|(known, _)| *known == ext))
+        .map(|(_, os)| *os)
+}
 
-    Ok(all_natives)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extension_os_against_each_folder() {
+        let cases = [("lwjgl.dll", "windows"), ("liblwjgl.so", "linux"), ("liblwjgl.dylib", "macos")];
+        for (file_name, built_for) in cases {
+            for os in NATIVE_OS_TYPES {
+                let path = Path::new("natives").join(os).join(file_name);
+                let expected = extension_os(&path);
+                assert_eq!(expected, Some(built_for), "{}", path.display());
+                // Only the matching folder is free of a mismatch warning
+                assert_eq!(expected == Some(*os), built_for == *os, "{}", path.display());
+            }
+        }
+    }
+
+    #[test]
+    fn extension_os_of_other_file_names() {
+        let cases = [
+            ("liblwjgl.so.1", Some("linux")),
+            ("LWJGL.DLL", Some("windows")),
+            ("libjinput.jnilib", Some("macos")),
+            ("lwjgl-natives-windows.jar", None),
+            ("README", None),
+        ];
+        for (file_name, expected) in cases {
+            assert_eq!(extension_os(Path::new(file_name)), expected, "{}", file_name);
+        }
+    }
+
+    #[test]
+    fn native_name_for_each_naming() {
+        let cases = [
+            (NativesNaming::Lighty, "linux", "liblwjgl.so", "natives:linux:liblwjgl.so"),
+            (NativesNaming::Lighty, "windows", "lwjgl.jar", "natives:windows:lwjgl.jar"),
+            (NativesNaming::Classifier, "windows", "lwjgl.jar", "lwjgl-natives-windows.jar"),
+            (NativesNaming::Classifier, "macos", "lwjgl-3.3.3.jar", "lwjgl-3.3.3-natives-macos.jar"),
+            (NativesNaming::Classifier, "linux", "lwjgl-natives-linux.jar", "lwjgl-natives-linux.jar"),
+            (NativesNaming::Classifier, "linux", "lwjgl", "lwjgl-natives-linux"),
+        ];
+        for (naming, os, file_name, expected) in cases {
+            assert_eq!(native_name(naming, os, file_name), expected);
+        }
+    }
 }
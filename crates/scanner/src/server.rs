@@ -1,12 +1,13 @@
-use super::{assets, client, libraries, mods, natives};
-use super::models::ServerScanner;
+use super::{assets, client, extra, libraries, mods, natives};
+use super::models::{ScanFailure, ScanOptions, ServerScan, ServerScanner};
 use super::errors::ScanError;
-use lighty_config::{ServerConfig, BatchConfig};
+use lighty_config::{ServerConfig, BatchConfig, LoaderTemplates};
 use lighty_models::*;
 use lighty_storage::StorageBackend;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use walkdir::WalkDir;
 
 type Result<T> = std::result::Result<T, ScanError>;
 
@@ -16,14 +17,15 @@ impl ServerScanner {
         storage: &Arc<dyn StorageBackend>,
         base_path: &str,
         batch_config: &BatchConfig,
-        buffer_size: usize,
-    ) -> Result<VersionBuilder> {
+        options: &ScanOptions,
+        loader_templates: &LoaderTemplates,
+    ) -> Result<ServerScan> {
         let start = std::time::Instant::now();
 
         let server_path = PathBuf::from(base_path).join(config.name.as_ref());
         Self::validate_server_path(&server_path, config.name.as_ref())?;
 
-        let builder = Self::build_version_metadata(config, &server_path, storage, batch_config, buffer_size).await?;
+        let scan = Self::build_with_timeout(config, &server_path, storage, batch_config, options, loader_templates).await?;
 
         let duration = start.elapsed();
         tracing::debug!(
@@ -32,7 +34,7 @@ impl ServerScanner {
             duration.as_secs_f64()
         );
 
-        Ok(builder)
+        Ok(scan)
     }
 
     pub async fn scan_server_silent(
@@ -40,66 +42,175 @@ impl ServerScanner {
         storage: &Arc<dyn StorageBackend>,
         base_path: &str,
         batch_config: &BatchConfig,
-        buffer_size: usize,
-    ) -> Result<VersionBuilder> {
+        options: &ScanOptions,
+        loader_templates: &LoaderTemplates,
+    ) -> Result<ServerScan> {
         let server_path = PathBuf::from(base_path).join(config.name.as_ref());
         Self::validate_server_path(&server_path, config.name.as_ref())?;
-        Self::build_version_metadata(config, &server_path, storage, batch_config, buffer_size).await
+        Self::build_with_timeout(config, &server_path, storage, batch_config, options, loader_templates).await
     }
 
-    fn validate_server_path(path: &PathBuf, folder: &str) -> Result<()> {
+    /// Builds the metadata, giving up after `options.timeout` (e.g. hung network mount)
+    async fn build_with_timeout(
+        config: &ServerConfig,
+        server_path: &Path,
+        storage: &Arc<dyn StorageBackend>,
+        batch_config: &BatchConfig,
+        options: &ScanOptions,
+        loader_templates: &LoaderTemplates,
+    ) -> Result<ServerScan> {
+        let build = Self::build_version_metadata(config, server_path, storage, batch_config, options, loader_templates);
+
+        match options.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, build)
+                .await
+                .map_err(|_| ScanError::Timeout {
+                    server: config.name.to_string(),
+                    secs: timeout.as_secs(),
+                })?,
+            None => build.await,
+        }
+    }
+
+    /// Counts the files under the enabled component folders, failing as soon as `limit` is exceeded
+    /// Runs before any hashing, so a misplaced world save doesn't queue thousands of files
+    async fn check_file_count(config: &ServerConfig, server_path: &Path, limit: usize) -> Result<()> {
+        let dirs: Vec<PathBuf> = FileCategory::ALL
+            .into_iter()
+            .filter(|category| config.category_enabled(*category))
+            .map(|category| server_path.join(config.category_dir(category)))
+            .collect();
+
+        let count = tokio::task::spawn_blocking(move || {
+            dirs.iter()
+                .flat_map(|dir| WalkDir::new(dir).into_iter().filter_map(|e| e.ok()))
+                .filter(|e| e.file_type().is_file())
+                .take(limit + 1)
+                .count()
+        })
+        .await?;
+
+        if count > limit {
+            return Err(ScanError::TooManyFiles {
+                server: config.name.to_string(),
+                limit,
+            });
+        }
+        Ok(())
+    }
+
+    fn validate_server_path(path: &Path, folder: &str) -> Result<()> {
         if !path.exists() {
             return Err(ScanError::ServerFolderNotFound(folder.to_string()));
         }
         Ok(())
     }
 
+    /// Public form of a skipped file, relative to the server folder (absolute paths stay private)
+    fn scan_warning(server_path: &Path, failure: &ScanFailure) -> String {
+        let path = failure.path.strip_prefix(server_path).unwrap_or(&failure.path);
+        let reason = failure
+            .error
+            .to_string()
+            .replace(&format!("{}/", server_path.display()), "");
+        format!("{}: {}", path.display(), reason)
+    }
+
     async fn build_version_metadata(
         config: &ServerConfig,
-        server_path: &PathBuf,
+        server_path: &Path,
         storage: &Arc<dyn StorageBackend>,
         batch_config: &BatchConfig,
-        buffer_size: usize,
-    ) -> Result<VersionBuilder> {
-        // Scan all components in parallel
-        let (libraries_result, mods_result, natives_result, client_result, assets_result) = tokio::join!(
+        options: &ScanOptions,
+        loader_templates: &LoaderTemplates,
+    ) -> Result<ServerScan> {
+        if let Some(limit) = options.max_files {
+            Self::check_file_count(config, server_path, limit).await?;
+        }
+
+        // Scan all components in parallel, each in its folder of the server layout
+        let dir = |category| server_path.join(config.category_dir(category));
+        let (libraries_result, mods_result, natives_result, client_result, assets_result, extra_result) = tokio::join!(
             async {
                 if config.enable_libraries {
-                    libraries::scan_libraries(server_path, &config.name, storage, batch_config.libraries, buffer_size).await
+                    libraries::scan_libraries(&dir(FileCategory::Libraries), &config.name, storage, batch_config.libraries, options).await
                 } else {
-                    Ok(vec![])
+                    Ok((vec![], vec![]))
                 }
             },
             async {
                 if config.enable_mods {
-                    mods::scan_mods(server_path, &config.name, storage, batch_config.mods, buffer_size).await
+                    mods::scan_mods(&dir(FileCategory::Mods), &config.name, storage, batch_config.mods, options).await
                 } else {
-                    Ok(vec![])
+                    Ok((vec![], vec![]))
                 }
             },
             async {
                 if config.enable_natives {
-                    natives::scan_natives(server_path, &config.name, storage, batch_config.natives, buffer_size).await.map(Some)
+                    natives::scan_natives(server_path, config, storage, batch_config.natives, options)
+                        .await
+                        .map(|(natives, failures)| (Some(natives), failures))
                 } else {
-                    Ok(None)
+                    Ok((None, vec![]))
                 }
             },
             async {
                 if config.enable_client {
-                    client::scan_client(server_path, &config.name, storage, buffer_size).await
+                    client::scan_client(&dir(FileCategory::Client), &config.name, storage, options).await
                 } else {
                     Ok(None)
                 }
             },
             async {
                 if config.enable_assets {
-                    assets::scan_assets(server_path, &config.name, storage, batch_config.assets, buffer_size).await
+                    assets::scan_assets(&dir(FileCategory::Assets), &config.name, storage, batch_config.assets, options).await
+                } else {
+                    Ok((vec![], vec![]))
+                }
+            },
+            async {
+                // Loose files like assets, hashed with the same concurrency
+                if config.enable_extra {
+                    extra::scan_extra_files(server_path, config, storage, batch_config.assets, options).await
                 } else {
-                    Ok(vec![])
+                    Ok((vec![], vec![]))
                 }
             },
         );
 
+        let (mut libraries, mut failures) = libraries_result?;
+        let (mut mods, mod_failures) = mods_result?;
+        let (mut natives, native_failures) = natives_result?;
+        let (mut assets, asset_failures) = assets_result?;
+        let (mut extra_files, extra_failures) = extra_result?;
+        failures.extend(mod_failures);
+        failures.extend(native_failures);
+        failures.extend(asset_failures);
+        failures.extend(extra_failures);
+
+        // A skipped empty client jar leaves the client out like any other skipped file
+        let client = match client_result {
+            Err(ScanError::EmptyFile(path)) => {
+                failures.push(ScanFailure { path: PathBuf::from(&path), error: ScanError::EmptyFile(path) });
+                None
+            }
+            result => result?,
+        };
+
+        if !failures.is_empty() {
+            tracing::warn!("{} file(s) skipped while scanning '{}'", failures.len(), config.name);
+        }
+
+        // Files complete in arbitrary order (buffer_unordered): sort for stable output
+        libraries.sort_by(|a, b| a.path.cmp(&b.path).then_with(|| a.name.cmp(&b.name)));
+        mods.sort_by(|a, b| a.path.cmp(&b.path).then_with(|| a.name.cmp(&b.name)));
+        if let Some(ref mut natives) = natives {
+            natives.sort_by(|a, b| a.os.cmp(&b.os).then_with(|| a.path.cmp(&b.path)));
+        }
+        assets.sort_by(|a, b| a.path.cmp(&b.path).then_with(|| a.hash.cmp(&b.hash)));
+        extra_files.sort_by(|a, b| a.path.cmp(&b.path));
+        failures.sort_by(|a, b| a.path.cmp(&b.path));
+
         let mut builder = VersionBuilder {
             main_class: MainClass {
                 main_class: config.main_class.clone(),
@@ -107,21 +218,30 @@ impl ServerScanner {
             java_version: JavaVersion {
                 major_version: config.java_version,
             },
-            arguments: Arguments {
-                game: config.game_args.clone(),
-                jvm: config.jvm_args.clone(),
-            },
-            libraries: libraries_result?,
-            mods: mods_result?,
-            natives: natives_result?,
-            client: client_result?,
-            assets: assets_result?,
+            arguments: Self::launch_arguments(config, loader_templates),
+            libraries,
+            mods,
+            natives,
+            client,
+            extra_files,
+            assets,
+            scan_warnings: failures.iter().map(|failure| Self::scan_warning(server_path, failure)).collect(),
             url_to_path_map: HashMap::new(),
+            path_to_meta_map: HashMap::new(),
+            sha1_to_path_map: HashMap::new(),
         };
 
-        // Build URL→path lookup map for O(1) file resolution
+        // Build URL→path and path→metadata lookup maps for O(1) file resolution
         builder.build_url_map();
 
-        Ok(builder)
+        // Published zero-byte files, often an incomplete transfer
+        let empty_files = builder
+            .manifest()
+            .into_iter()
+            .filter(|entry| entry.size == 0)
+            .map(|entry| server_path.join(config.local_path(&entry.path)))
+            .collect();
+
+        Ok(ServerScan { builder, failures, empty_files })
     }
 }
@@ -0,0 +1,19 @@
+use super::super::errors::ScanError;
+use super::super::models::ScanOptions;
+use lighty_utils::{compute_checksums, FileChecksums};
+use std::path::Path;
+
+type Result<T> = std::result::Result<T, ScanError>;
+
+/// Hashes a file with the scan options, failing with `EmptyFile` on zero-byte files when `skip_empty_files` is set
+pub(crate) async fn file_checksums(path: &Path, options: &ScanOptions) -> Result<FileChecksums> {
+    let checksums = compute_checksums(path, options.buffer_size, options.compute_crc32)
+        .await
+        .map_err(|e| ScanError::from(e).for_file(path))?;
+
+    if checksums.size == 0 && options.skip_empty_files {
+        return Err(ScanError::EmptyFile(path.display().to_string()));
+    }
+
+    Ok(checksums)
+}
@@ -1,7 +1,8 @@
-use super::super::models::{JarScanner, JarFileInfo, FileInfo};
+use super::super::models::{JarScanner, JarFileInfo, FileInfo, ScanOptions, ScanFailure};
 use super::super::errors::ScanError;
+use super::{ensure_stable, file_checksums, file_mtime};
 use lighty_storage::StorageBackend;
-use lighty_utils::{compute_sha1_with_size, normalize_path};
+use lighty_utils::normalize_path;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use walkdir::WalkDir;
@@ -21,22 +22,17 @@ impl JarScanner {
     }
 
     /// Scan directory for JAR files and process them with controlled concurrency
-    pub async fn scan<T, F>(self, mapper: F, buffer_size: usize) -> Result<Vec<T>>
+    pub async fn scan<T, F>(self, mapper: F, options: &ScanOptions) -> Result<(Vec<T>, Vec<ScanFailure>)>
     where
         T: Send + 'static,
         F: Fn(JarFileInfo) -> Result<T> + Send + Sync + 'static,
     {
         if !self.base_dir.exists() {
-            return Ok(vec![]);
+            return Ok((vec![], vec![]));
         }
 
         // Collect all jar file paths first (sync operation)
-        let jar_paths: Vec<PathBuf> = WalkDir::new(&self.base_dir)
-            .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(|e| is_jar_file(e.path()))
-            .map(|e| e.path().to_path_buf())
-            .collect();
+        let (jar_paths, unreadable) = walk_files(&self.base_dir, is_jar_file);
 
         // Create semaphore to control concurrency
         let semaphore = Arc::new(Semaphore::new(self.batch_size));
@@ -44,61 +40,72 @@ impl JarScanner {
         let server = self.server;
         let storage = self.storage;
         let mapper = std::sync::Arc::new(mapper);
+        let options = *options;
 
         // Process files concurrently with semaphore control
-        let results: Vec<Result<T>> = stream::iter(jar_paths)
+        let mut results: Vec<(PathBuf, Result<T>)> = stream::iter(jar_paths)
             .map(|jar_path| {
                 let sem = Arc::clone(&semaphore);
                 let base_dir = base_dir.clone();
                 let server = server.clone();
                 let storage = Arc::clone(&storage);
                 let mapper = Arc::clone(&mapper);
-                let buffer_size = buffer_size;
 
                 async move {
                     // Acquire semaphore permit
                     let _permit = sem.acquire().await.unwrap();
 
-                    let relative = jar_path
-                        .strip_prefix(&base_dir)
-                        .map_err(|e| ScanError::InvalidMetadata(format!("Failed to strip prefix: {}", e)))?;
-
-                    // Async hash computation
-                    let (sha1, size) = compute_sha1_with_size(&jar_path, buffer_size).await?;
-
-                    let file_name = jar_path
-                        .file_name()
-                        .ok_or_else(|| ScanError::InvalidMetadata("Failed to get filename".to_string()))?
-                        .to_string_lossy()
-                        .to_string();
-
-                    let url_path = normalize_path(relative);
-                    let remote_key = format!("{}/{}", server, url_path);
-                    let url = storage.get_url(&remote_key);
-
-                    let info = JarFileInfo {
-                        file_name,
-                        relative_path: relative.to_path_buf(),
-                        url,
-                        url_path,
-                        sha1,
-                        size,
-                    };
-
-                    mapper(info)
+                    let result: Result<T> = async {
+                        let relative = jar_path
+                            .strip_prefix(&base_dir)
+                            .map_err(|e| ScanError::InvalidMetadata(format!("Failed to strip prefix: {}", e)))?;
+
+                        // Skip hashing a file that is still being copied
+                        ensure_stable(&jar_path, options.stability_delay).await?;
+
+                        // Async hash computation
+                        let checksums = file_checksums(&jar_path, &options).await?;
+                        let mtime = file_mtime(&jar_path, options.include_mtime).await?;
+
+                        let file_name = jar_path
+                            .file_name()
+                            .ok_or_else(|| ScanError::InvalidMetadata("Failed to get filename".to_string()))?
+                            .to_string_lossy()
+                            .to_string();
+
+                        let url_path = normalize_path(relative);
+                        let remote_key = format!("{}/{}", server, url_path);
+                        let url = storage.get_url(&remote_key);
+
+                        let info = JarFileInfo {
+                            file_name,
+                            relative_path: relative.to_path_buf(),
+                            url,
+                            url_path,
+                            sha1: checksums.sha1,
+                            size: checksums.size,
+                            crc32: checksums.crc32,
+                            mtime,
+                        };
+
+                        mapper(info)
+                    }
+                    .await;
+
+                    (jar_path, result)
                 }
             })
             .buffer_unordered(self.batch_size)
             .collect()
             .await;
+        results.extend(unreadable.into_iter().map(|(path, error)| (path, Err(error))));
 
-        // Filter out errors and collect successful results
-        Ok(results.into_iter().filter_map(|r| r.ok()).collect())
+        partition_results(results, options.strict)
     }
 }
 
 fn is_jar_file(path: &Path) -> bool {
-    path.is_file() && path.extension().map_or(false, |ext| ext == "jar")
+    path.is_file() && path.extension().is_some_and(|ext| ext == "jar")
 }
 
 /// Scan files with a custom filter and processor (async with concurrency control)
@@ -109,75 +116,137 @@ pub async fn scan_files_parallel<T, Filter, Mapper>(
     filter: Filter,
     mapper: Mapper,
     concurrency: usize,
-    buffer_size: usize,
-) -> Result<Vec<T>>
+    options: &ScanOptions,
+) -> Result<(Vec<T>, Vec<ScanFailure>)>
 where
     T: Send + 'static,
     Filter: Fn(&Path) -> bool + Send + Sync + 'static,
     Mapper: Fn(FileInfo) -> Result<T> + Send + Sync + 'static,
 {
     if !base_dir.exists() {
-        return Ok(vec![]);
+        return Ok((vec![], vec![]));
     }
 
     // Collect all matching file paths
-    let file_paths: Vec<PathBuf> = WalkDir::new(&base_dir)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| filter(e.path()))
-        .map(|e| e.path().to_path_buf())
-        .collect();
+    let (file_paths, unreadable) = walk_files(&base_dir, filter);
 
     // Create semaphore to control concurrency
     let semaphore = Arc::new(Semaphore::new(concurrency));
     let mapper = Arc::new(mapper);
+    let options = *options;
 
     // Process all files concurrently with semaphore control
-    let results: Vec<Result<T>> = stream::iter(file_paths)
+    let mut results: Vec<(PathBuf, Result<T>)> = stream::iter(file_paths)
         .map(|file_path| {
             let sem = Arc::clone(&semaphore);
             let base_dir = base_dir.clone();
             let server = server.clone();
             let storage = Arc::clone(&storage);
             let mapper = Arc::clone(&mapper);
-            let buffer_size = buffer_size;
 
             async move {
                 // Acquire semaphore permit
                 let _permit = sem.acquire().await.unwrap();
 
-                let relative = file_path
-                    .strip_prefix(&base_dir)
-                    .map_err(|e| ScanError::InvalidMetadata(format!("Failed to strip prefix: {}", e)))?;
-
-                // Async hash computation
-                let (sha1, size) = compute_sha1_with_size(&file_path, buffer_size).await?;
-
-                let file_name = file_path
-                    .file_name()
-                    .ok_or_else(|| ScanError::InvalidMetadata("Failed to get filename".to_string()))?
-                    .to_string_lossy()
-                    .to_string();
-
-                let url_path = normalize_path(relative);
-                let remote_key = format!("{}/{}", server, url_path);
-                let url = storage.get_url(&remote_key);
-
-                let info = FileInfo {
-                    file_name,
-                    relative_path: relative.to_path_buf(),
-                    url,
-                    url_path,
-                    sha1,
-                    size,
-                };
+                let result: Result<T> = async {
+                    let relative = file_path
+                        .strip_prefix(&base_dir)
+                        .map_err(|e| ScanError::InvalidMetadata(format!("Failed to strip prefix: {}", e)))?;
+
+                    // Skip hashing a file that is still being copied
+                    ensure_stable(&file_path, options.stability_delay).await?;
+
+                    // Async hash computation
+                    let checksums = file_checksums(&file_path, &options).await?;
+                    let mtime = file_mtime(&file_path, options.include_mtime).await?;
+
+                    let file_name = file_path
+                        .file_name()
+                        .ok_or_else(|| ScanError::InvalidMetadata("Failed to get filename".to_string()))?
+                        .to_string_lossy()
+                        .to_string();
+
+                    let url_path = normalize_path(relative);
+                    let remote_key = format!("{}/{}", server, url_path);
+                    let url = storage.get_url(&remote_key);
 
-                mapper(info)
+                    let info = FileInfo {
+                        file_name,
+                        relative_path: relative.to_path_buf(),
+                        url,
+                        url_path,
+                        sha1: checksums.sha1,
+                        size: checksums.size,
+                        crc32: checksums.crc32,
+                        mtime,
+                    };
+
+                    mapper(info)
+                }
+                .await;
+
+                (file_path, result)
             }
         })
         .buffer_unordered(concurrency)
         .collect()
         .await;
+    results.extend(unreadable.into_iter().map(|(path, error)| (path, Err(error))));
+
+    partition_results(results, options.strict)
+}
+
+/// Files under `dir` matching `filter`, with the entries that could not be read (e.g. permission denied)
+/// Unreadable entries are reported as scan failures instead of silently vanishing from the metadata
+pub(crate) fn walk_files(dir: &Path, filter: impl Fn(&Path) -> bool) -> (Vec<PathBuf>, Vec<(PathBuf, ScanError)>) {
+    let mut files = Vec::new();
+    let mut unreadable = Vec::new();
+
+    for entry in WalkDir::new(dir) {
+        match entry {
+            Ok(entry) if filter(entry.path()) => files.push(entry.into_path()),
+            Ok(_) => {}
+            Err(e) => {
+                let path = e.path().unwrap_or(dir).to_path_buf();
+                let error = match e.io_error().map(std::io::Error::kind) {
+                    Some(std::io::ErrorKind::PermissionDenied) => ScanError::PermissionDenied(path.display().to_string()),
+                    Some(std::io::ErrorKind::NotFound) => ScanError::FileNotFound(path.display().to_string()),
+                    _ => ScanError::ScanDirectoryError(e.to_string()),
+                };
+                unreadable.push((path, error));
+            }
+        }
+    }
+
+    (files, unreadable)
+}
+
+/// Splits per-file results into scanned items and logged failures
+/// In strict mode the first failure aborts the scan instead
+/// A file still being written always aborts it: leaving it out would unpublish the file
+/// A skipped empty file is left out on purpose, even in strict mode
+pub(crate) fn partition_results<T>(
+    results: Vec<(PathBuf, Result<T>)>,
+    strict: bool,
+) -> Result<(Vec<T>, Vec<ScanFailure>)> {
+    let mut items = Vec::with_capacity(results.len());
+    let mut failures = Vec::new();
+
+    for (path, result) in results {
+        match result.map_err(|error| error.for_file(&path)) {
+            Ok(item) => items.push(item),
+            Err(error @ ScanError::FileUnstable(_)) => return Err(error),
+            Err(error @ ScanError::EmptyFile(_)) => failures.push(ScanFailure { path, error }),
+            Err(error) if strict => {
+                return Err(ScanError::FileFailed {
+                    path: path.display().to_string(),
+                    reason: error.to_string(),
+                });
+            }
+            // Reported by the caller (one `ScanFileFailed` event per file)
+            Err(error) => failures.push(ScanFailure { path, error }),
+        }
+    }
 
-    Ok(results.into_iter().filter_map(|r| r.ok()).collect())
+    Ok((items, failures))
 }
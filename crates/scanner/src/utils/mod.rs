@@ -1,4 +1,11 @@
+mod checksum;
 mod jar;
+mod mtime;
+mod stability;
 
 pub use super::models::JarScanner;
 pub use jar::scan_files_parallel;
+pub(crate) use checksum::file_checksums;
+pub(crate) use jar::{partition_results, walk_files};
+pub(crate) use mtime::file_mtime;
+pub(crate) use stability::ensure_stable;
@@ -0,0 +1,16 @@
+use super::super::errors::ScanError;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+type Result<T> = std::result::Result<T, ScanError>;
+
+/// Modification time of a file in Unix seconds, None unless `cache.include_mtime` is set
+pub(crate) async fn file_mtime(path: &Path, enabled: bool) -> Result<Option<u64>> {
+    if !enabled {
+        return Ok(None);
+    }
+
+    let modified = tokio::fs::metadata(path).await?.modified()?;
+    // Times before 1970 (bad clocks, odd archives) are published as 0
+    Ok(Some(modified.duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs())))
+}
@@ -0,0 +1,34 @@
+use super::super::errors::ScanError;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+type Result<T> = std::result::Result<T, ScanError>;
+
+/// Files modified longer ago than this are considered settled and not re-checked
+const RECENT_WRITE_WINDOW: Duration = Duration::from_secs(30);
+
+/// Fails with `FileUnstable` if a recently modified file changes size or mtime within `delay`
+/// Keeps half-copied files from being hashed and published
+pub(crate) async fn ensure_stable(path: &Path, delay: Option<Duration>) -> Result<()> {
+    let Some(delay) = delay else {
+        return Ok(());
+    };
+
+    let before = tokio::fs::metadata(path).await?;
+    let modified = before.modified()?;
+    let recently_written = SystemTime::now()
+        .duration_since(modified)
+        .map_or(true, |age| age < RECENT_WRITE_WINDOW);
+    if !recently_written {
+        return Ok(());
+    }
+
+    tokio::time::sleep(delay).await;
+
+    let after = tokio::fs::metadata(path).await?;
+    if after.len() != before.len() || after.modified()? != modified {
+        return Err(ScanError::FileUnstable(path.display().to_string()));
+    }
+
+    Ok(())
+}
@@ -1,12 +1,44 @@
 use crate::StorageError;
 use std::path::Path;
 
+/// Size and checksum of a stored object
+#[derive(Debug, Clone)]
+pub struct RemoteFile {
+    pub size: u64,
+    /// SHA1 recorded at upload time (None for objects uploaded by other tools)
+    pub sha1: Option<String>,
+}
+
+/// Body of a stored object, read as it downloads
+pub struct RemoteStream {
+    /// Object size, if reported by the backend
+    pub size: Option<u64>,
+    pub reader: Box<dyn tokio::io::AsyncRead + Send + Unpin>,
+}
+
 /// Storage backend trait for file storage abstraction
 #[async_trait::async_trait]
 pub trait StorageBackend: Send + Sync {
     /// Upload file to storage, returns public URL
     async fn upload_file(&self, local_path: &Path, remote_key: &str) -> Result<String, StorageError>;
 
+    /// Look up a stored object without downloading it (None if it doesn't exist)
+    /// Backends that can't tell return None, so the file is always uploaded
+    async fn head_file(&self, _remote_key: &str) -> Result<Option<RemoteFile>, StorageError> {
+        Ok(None)
+    }
+
+    /// SHA1 recorded when a stored object was uploaded (None if missing or uploaded by other tools)
+    async fn remote_checksum(&self, remote_key: &str) -> Result<Option<String>, StorageError> {
+        Ok(self.head_file(remote_key).await?.and_then(|remote| remote.sha1))
+    }
+
+    /// Download a stored object as a stream (None if it doesn't exist)
+    /// Backends that can't stream return None, so missing local files stay 404
+    async fn get_stream(&self, _remote_key: &str) -> Result<Option<RemoteStream>, StorageError> {
+        Ok(None)
+    }
+
     /// Delete file from storage
     async fn delete_file(&self, remote_key: &str) -> Result<(), StorageError>;
 
@@ -9,6 +9,14 @@ pub enum StorageError {
     #[error("S3 SDK error: {0}")]
     S3SdkError(String),
 
+    #[cfg(feature = "gcs")]
+    #[error("GCS authentication error: {0}")]
+    GcsAuthError(String),
+
+    #[cfg(feature = "gcs")]
+    #[error("GCS API error: {0}")]
+    GcsApiError(String),
+
     #[error("Upload failed for '{0}': {1}")]
     UploadError(String, String),
 
@@ -20,4 +28,14 @@ pub enum StorageError {
 
     #[error("File not found: {0}")]
     FileNotFound(String),
+
+    #[error("Storage endpoint unreachable: {0}")]
+    Unreachable(String),
+}
+
+impl StorageError {
+    /// Whether retrying the operation later may succeed (network blip, endpoint still booting)
+    pub fn is_transient(&self) -> bool {
+        matches!(self, StorageError::Unreachable(_))
+    }
 }
@@ -0,0 +1,381 @@
+use crate::backend::{RemoteFile, RemoteStream, StorageBackend};
+use crate::StorageError;
+use futures::TryStreamExt;
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use reqwest::{Client, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+
+const STORAGE_API: &str = "https://storage.googleapis.com";
+const TOKEN_SCOPE: &str = "https://www.googleapis.com/auth/devstorage.read_write";
+const DEFAULT_TOKEN_URI: &str = "https://oauth2.googleapis.com/token";
+
+/// Refresh the access token this long before it expires
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+/// Custom metadata key holding the file SHA1, read back by `head_file`
+const SHA1_METADATA_KEY: &str = "sha1";
+
+/// Fields of a service account JSON key used for authentication
+#[derive(Deserialize)]
+struct ServiceAccountKey {
+    client_email: String,
+    private_key: String,
+    #[serde(default)]
+    token_uri: Option<String>,
+}
+
+/// JWT claims exchanged for an OAuth2 access token
+#[derive(Serialize)]
+struct TokenClaims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: u64,
+    exp: u64,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+/// Fields of an object resource (`objects.get`) used by `head_file`
+#[derive(Deserialize)]
+struct ObjectResource {
+    /// The JSON API reports sizes as decimal strings
+    size: String,
+    #[serde(default)]
+    metadata: HashMap<String, String>,
+}
+
+impl ObjectResource {
+    fn into_remote_file(self) -> Result<RemoteFile, StorageError> {
+        let size = self
+            .size
+            .parse()
+            .map_err(|_| StorageError::GcsApiError(format!("Invalid object size: {}", self.size)))?;
+        Ok(RemoteFile {
+            size,
+            sha1: self.metadata.get(SHA1_METADATA_KEY).cloned(),
+        })
+    }
+}
+
+/// Body of a multipart upload: the object resource (name and custom metadata) followed by the file
+/// The SHA1 of the file is part of the boundary, which therefore can't appear in the file itself
+fn multipart_body(key: &str, sha1: &str, file_data: &[u8]) -> (String, Vec<u8>) {
+    let boundary = format!("lighty-{}", sha1);
+    let resource = serde_json::json!({
+        "name": key,
+        "metadata": { SHA1_METADATA_KEY: sha1 },
+    });
+
+    let mut body = Vec::with_capacity(file_data.len() + 512);
+    body.extend_from_slice(format!("--{}\r\nContent-Type: application/json; charset=UTF-8\r\n\r\n", boundary).as_bytes());
+    body.extend_from_slice(resource.to_string().as_bytes());
+    body.extend_from_slice(format!("\r\n--{}\r\nContent-Type: application/octet-stream\r\n\r\n", boundary).as_bytes());
+    body.extend_from_slice(file_data);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+
+    (format!("multipart/related; boundary={}", boundary), body)
+}
+
+struct CachedToken {
+    value: String,
+    expires_at: Instant,
+}
+
+/// Google Cloud Storage backend using the JSON API
+/// Authenticates with a service account key (JWT bearer flow)
+pub struct GcsBackend {
+    client: Client,
+    bucket_name: String,
+    public_url: String,
+    bucket_prefix: String,
+    client_email: String,
+    token_uri: String,
+    signing_key: EncodingKey,
+    token: Mutex<Option<CachedToken>>,
+}
+
+impl GcsBackend {
+    pub async fn new(
+        bucket_name: String,
+        credentials_path: &Path,
+        public_url: String,
+        bucket_prefix: String,
+    ) -> Result<Self, StorageError> {
+        let key_json = tokio::fs::read_to_string(credentials_path).await.map_err(|e| {
+            StorageError::ConfigError(format!(
+                "Failed to read GCS credentials {}: {}",
+                credentials_path.display(),
+                e
+            ))
+        })?;
+        let key: ServiceAccountKey = serde_json::from_str(&key_json)
+            .map_err(|e| StorageError::ConfigError(format!("Invalid GCS service account key: {}", e)))?;
+        let signing_key = EncodingKey::from_rsa_pem(key.private_key.as_bytes())
+            .map_err(|e| StorageError::ConfigError(format!("Invalid GCS private key: {}", e)))?;
+
+        let public_url = if public_url.is_empty() {
+            format!("{}/{}", STORAGE_API, bucket_name)
+        } else {
+            public_url.trim_end_matches('/').to_string()
+        };
+
+        Ok(Self {
+            client: Client::new(),
+            bucket_name,
+            public_url,
+            bucket_prefix,
+            client_email: key.client_email,
+            token_uri: key.token_uri.unwrap_or_else(|| DEFAULT_TOKEN_URI.to_string()),
+            signing_key,
+            token: Mutex::new(None),
+        })
+    }
+
+    fn build_key(&self, remote_key: &str) -> String {
+        if self.bucket_prefix.is_empty() {
+            remote_key.to_string()
+        } else {
+            format!("{}/{}", self.bucket_prefix, remote_key)
+        }
+    }
+
+    /// Returns a valid access token, exchanging a fresh signed JWT when the cached one expires
+    async fn access_token(&self) -> Result<String, StorageError> {
+        let mut token = self.token.lock().await;
+        if let Some(cached) = token.as_ref() {
+            if cached.expires_at > Instant::now() + TOKEN_REFRESH_MARGIN {
+                return Ok(cached.value.clone());
+            }
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let claims = TokenClaims {
+            iss: &self.client_email,
+            scope: TOKEN_SCOPE,
+            aud: &self.token_uri,
+            iat: now,
+            exp: now + 3600,
+        };
+        let assertion = jsonwebtoken::encode(&Header::new(Algorithm::RS256), &claims, &self.signing_key)
+            .map_err(|e| StorageError::GcsAuthError(e.to_string()))?;
+
+        let response = self
+            .client
+            .post(&self.token_uri)
+            .form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                ("assertion", assertion.as_str()),
+            ])
+            .send()
+            .await
+            .map_err(|e| StorageError::GcsAuthError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(StorageError::GcsAuthError(format!("token request returned {}: {}", status, body)));
+        }
+
+        let response: TokenResponse = response
+            .json()
+            .await
+            .map_err(|e| StorageError::GcsAuthError(e.to_string()))?;
+
+        *token = Some(CachedToken {
+            value: response.access_token.clone(),
+            expires_at: Instant::now() + Duration::from_secs(response.expires_in),
+        });
+
+        Ok(response.access_token)
+    }
+
+    /// Object URL with the name percent-encoded as a single path segment
+    fn object_url(&self, key: &str) -> Result<Url, StorageError> {
+        let mut url = Url::parse(STORAGE_API)
+            .map_err(|e| StorageError::ConfigError(e.to_string()))?;
+        url.path_segments_mut()
+            .map_err(|_| StorageError::ConfigError("Invalid GCS API URL".to_string()))?
+            .extend(["storage", "v1", "b", &self.bucket_name, "o", key]);
+        Ok(url)
+    }
+}
+
+#[async_trait::async_trait]
+impl StorageBackend for GcsBackend {
+    async fn upload_file(&self, local_path: &Path, remote_key: &str) -> Result<String, StorageError> {
+        let key = self.build_key(remote_key);
+
+        tracing::info!("Uploading {} to GCS bucket {}", key, self.bucket_name);
+
+        let file_data = tokio::fs::read(local_path).await?;
+        let sha1 = hex::encode(Sha1::digest(&file_data));
+        let (content_type, body) = multipart_body(&key, &sha1, &file_data);
+        let token = self.access_token().await?;
+        let upload_url = format!("{}/upload/storage/v1/b/{}/o", STORAGE_API, self.bucket_name);
+
+        let response = self
+            .client
+            .post(upload_url)
+            .bearer_auth(token)
+            .query(&[("uploadType", "multipart")])
+            .header(reqwest::header::CONTENT_TYPE, content_type)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| StorageError::UploadError(key.clone(), e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(StorageError::UploadError(key, format!("{}: {}", status, body)));
+        }
+
+        let url = self.get_url(remote_key);
+        tracing::info!("Upload complete: {}", url);
+
+        Ok(url)
+    }
+
+    async fn head_file(&self, remote_key: &str) -> Result<Option<RemoteFile>, StorageError> {
+        let key = self.build_key(remote_key);
+
+        let token = self.access_token().await?;
+        let response = self
+            .client
+            .get(self.object_url(&key)?)
+            .bearer_auth(token)
+            .query(&[("fields", "size,metadata")])
+            .send()
+            .await
+            .map_err(|e| StorageError::Unreachable(format!("objects.get failed for '{}': {}", key, e)))?;
+
+        let status = response.status();
+        if status == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(StorageError::GcsApiError(format!("objects.get failed for '{}': {}: {}", key, status, body)));
+        }
+
+        let object: ObjectResource = response
+            .json()
+            .await
+            .map_err(|e| StorageError::GcsApiError(format!("Invalid object resource for '{}': {}", key, e)))?;
+        object.into_remote_file().map(Some)
+    }
+
+    async fn get_stream(&self, remote_key: &str) -> Result<Option<RemoteStream>, StorageError> {
+        let key = self.build_key(remote_key);
+
+        let token = self.access_token().await?;
+        let response = self
+            .client
+            .get(self.object_url(&key)?)
+            .bearer_auth(token)
+            .query(&[("alt", "media")])
+            .send()
+            .await
+            .map_err(|e| StorageError::Unreachable(format!("objects.get failed for '{}': {}", key, e)))?;
+
+        let status = response.status();
+        if status == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(StorageError::GcsApiError(format!("objects.get failed for '{}': {}: {}", key, status, body)));
+        }
+
+        let size = response.content_length();
+        let chunks = futures::stream::try_unfold(response, |mut response| async move {
+            Ok(response.chunk().await?.map(|chunk| (chunk, response)))
+        })
+        .map_err(|e: reqwest::Error| std::io::Error::other(e));
+
+        Ok(Some(RemoteStream {
+            size,
+            reader: Box::new(tokio_util::io::StreamReader::new(Box::pin(chunks))),
+        }))
+    }
+
+    async fn delete_file(&self, remote_key: &str) -> Result<(), StorageError> {
+        let key = self.build_key(remote_key);
+
+        tracing::info!("Deleting {} from GCS bucket {}", key, self.bucket_name);
+
+        let token = self.access_token().await?;
+        let response = self
+            .client
+            .delete(self.object_url(&key)?)
+            .bearer_auth(token)
+            .send()
+            .await
+            .map_err(|e| StorageError::DeleteError(key.clone(), e.to_string()))?;
+
+        // Already gone is fine, same as S3 DeleteObject
+        let status = response.status();
+        if !status.is_success() && status != StatusCode::NOT_FOUND {
+            let body = response.text().await.unwrap_or_default();
+            return Err(StorageError::DeleteError(key, format!("{}: {}", status, body)));
+        }
+
+        tracing::info!("Delete complete: {}", key);
+        Ok(())
+    }
+
+    fn get_url(&self, remote_key: &str) -> String {
+        let key = self.build_key(remote_key);
+        format!("{}/{}", self.public_url, key)
+    }
+
+    fn is_remote(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multipart_body_carries_the_sha1_metadata() {
+        let sha1 = hex::encode(Sha1::digest(b"jar"));
+        let (content_type, body) = multipart_body("survival/client.jar", &sha1, b"jar");
+        let body = String::from_utf8(body).unwrap();
+
+        assert_eq!(content_type, format!("multipart/related; boundary=lighty-{}", sha1));
+        let parts: Vec<&str> = body.split(&format!("--lighty-{}", sha1)).collect();
+        assert_eq!(parts.len(), 4);
+        let resource: serde_json::Value = serde_json::from_str(parts[1].split("\r\n\r\n").nth(1).unwrap().trim_end()).unwrap();
+        assert_eq!(resource["name"], "survival/client.jar");
+        assert_eq!(resource["metadata"]["sha1"], sha1.as_str());
+        assert_eq!(parts[2], "\r\nContent-Type: application/octet-stream\r\n\r\njar\r\n");
+        assert_eq!(parts[3], "--\r\n");
+    }
+
+    #[test]
+    fn object_resource_reads_size_and_sha1() {
+        let object: ObjectResource = serde_json::from_str(r#"{"size": "1024", "metadata": {"sha1": "abc"}}"#).unwrap();
+        let remote = object.into_remote_file().unwrap();
+        assert_eq!(remote.size, 1024);
+        assert_eq!(remote.sha1.as_deref(), Some("abc"));
+
+        // Uploaded by another tool: no custom metadata
+        let object: ObjectResource = serde_json::from_str(r#"{"size": "7"}"#).unwrap();
+        assert_eq!(object.into_remote_file().unwrap().sha1, None);
+    }
+}
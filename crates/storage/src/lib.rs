@@ -5,9 +5,15 @@ mod errors;
 #[cfg(feature = "s3")]
 mod s3;
 
-pub use backend::StorageBackend;
+#[cfg(feature = "gcs")]
+mod gcs;
+
+pub use backend::{RemoteFile, RemoteStream, StorageBackend};
 pub use local::LocalBackend;
 pub use errors::*;
 
 #[cfg(feature = "s3")]
 pub use s3::S3Backend;
+
+#[cfg(feature = "gcs")]
+pub use gcs::GcsBackend;
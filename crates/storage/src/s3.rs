@@ -1,9 +1,17 @@
-use crate::backend::StorageBackend;
+use crate::backend::{RemoteFile, RemoteStream, StorageBackend};
 use crate::StorageError;
 use aws_config::{BehaviorVersion, Region};
 use aws_credential_types::Credentials;
-use aws_sdk_s3::{primitives::ByteStream, Client};
+use aws_sdk_s3::{error::SdkError, primitives::ByteStream, Client};
+use sha1::{Digest, Sha1};
 use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+/// User metadata key holding the local file mtime, exposed as `x-amz-meta-mtime`
+const MTIME_METADATA_KEY: &str = "mtime";
+
+/// User metadata key holding the file SHA1, read back by `head_file`
+const SHA1_METADATA_KEY: &str = "sha1";
 
 /// S3-compatible storage backend
 /// Compatible with: Cloudflare R2, AWS S3, MinIO, DigitalOcean Spaces, etc.
@@ -41,6 +49,22 @@ impl S3Backend {
 
         let client = Client::new(&config);
 
+        // Fails fast on an unreachable endpoint (retried at startup) rather than on the first upload
+        // Other failures (missing HeadBucket permission, unknown bucket) only warn, uploads report them
+        match client.head_bucket().bucket(&bucket_name).send().await {
+            Ok(_) => {}
+            Err(e @ (SdkError::DispatchFailure(_) | SdkError::TimeoutError(_))) => {
+                return Err(StorageError::Unreachable(format!(
+                    "S3 endpoint for bucket '{}': {}",
+                    bucket_name,
+                    error_chain(&e)
+                )));
+            }
+            Err(e) => {
+                tracing::warn!("HeadBucket failed for '{}': {}", bucket_name, e.into_service_error());
+            }
+        }
+
         Ok(Self {
             client,
             bucket_name,
@@ -58,6 +82,24 @@ impl S3Backend {
     }
 }
 
+/// An error followed by its sources ("dispatch failure: io error: ... Connection refused")
+fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+/// Modification time of a local file in Unix seconds (None if the platform doesn't report it)
+async fn local_mtime(path: &Path) -> Option<u64> {
+    let modified = tokio::fs::metadata(path).await.ok()?.modified().ok()?;
+    modified.duration_since(UNIX_EPOCH).ok().map(|elapsed| elapsed.as_secs())
+}
+
 #[async_trait::async_trait]
 impl StorageBackend for S3Backend {
     async fn upload_file(&self, local_path: &Path, remote_key: &str) -> Result<String, StorageError> {
@@ -66,13 +108,23 @@ impl StorageBackend for S3Backend {
         tracing::info!("Uploading {} to S3 bucket {}", key, self.bucket_name);
 
         let file_data = tokio::fs::read(local_path).await?;
+        let sha1 = hex::encode(Sha1::digest(&file_data));
         let byte_stream = ByteStream::from(file_data);
 
-        self.client
+        let mut request = self
+            .client
             .put_object()
             .bucket(&self.bucket_name)
             .key(&key)
             .body(byte_stream)
+            .metadata(SHA1_METADATA_KEY, sha1);
+
+        // Keep the local mtime on the object (`x-amz-meta-mtime`, Unix seconds)
+        if let Some(mtime) = local_mtime(local_path).await {
+            request = request.metadata(MTIME_METADATA_KEY, mtime.to_string());
+        }
+
+        request
             .send()
             .await
             .map_err(|e| StorageError::UploadError(key.clone(), e.to_string()))?;
@@ -83,6 +135,60 @@ impl StorageBackend for S3Backend {
         Ok(url)
     }
 
+    async fn head_file(&self, remote_key: &str) -> Result<Option<RemoteFile>, StorageError> {
+        let key = self.build_key(remote_key);
+
+        let output = match self
+            .client
+            .head_object()
+            .bucket(&self.bucket_name)
+            .key(&key)
+            .send()
+            .await
+        {
+            Ok(output) => output,
+            Err(e) => {
+                let e = e.into_service_error();
+                if e.is_not_found() {
+                    return Ok(None);
+                }
+                return Err(StorageError::S3SdkError(format!("HeadObject failed for '{}': {}", key, e)));
+            }
+        };
+
+        Ok(Some(RemoteFile {
+            size: output.content_length().unwrap_or_default().max(0) as u64,
+            sha1: output.metadata().and_then(|metadata| metadata.get(SHA1_METADATA_KEY).cloned()),
+        }))
+    }
+
+    async fn get_stream(&self, remote_key: &str) -> Result<Option<RemoteStream>, StorageError> {
+        let key = self.build_key(remote_key);
+
+        let output = match self
+            .client
+            .get_object()
+            .bucket(&self.bucket_name)
+            .key(&key)
+            .send()
+            .await
+        {
+            Ok(output) => output,
+            Err(e) => {
+                let e = e.into_service_error();
+                if e.is_no_such_key() {
+                    return Ok(None);
+                }
+                return Err(StorageError::S3SdkError(format!("GetObject failed for '{}': {}", key, e)));
+            }
+        };
+
+        Ok(Some(RemoteStream {
+            size: output.content_length().map(|length| length.max(0) as u64),
+            reader: Box::new(output.body.into_async_read()),
+        }))
+    }
+
     async fn delete_file(&self, remote_key: &str) -> Result<(), StorageError> {
         let key = self.build_key(remote_key);
 
@@ -11,9 +11,24 @@ pub async fn compute_sha1<P: AsRef<Path>>(path: P) -> Result<String, UtilsError>
     Ok(sha1)
 }
 
+/// Checksums computed in a single pass over a file
+#[derive(Debug, Clone)]
+pub struct FileChecksums {
+    pub sha1: String,
+    pub size: u64,
+    pub crc32: Option<String>,
+}
+
 pub async fn compute_sha1_with_size<P: AsRef<Path>>(path: P, buffer_size: usize) -> Result<(String, u64), UtilsError> {
+    let checksums = compute_checksums(path, buffer_size, false).await?;
+    Ok((checksums.sha1, checksums.size))
+}
+
+/// Computes SHA1 and size, plus CRC32 if requested, during the same read (no second pass)
+pub async fn compute_checksums<P: AsRef<Path>>(path: P, buffer_size: usize, with_crc32: bool) -> Result<FileChecksums, UtilsError> {
     let mut file = File::open(path).await?;
     let mut hasher = Sha1::new();
+    let mut crc32_hasher = with_crc32.then(crc32fast::Hasher::new);
     let mut buffer = vec![0u8; buffer_size];
     let mut total_bytes = 0u64;
 
@@ -23,11 +38,18 @@ pub async fn compute_sha1_with_size<P: AsRef<Path>>(path: P, buffer_size: usize)
             break;
         }
         hasher.update(&buffer[..bytes_read]);
+        if let Some(crc32_hasher) = crc32_hasher.as_mut() {
+            crc32_hasher.update(&buffer[..bytes_read]);
+        }
         total_bytes += bytes_read as u64;
     }
 
     let result = hasher.finalize();
-    Ok((hex::encode(result), total_bytes))
+    Ok(FileChecksums {
+        sha1: hex::encode(result),
+        size: total_bytes,
+        crc32: crc32_hasher.map(|h| format!("{:08x}", h.finalize())),
+    })
 }
 
 pub fn compute_sha1_sync<P: AsRef<Path>>(path: P) -> Result<String, UtilsError> {
@@ -1,9 +1,9 @@
 use super::models::ConfigWatcher;
 use super::errors::WatcherError;
 use lighty_cache::CacheManager;
-use lighty_config::{Config, ServerConfig};
+use lighty_config::{Config, FileCategory, LoaderTemplates, ServerConfig, WatcherBackend};
 use lighty_filesystem::FileSystem;
-use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use notify::{Event, RecursiveMode, Watcher};
 use std::collections::{HashSet, HashMap};
 use std::path::Path;
 use std::sync::Arc;
@@ -11,6 +11,21 @@ use tokio::sync::RwLock;
 
 type Result<T> = std::result::Result<T, WatcherError>;
 
+/// Server-level changes of a config reload
+#[derive(Debug, Default)]
+struct ServerDiff {
+    added: Vec<Arc<str>>,
+    modified: Vec<Arc<str>>,
+    removed: Vec<Arc<str>>,
+}
+
+impl ServerDiff {
+    /// Whether the server was added or modified
+    fn touches(&self, name: &Arc<str>) -> bool {
+        self.added.contains(name) || self.modified.contains(name)
+    }
+}
+
 impl ConfigWatcher {
     pub fn new(config: Arc<RwLock<Config>>, config_path: String, cache_manager: Arc<CacheManager>) -> Self {
         Self {
@@ -38,8 +53,57 @@ impl ConfigWatcher {
             || old.enable_mods != new.enable_mods
             || old.enable_natives != new.enable_natives
             || old.enable_assets != new.enable_assets
+            || old.enable_extra != new.enable_extra
+            || FileCategory::ALL.into_iter().any(|category| old.category_dir(category) != new.category_dir(category))
             || old.game_args != new.game_args
             || old.jvm_args != new.jvm_args
+            || old.natives_naming != new.natives_naming
+            || old.natives_extract_exclude != new.natives_extract_exclude
+    }
+
+    /// Whether a reload changes how unchanged files are published: categories kept off remote
+    /// storage (`[storage] upload_*`, URLs move) or `[cache] include_mtime` (fields appear or go away)
+    fn publishing_changed(old: &Config, new: &Config) -> bool {
+        old.storage.local_categories() != new.storage.local_categories()
+            || old.cache.include_mtime != new.cache.include_mtime
+    }
+
+    /// Servers added, modified (significant field or loader template changed) and removed by a reload
+    fn diff_servers(old: &Config, new: &Config) -> ServerDiff {
+        let old_configs: HashMap<_, _> = old.servers.iter().map(|s| (s.name.as_ref(), s)).collect();
+        let new_names: HashSet<_> = new.servers.iter().map(|s| s.name.as_ref()).collect();
+
+        let mut diff = ServerDiff::default();
+        for new_server in &new.servers {
+            match old_configs.get(new_server.name.as_ref()) {
+                None => diff.added.push(new_server.name.clone()),
+                Some(old_server) => {
+                    if Self::server_config_changed(old_server, new_server)
+                        || Self::loader_template_changed(&new_server.loader, &old.loader_templates, &new.loader_templates)
+                    {
+                        diff.modified.push(new_server.name.clone());
+                    }
+                }
+            }
+        }
+        diff.removed = old
+            .servers
+            .iter()
+            .filter(|s| !new_names.contains(s.name.as_ref()))
+            .map(|s| s.name.clone())
+            .collect();
+        diff
+    }
+
+    /// Checks if the `[loader_templates]` entry of a loader changed (names are case-insensitive)
+    fn loader_template_changed(loader: &str, old: &LoaderTemplates, new: &LoaderTemplates) -> bool {
+        let find = |templates: &'_ LoaderTemplates| {
+            templates
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(loader))
+                .map(|(_, template)| template.clone())
+        };
+        find(old) != find(new)
     }
 
     pub async fn start_watching(self: Arc<Self>) -> Result<tokio::task::JoinHandle<()>> {
@@ -63,28 +127,43 @@ impl ConfigWatcher {
         config: Arc<RwLock<Config>>,
         cache_manager: Arc<CacheManager>,
     ) -> Result<()> {
-        // Get channel size from config
-        let channel_size = {
+        // Get channel size and watcher backend from config
+        let (channel_size, backend, poll_interval_ms) = {
             let config_read = config.read().await;
-            config_read.cache.config_reload_channel_size
+            (
+                config_read.cache.config_reload_channel_size,
+                config_read.hot_reload.files.watcher,
+                config_read.hot_reload.files.poll_interval_ms,
+            )
         };
 
         let (tx, mut rx) = tokio::sync::mpsc::channel(channel_size);
 
-        let mut watcher: RecommendedWatcher = notify::recommended_watcher(
-            move |res: std::result::Result<Event, notify::Error>| {
-                if let Ok(event) = res {
-                    tracing::debug!("File watcher event: {:?}", event);
-                    if matches!(
-                        event.kind,
-                        notify::EventKind::Modify(_) | notify::EventKind::Create(_)
-                    ) {
-                        tracing::debug!("Config file change detected, sending reload signal");
-                        let _ = tx.blocking_send(());
-                    }
+        // Remote server list changes go through the same reload as file edits
+        let remote_refresh = tokio::spawn(Self::poll_remote_servers(Arc::clone(&config), tx.clone()));
+
+        let handler = move |res: std::result::Result<Event, notify::Error>| {
+            if let Ok(event) = res {
+                tracing::debug!("File watcher event: {:?}", event);
+                if matches!(
+                    event.kind,
+                    notify::EventKind::Modify(_) | notify::EventKind::Create(_)
+                ) {
+                    tracing::debug!("Config file change detected, sending reload signal");
+                    let _ = tx.blocking_send(());
                 }
-            },
-        )?;
+            }
+        };
+
+        // Polling watcher for config files on NFS/SMB mounts, where native events are unreliable
+        let mut watcher: Box<dyn Watcher + Send> = match backend {
+            WatcherBackend::Native => Box::new(notify::recommended_watcher(handler)?),
+            WatcherBackend::Poll => {
+                let poll_config = notify::Config::default()
+                    .with_poll_interval(std::time::Duration::from_millis(poll_interval_ms));
+                Box::new(notify::PollWatcher::new(handler, poll_config)?)
+            }
+        };
 
         watcher.watch(Path::new(config_path), RecursiveMode::NonRecursive)?;
         tracing::info!("File watcher initialized for: {}", config_path);
@@ -137,127 +216,163 @@ impl ConfigWatcher {
             }
 
             tracing::info!("🔄 Reloading configuration from {}", config_path);
-            match Config::from_file_no_migration(config_path).await {
+            let loaded = match Config::from_file_no_migration(config_path).await {
+                Ok(mut new_config) => {
+                    // An unreachable list keeps the servers fetched from the same URL by the last load
+                    let previous = {
+                        let config_read = config.read().await;
+                        config_read
+                            .remote_server_list
+                            .clone()
+                            .filter(|_| config_read.remote_servers.url == new_config.remote_servers.url)
+                    };
+                    new_config.fetch_remote_servers(previous.as_ref()).await.map(|()| new_config)
+                }
+                Err(e) => Err(e),
+            };
+            match loaded {
                 Ok(new_config) => {
-                    tracing::debug!("Config loaded successfully, acquiring locks...");
-
-                    // CRITICAL: Pause rescan to prevent race condition during config reload
-                    cache_manager.pause_rescan();
-
-                    let (old_servers, old_configs) = {
-                        tracing::debug!("Acquiring read lock on config...");
+                    // Diffed before the swap, so the write lock is only held to replace the config
+                    let (diff, old_base_path, old_maintenance_mode, publishing_changed) = {
                         let config_read = config.read().await;
-                        tracing::debug!("Read lock acquired");
-                        let names = config_read
-                            .servers
-                            .iter()
-                            .map(|s| s.name.clone())
-                            .collect::<HashSet<_>>();
-                        let configs = config_read.servers.clone();
-                        (names, configs)
+                        (
+                            Self::diff_servers(&config_read, &new_config),
+                            config_read.server.base_path.clone(),
+                            config_read.server.maintenance_mode,
+                            Self::publishing_changed(&config_read, &new_config),
+                        )
                     };
+                    let maintenance_mode = new_config.server.maintenance_mode;
+                    let base_path_changed = old_base_path != new_config.server.base_path;
+                    let servers = new_config.servers.clone();
+                    let base_path = new_config.server.base_path.clone();
+                    let public_base_url = new_config.cdn.public_base_url.clone();
 
-                    let new_servers = new_config
-                        .servers
-                        .iter()
-                        .map(|s| s.name.clone())
-                        .collect::<HashSet<_>>();
-
-                    let added_servers: Vec<_> =
-                        new_servers.difference(&old_servers).cloned().collect();
-
-                    // Detect modified servers (existing servers with config changes)
-                    // O(1) HashMap lookup instead of O(n) find
-                    let old_configs_map: HashMap<_, _> = old_configs
-                        .iter()
-                        .map(|s| (s.name.as_ref(), s))
-                        .collect();
-
-                    let mut modified_servers = Vec::new();
-                    for new_server in &new_config.servers {
-                        if let Some(old_server) = old_configs_map.get(new_server.name.as_ref()) {
-                            // Check if any config field changed
-                            if Self::server_config_changed(old_server, new_server) {
-                                modified_servers.push(new_server.name.clone());
-                            }
+                    *config.write().await = new_config;
+
+                    // Before the rescans below, so they are skipped when entering maintenance
+                    if maintenance_mode != old_maintenance_mode {
+                        cache_manager.set_maintenance_from_config(maintenance_mode);
+                    }
+
+                    // Removed servers stop being served: cached versions, RAM files and path entries
+                    for server_name in &diff.removed {
+                        tracing::info!("🗑 Server removed from config: {}", server_name);
+                        cache_manager.remove_server(server_name, &old_base_path).await;
+                    }
+                    for server_name in &diff.added {
+                        cache_manager.restore_server(server_name);
+                    }
+
+                    // Rescans keep running: only the path cache entries of changed servers are touched
+                    if base_path_changed {
+                        cache_manager.rebuild_server_cache_with_data(&servers, &base_path);
+                    } else {
+                        for server in servers.iter().filter(|s| diff.touches(&s.name)) {
+                            cache_manager.update_server_path(server, &base_path);
+                        }
+                    }
+                    cache_manager.refresh_public_base_url(&public_base_url);
+
+                    tracing::info!(
+                        "✓ Configuration reloaded successfully from {} ({} added, {} modified, {} removed)",
+                        config_path,
+                        diff.added.len(),
+                        diff.modified.len(),
+                        diff.removed.len()
+                    );
+
+                    for server_name in &diff.modified {
+                        tracing::info!("🔄 Server config changed, rescanning: {}", server_name);
+                        if let Err(e) = cache_manager.force_rescan(server_name).await {
+                            tracing::error!("Failed to rescan modified server {}: {}", server_name, e);
                         }
                     }
 
-                    // Update config with exclusive write lock
-                    tracing::debug!("Acquiring write lock on config...");
-                    let mut config_write = config.write().await;
-                    tracing::debug!("Write lock acquired, updating config...");
-                    *config_write = new_config;
-
-                    // Rebuild server path cache after config update (pass data directly to avoid deadlock)
-                    tracing::debug!("Rebuilding server path cache...");
-                    cache_manager.rebuild_server_cache_with_data(&config_write.servers, config_write.server.base_path.as_ref());
-
-                    // Resume rescan BEFORE dropping lock to avoid race condition
-                    tracing::debug!("Resuming rescan...");
-                    cache_manager.resume_rescan();
-
-                    tracing::info!("✓ Configuration reloaded successfully from {}", config_path);
-
-                    // Rescan modified servers
-                    if !modified_servers.is_empty() {
-                        for server_name in &modified_servers {
-                            tracing::info!("🔄 Server config changed, rescanning: {}", server_name);
-                            drop(config_write);
-                            if let Err(e) = cache_manager.force_rescan(server_name).await {
-                                tracing::error!("Failed to rescan modified server {}: {}", server_name, e);
+                    // Same files, published differently: the other servers sync and publish their metadata again
+                    if publishing_changed {
+                        for server in servers.iter().filter(|s| s.enabled && !diff.touches(&s.name)) {
+                            tracing::info!("🔄 Publishing settings changed, republishing: {}", server.name);
+                            if let Err(e) = cache_manager.republish_server(&server.name).await {
+                                tracing::error!("Failed to republish server {}: {}", server.name, e);
                             }
-                            config_write = config.write().await;
                         }
                     }
 
-                    if !added_servers.is_empty() {
-                        tracing::info!("Detected {} new server(s): {:?}", added_servers.len(), added_servers);
-                        for server_name in &added_servers {
-                            if let Some(server_config) =
-                                config_write.servers.iter().find(|s| &s.name == server_name)
-                            {
-                                // Skip if server is disabled
-                                if !server_config.enabled {
-                                    tracing::warn!("Skipping disabled server: {}", server_name);
-                                    continue;
-                                }
-
-                                tracing::info!("🆕 New server detected: {}", server_name);
-
-                                let base_path = config_write.server.base_path.clone();
-                                let folder = server_config.name.clone();
-
-                                // Drop lock before I/O operations
-                                drop(config_write);
-
-                                if let Err(e) = FileSystem::ensure_server_structure(&base_path, &folder).await {
-                                    tracing::error!(
-                                        "Failed to create folders for {}: {}",
-                                        server_name,
-                                        e
-                                    );
-                                }
-
-                                if let Err(e) = cache_manager.force_rescan(server_name).await {
-                                    tracing::error!("Failed to scan new server {}: {}", server_name, e);
-                                }
-
-                                // Re-acquire lock for next iteration
-                                config_write = config.write().await;
-                            }
+                    if !diff.added.is_empty() {
+                        tracing::info!("Detected {} new server(s): {:?}", diff.added.len(), diff.added);
+                    }
+                    for server in servers.iter().filter(|s| diff.added.contains(&s.name)) {
+                        if !server.enabled {
+                            tracing::warn!("Skipping disabled server: {}", server.name);
+                            continue;
+                        }
+
+                        tracing::info!("🆕 New server detected: {}", server.name);
+
+                        if let Err(e) = FileSystem::ensure_server_structure(&base_path, &server.name, |category| server.category_dir(category)).await {
+                            tracing::error!("Failed to create folders for {}: {}", server.name, e);
+                        }
+
+                        if let Err(e) = cache_manager.force_rescan(&server.name).await {
+                            tracing::error!("Failed to scan new server {}: {}", server.name, e);
                         }
                     }
 
-                    drop(config_write);
+                    // After new server folders exist, so they can be watched
+                    cache_manager.notify_config_reloaded();
                 }
                 Err(e) => {
                     tracing::error!("Failed to reload config: {}", e);
-                    cache_manager.resume_rescan();
                 }
             }
         }
 
+        remote_refresh.abort();
         Ok(())
     }
+
+    /// Re-fetches `[remote_servers]` every `refresh_interval_secs`, signalling a reload when the list changed
+    /// Idle while config hot-reload is off; the interval and URL are read again after each wait, so reloads can enable or retune it
+    async fn poll_remote_servers(config: Arc<RwLock<Config>>, tx: tokio::sync::mpsc::Sender<()>) {
+        // Re-checked this often while the refresh is disabled
+        const IDLE_CHECK: std::time::Duration = std::time::Duration::from_secs(60);
+
+        loop {
+            let interval = {
+                let config_read = config.read().await;
+                let settings = &config_read.remote_servers;
+                if config_read.hot_reload.config.enabled && settings.enabled() && settings.refresh_interval_secs > 0 {
+                    std::time::Duration::from_secs(settings.refresh_interval_secs)
+                } else {
+                    IDLE_CHECK
+                }
+            };
+            tokio::time::sleep(interval).await;
+
+            let (enabled, settings, current) = {
+                let config_read = config.read().await;
+                (
+                    config_read.hot_reload.config.enabled,
+                    config_read.remote_servers.clone(),
+                    config_read.remote_server_list.clone(),
+                )
+            };
+            // Nothing would apply a changed list while hot-reload is off
+            if !enabled || !settings.enabled() || settings.refresh_interval_secs == 0 {
+                continue;
+            }
+
+            match settings.fetch().await {
+                Ok(servers) if current.as_ref() != Some(&servers) => {
+                    tracing::info!("Remote server list changed ({} servers), reloading configuration", servers.len());
+                    if tx.send(()).await.is_err() {
+                        return;
+                    }
+                }
+                Ok(_) => tracing::debug!("Remote server list unchanged"),
+                Err(e) => tracing::warn!("Failed to refresh remote server list, keeping the current servers: {}", e),
+            }
+        }
+    }
 }
@@ -4,7 +4,17 @@ use lighty_filesystem::FileSystem;
 use anyhow::Result;
 use std::sync::Arc;
 
-pub async fn load(config_path: &str, events: &Arc<EventBus>) -> Result<Config> {
+/// Auto-migration is disabled by `--no-migrate` or `LIGHTY_NO_MIGRATE=1`
+pub fn migration_enabled() -> bool {
+    let flag = std::env::args().skip(1).any(|arg| arg == "--no-migrate");
+    let env = std::env::var("LIGHTY_NO_MIGRATE")
+        .is_ok_and(|value| matches!(value.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"));
+    !(flag || env)
+}
+
+/// Loads the config, migrating it in place unless `migrate` is false
+/// Without migration, a config missing fields or holding deprecated ones is rejected untouched
+pub async fn load(config_path: &str, migrate: bool, events: &Arc<EventBus>) -> Result<Config> {
     let abs_config_path = FileSystem::get_absolute_path_string(config_path)?;
 
     events.emit(AppEvent::ConfigLoading {
@@ -12,7 +22,13 @@ pub async fn load(config_path: &str, events: &Arc<EventBus>) -> Result<Config> {
     });
 
     let config_exists = std::path::Path::new(config_path).exists();
-    let config = Config::from_file_with_events(config_path, Some(events)).await?;
+    let mut config = if migrate {
+        Config::from_file_with_events(config_path, Some(events)).await?
+    } else {
+        tracing::info!("Config auto-migration disabled, validating {} as is", abs_config_path);
+        Config::from_file_strict(config_path).await?
+    };
+    config.fetch_remote_servers(None).await?;
 
     if !config_exists {
         events.emit(AppEvent::ConfigCreated {
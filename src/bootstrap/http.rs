@@ -0,0 +1,309 @@
+use lighty_config::ServerSettings;
+use axum::{extract::Request, Router};
+use hyper::{body::Incoming, service::service_fn};
+use hyper_util::rt::{TokioExecutor, TokioIo, TokioTimer};
+use hyper_util::server::graceful::GracefulConnection;
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tokio::time::Instant;
+use tokio_rustls::TlsAcceptor;
+use tower::ServiceExt;
+
+/// Time a client gets to complete the TLS handshake
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Connection-level settings from `[server]` (applied per accepted connection)
+#[derive(Debug, Clone, Copy)]
+pub struct ConnectionSettings {
+    pub tcp_nodelay: bool,
+    pub keep_alive: bool,
+    /// Connections idle (or still sending request headers) for longer are closed
+    pub keep_alive_timeout: Option<Duration>,
+    /// Accept HTTP/2 (h2c) next to HTTP/1.1
+    pub http2: bool,
+    /// Interval of HTTP/2 PING frames detecting dead connections
+    pub http2_keep_alive_interval: Option<Duration>,
+    /// 0 = unlimited
+    pub max_connections_per_ip: usize,
+}
+
+impl From<&ServerSettings> for ConnectionSettings {
+    fn from(server: &ServerSettings) -> Self {
+        Self {
+            tcp_nodelay: server.tcp_nodelay,
+            keep_alive: server.keep_alive,
+            keep_alive_timeout: (server.keep_alive_timeout_secs > 0)
+                .then(|| Duration::from_secs(server.keep_alive_timeout_secs)),
+            http2: server.http2,
+            http2_keep_alive_interval: (server.http2_keep_alive_interval_secs > 0)
+                .then(|| Duration::from_secs(server.http2_keep_alive_interval_secs)),
+            max_connections_per_ip: server.max_connections_per_ip,
+        }
+    }
+}
+
+/// Open connections per client IP
+struct ConnectionLimiter {
+    max_per_ip: usize,
+    open: Mutex<HashMap<IpAddr, usize>>,
+}
+
+/// Releases a connection slot when the connection closes
+struct ConnectionSlot {
+    limiter: Arc<ConnectionLimiter>,
+    ip: IpAddr,
+}
+
+impl ConnectionLimiter {
+    fn acquire(limiter: &Arc<Self>, ip: IpAddr) -> Option<ConnectionSlot> {
+        if limiter.max_per_ip > 0 {
+            let mut open = limiter.open.lock().unwrap_or_else(|e| e.into_inner());
+            let count = open.entry(ip).or_insert(0);
+            if *count >= limiter.max_per_ip {
+                return None;
+            }
+            *count += 1;
+        }
+        Some(ConnectionSlot {
+            limiter: Arc::clone(limiter),
+            ip,
+        })
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        if self.limiter.max_per_ip == 0 {
+            return;
+        }
+        let mut open = self.limiter.open.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(count) = open.get_mut(&self.ip) {
+            *count -= 1;
+            if *count == 0 {
+                open.remove(&self.ip);
+            }
+        }
+    }
+}
+
+/// Request activity of a connection, used to close it once idle for `keep_alive_timeout`
+struct ConnectionActivity {
+    in_flight: AtomicUsize,
+    last_active: Mutex<Instant>,
+}
+
+impl ConnectionActivity {
+    fn begin(&self) {
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+        self.touch();
+    }
+
+    fn end(&self) {
+        self.in_flight.fetch_sub(1, Ordering::Relaxed);
+        self.touch();
+    }
+
+    fn touch(&self) {
+        *self.last_active.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+    }
+
+    /// Resolves once no request has been in flight for `timeout`
+    async fn idle_for(&self, timeout: Duration) {
+        loop {
+            let deadline = *self.last_active.lock().unwrap_or_else(|e| e.into_inner()) + timeout;
+            if deadline <= Instant::now() && self.in_flight.load(Ordering::Relaxed) == 0 {
+                return;
+            }
+            tokio::time::sleep_until(deadline.max(Instant::now() + Duration::from_millis(100))).await;
+        }
+    }
+}
+
+/// Serves `app` over HTTP/1.1 (and HTTP/2 if enabled) until `shutdown` resolves, then waits for open connections to finish
+/// Same accept loop as `axum::serve`, with keep-alive and per-IP limits from `[server]`
+/// With a `tls` acceptor, every connection is a TLS one (no plain HTTP fallback)
+pub async fn serve(
+    listener: TcpListener,
+    app: Router,
+    settings: ConnectionSettings,
+    tls: Option<TlsAcceptor>,
+    shutdown: impl Future<Output = ()>,
+) -> std::io::Result<()> {
+    let limiter = Arc::new(ConnectionLimiter {
+        max_per_ip: settings.max_connections_per_ip,
+        open: Mutex::new(HashMap::new()),
+    });
+    // Dropped on shutdown: connections finish their current request and close
+    let (shutdown_tx, shutdown_rx) = watch::channel(());
+    // Every connection holds a receiver, all dropped once the last one closes
+    let (closed_tx, closed_rx) = watch::channel(());
+    tokio::pin!(shutdown);
+
+    loop {
+        let (stream, remote_addr) = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    if !is_connection_error(&e) {
+                        // e.g. EMFILE: give open connections a chance to close
+                        tracing::error!("Failed to accept connection: {}", e);
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                    }
+                    continue;
+                }
+            },
+            _ = &mut shutdown => break,
+        };
+
+        let Some(slot) = ConnectionLimiter::acquire(&limiter, remote_addr.ip()) else {
+            tracing::debug!("Connection limit reached for {}, closing connection", remote_addr.ip());
+            continue;
+        };
+
+        if let Err(e) = stream.set_nodelay(settings.tcp_nodelay) {
+            tracing::trace!("Failed to set TCP_NODELAY: {}", e);
+        }
+
+        let activity = Arc::new(ConnectionActivity {
+            in_flight: AtomicUsize::new(0),
+            last_active: Mutex::new(Instant::now()),
+        });
+        let service = {
+            let app = app.clone();
+            let activity = Arc::clone(&activity);
+            service_fn(move |request: Request<Incoming>| {
+                activity.begin();
+                let response = app.clone().oneshot(request);
+                let activity = Arc::clone(&activity);
+                async move {
+                    let response = response.await;
+                    activity.end();
+                    response
+                }
+            })
+        };
+
+        let shutdown_rx = shutdown_rx.clone();
+        let closed_rx = closed_rx.clone();
+        let tls = tls.clone();
+        tokio::spawn(async move {
+            match tls {
+                Some(acceptor) => match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                    Ok(Ok(stream)) => {
+                        serve_connection(TokioIo::new(stream), service, &activity, settings, shutdown_rx, remote_addr).await;
+                    }
+                    Ok(Err(e)) => tracing::debug!("TLS handshake with {} failed: {}", remote_addr, e),
+                    Err(_) => tracing::debug!("TLS handshake with {} timed out", remote_addr),
+                },
+                None => {
+                    serve_connection(TokioIo::new(stream), service, &activity, settings, shutdown_rx, remote_addr).await;
+                }
+            }
+
+            drop(slot);
+            drop(closed_rx);
+        });
+    }
+
+    drop(listener);
+    drop(shutdown_tx);
+    drop(shutdown_rx);
+    drop(closed_rx);
+    closed_tx.closed().await;
+    Ok(())
+}
+
+/// Serves HTTP on an accepted connection (plain TCP or TLS)
+async fn serve_connection<I, S>(
+    io: I,
+    service: S,
+    activity: &ConnectionActivity,
+    settings: ConnectionSettings,
+    shutdown_rx: watch::Receiver<()>,
+    remote_addr: std::net::SocketAddr,
+) where
+    I: hyper::rt::Read + hyper::rt::Write + Unpin + Send + 'static,
+    S: hyper::service::Service<Request<Incoming>, Response = axum::response::Response, Error = std::convert::Infallible>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+{
+    if settings.http2 {
+        // Detects the protocol from the connection preface (h2c prior knowledge or HTTP/1.1)
+        let mut builder = hyper_util::server::conn::auto::Builder::new(TokioExecutor::new());
+        builder
+            .http1()
+            .timer(TokioTimer::new())
+            .keep_alive(settings.keep_alive)
+            .header_read_timeout(settings.keep_alive_timeout)
+            .http2()
+            .timer(TokioTimer::new())
+            .keep_alive_interval(settings.http2_keep_alive_interval);
+        let conn = builder.serve_connection(io, service);
+        drive_connection(conn, activity, settings, shutdown_rx, remote_addr).await;
+    } else {
+        let mut builder = hyper::server::conn::http1::Builder::new();
+        builder
+            .timer(TokioTimer::new())
+            .keep_alive(settings.keep_alive)
+            .header_read_timeout(settings.keep_alive_timeout);
+        let conn = builder.serve_connection(io, service);
+        drive_connection(conn, activity, settings, shutdown_rx, remote_addr).await;
+    }
+}
+
+/// Runs a connection to completion, shutting it down gracefully on server shutdown or once idle
+async fn drive_connection<C>(
+    conn: C,
+    activity: &ConnectionActivity,
+    settings: ConnectionSettings,
+    mut shutdown_rx: watch::Receiver<()>,
+    remote_addr: std::net::SocketAddr,
+) where
+    C: GracefulConnection,
+    C::Error: std::fmt::Display,
+{
+    tokio::pin!(conn);
+
+    let mut closing = false;
+    loop {
+        tokio::select! {
+            result = conn.as_mut() => {
+                if let Err(e) = result {
+                    tracing::trace!("Connection from {} closed with error: {}", remote_addr, e);
+                }
+                break;
+            }
+            _ = shutdown_rx.changed(), if !closing => {
+                conn.as_mut().graceful_shutdown();
+                closing = true;
+            }
+            _ = async {
+                match settings.keep_alive_timeout {
+                    Some(timeout) => activity.idle_for(timeout).await,
+                    None => std::future::pending().await,
+                }
+            }, if !closing => {
+                tracing::trace!("Closing idle connection from {}", remote_addr);
+                conn.as_mut().graceful_shutdown();
+                closing = true;
+            }
+        }
+    }
+}
+
+fn is_connection_error(e: &std::io::Error) -> bool {
+    matches!(
+        e.kind(),
+        std::io::ErrorKind::ConnectionRefused
+            | std::io::ErrorKind::ConnectionAborted
+            | std::io::ErrorKind::ConnectionReset
+    )
+}
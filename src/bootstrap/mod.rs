@@ -1,4 +1,7 @@
 pub mod config;
+pub mod http;
 pub mod logging;
 pub mod router;
+pub mod scaffold;
 pub mod server;
+pub mod tls;
@@ -1,10 +1,22 @@
-use lighty_api::{get_server_metadata, list_servers, serve_file, AppState};
+use lighty_api::{
+    favicon, freeze_server, get_file_meta, get_server_bundle, get_server_launch, get_server_manifest, get_server_tree, get_maintenance, get_server_metadata, get_stats, get_ready, get_tasks, get_version,
+    list_servers, promote_server, purge_server, request_id, require_admin_token, reupload_server,
+    serve_file, serve_file_by_hash, set_maintenance, start_drain, stop_drain, verify_server, AppState, X_REQUEST_ID,
+};
 use lighty_config::Config;
-use axum::{http::StatusCode, routing::get, Router};
+use axum::{
+    http::StatusCode,
+    middleware,
+    routing::{get, post},
+    Router,
+};
 use std::time::Duration;
 use tower::limit::ConcurrencyLimitLayer;
 use tower_http::{
-    compression::CompressionLayer,
+    compression::{
+        predicate::{NotForContentType, Predicate},
+        CompressionLayer, DefaultPredicate,
+    },
     cors::{Any, CorsLayer},
     limit::RequestBodyLimitLayer,
     timeout::TimeoutLayer,
@@ -18,6 +30,13 @@ pub fn build(config: &Config, app_state: AppState) -> Router {
     let mut router = Router::new()
         .route("/", get(list_servers))
         .route("/:server_name.json", get(get_server_metadata))
+        .route("/:server_name/manifest.json", get(get_server_manifest))
+        .route("/:server_name/launch.json", get(get_server_launch))
+        .route("/:server_name/tree.json", get(get_server_tree))
+        .route("/:server_name/bundle.zip", get(get_server_bundle))
+        .route("/:server_name/meta/*path", get(get_file_meta))
+        .route("/:server_name/by-hash/:sha1", get(serve_file_by_hash))
+        .route("/favicon.ico", get(favicon))
         .fallback(serve_file)
         .layer(ConcurrencyLimitLayer::new(max_concurrent_requests))
         .layer(RequestBodyLimitLayer::new(max_body_size))
@@ -25,11 +44,34 @@ pub fn build(config: &Config, app_state: AppState) -> Router {
 
     // Optionally enable compression based on config
     if config.server.enable_compression {
-        router = router.layer(CompressionLayer::new());
+        // Bundles are already deflated, compressing them again only burns CPU
+        let predicate = DefaultPredicate::new().and(NotForContentType::const_new("application/zip"));
+        router = router.layer(CompressionLayer::new().compress_when(predicate));
     }
 
+    // Registered after the limit layers so diagnostics stay reachable under load
+    router = router
+        .route("/version", get(get_version))
+        .route("/ready", get(get_ready));
+
+    // Admin routes also skip the request timeout (re-uploads can be long)
+    let admin = Router::new()
+        .route("/admin/reupload/:server_name", post(reupload_server))
+        .route("/admin/stats", get(get_stats))
+        .route("/admin/tasks", get(get_tasks))
+        .route("/admin/maintenance", get(get_maintenance).post(set_maintenance))
+        .route("/admin/drain", post(start_drain).delete(stop_drain))
+        .route("/admin/freeze/:server_name", post(freeze_server))
+        .route("/admin/promote/:server_name", post(promote_server))
+        .route("/admin/purge/:server_name", post(purge_server))
+        .route("/admin/verify/:server_name", post(verify_server))
+        .route_layer(middleware::from_fn_with_state(app_state.clone(), require_admin_token));
+    router = router.merge(admin);
+
+    // Outermost so every response, including CORS and limit rejections, carries the request ID
     router
         .layer(build_cors_layer(&config.server.allowed_origins))
+        .layer(middleware::from_fn(request_id))
         .with_state(app_state)
 }
 
@@ -39,6 +81,7 @@ fn build_cors_layer(allowed_origins: &[String]) -> CorsLayer {
             .allow_origin(Any)
             .allow_methods(Any)
             .allow_headers(Any)
+            .expose_headers([X_REQUEST_ID.clone()])
     } else {
         let origins: Vec<_> = allowed_origins
             .iter()
@@ -48,5 +91,6 @@ fn build_cors_layer(allowed_origins: &[String]) -> CorsLayer {
             .allow_origin(origins)
             .allow_methods(Any)
             .allow_headers(Any)
+            .expose_headers([X_REQUEST_ID.clone()])
     }
 }
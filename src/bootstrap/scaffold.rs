@@ -0,0 +1,56 @@
+use lighty_config::{scaffold_server_block, scaffold_template, Config, FileCategory};
+use lighty_filesystem::FileSystem;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// `--scaffold-config [base_path]`: the base path to scan, if the flag is set
+/// Without a path, the template's `base_path` is scanned
+pub fn requested() -> Option<Option<String>> {
+    let mut args = std::env::args().skip(1).skip_while(|arg| arg != "--scaffold-config");
+    args.next()?;
+    Some(args.next().filter(|arg| !arg.starts_with("--")))
+}
+
+/// Writes a new config at `config_path` with a `[[servers]]` block per folder of the base path
+/// Only lists directories, files are not scanned
+/// Built in memory and renamed into place, so a failure leaves no partial config behind
+pub async fn scaffold_config(config_path: &str, base_path: Option<&str>) -> Result<()> {
+    let path = Path::new(config_path);
+    if path.exists() {
+        anyhow::bail!("{} already exists, refusing to overwrite it", config_path);
+    }
+
+    let base_path = base_path.map(FileSystem::get_absolute_path_string).transpose()?;
+    let mut content = scaffold_template(base_path.as_deref());
+
+    // Parsed like at startup so a relative base_path resolves the same way (base_path_root)
+    let config = Config::from_toml_str(&content)?;
+    let base_path = Path::new(config.server.base_path.as_ref());
+    let folders = FileSystem::list_subdirectories(base_path)
+        .await
+        .with_context(|| format!("Failed to read base path {}", base_path.display()))?;
+
+    for name in &folders {
+        let server_path = base_path.join(name);
+        let categories: Vec<_> = FileCategory::ALL
+            .into_iter()
+            .filter(|category| server_path.join(category.dir()).is_dir())
+            .collect();
+        content.push_str(&scaffold_server_block(name, &categories));
+    }
+
+    let temp_path = PathBuf::from(format!("{}.tmp", config_path));
+    tokio::fs::write(&temp_path, content).await?;
+    if let Err(e) = tokio::fs::rename(&temp_path, path).await {
+        let _ = tokio::fs::remove_file(&temp_path).await;
+        return Err(e).with_context(|| format!("Failed to move {} into place", temp_path.display()));
+    }
+
+    tracing::info!(
+        "Scaffolded {} with {} server(s) from {}, review loader, versions and main_class before starting",
+        config_path,
+        folders.len(),
+        base_path.display()
+    );
+    Ok(())
+}
@@ -21,7 +21,10 @@ pub async fn initialize_folders(config: &Config, events: &Arc<EventBus>) -> Resu
             path: path.clone(),
         });
 
-        FileSystem::ensure_server_structure(config.server.base_path.as_ref(), server_config.name.as_ref()).await?;
+        FileSystem::ensure_server_structure(config.server.base_path.as_ref(), server_config.name.as_ref(), |category| {
+            server_config.category_dir(category)
+        })
+        .await?;
 
         events.emit(AppEvent::ServerFolderCreated {
             name: server_config.name.to_string(),
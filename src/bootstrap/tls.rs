@@ -0,0 +1,170 @@
+use lighty_config::{Config, WatcherBackend};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use rustls_pki_types::pem::PemObject;
+use rustls_pki_types::{CertificateDer, PrivateKeyDer};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio_rustls::rustls::crypto::{ring, CryptoProvider};
+use tokio_rustls::rustls::server::{ClientHello, ResolvesServerCert};
+use tokio_rustls::rustls::sign::CertifiedKey;
+use tokio_rustls::rustls::ServerConfig;
+use tokio_rustls::TlsAcceptor;
+
+/// Native TLS termination from `[tls]`, with the certificate swapped in place when its files change
+pub struct TlsTermination {
+    acceptor: TlsAcceptor,
+    certificate: Arc<ReloadableCertificate>,
+}
+
+/// Certificate handed to every new handshake, replaced on reload (open connections keep theirs)
+#[derive(Debug)]
+struct ReloadableCertificate {
+    provider: Arc<CryptoProvider>,
+    cert_path: PathBuf,
+    key_path: PathBuf,
+    current: RwLock<Arc<CertifiedKey>>,
+}
+
+impl ReloadableCertificate {
+    fn load(&self) -> anyhow::Result<CertifiedKey> {
+        load_certified_key(&self.provider, &self.cert_path, &self.key_path)
+    }
+
+    fn reload(&self) {
+        match self.load() {
+            Ok(certified_key) => {
+                *self.current.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(certified_key);
+                tracing::info!("TLS certificate reloaded from {}", self.cert_path.display());
+            }
+            // e.g. the certificate was renewed but the key not written yet: the next event retries
+            Err(e) => tracing::warn!("Failed to reload TLS certificate, keeping the current one: {}", e),
+        }
+    }
+}
+
+impl ResolvesServerCert for ReloadableCertificate {
+    fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        Some(Arc::clone(&self.current.read().unwrap_or_else(|e| e.into_inner())))
+    }
+}
+
+fn load_certified_key(provider: &CryptoProvider, cert_path: &Path, key_path: &Path) -> anyhow::Result<CertifiedKey> {
+    let certs = CertificateDer::pem_file_iter(cert_path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| anyhow::anyhow!("Failed to read TLS certificate {}: {}", cert_path.display(), e))?;
+    if certs.is_empty() {
+        anyhow::bail!("No certificate found in {}", cert_path.display());
+    }
+
+    let key = PrivateKeyDer::from_pem_file(key_path)
+        .map_err(|e| anyhow::anyhow!("Failed to read TLS private key {}: {}", key_path.display(), e))?;
+
+    CertifiedKey::from_der(certs, key, provider)
+        .map_err(|e| anyhow::anyhow!("Invalid TLS certificate or key: {}", e))
+}
+
+impl TlsTermination {
+    /// Loads the certificate of an enabled `[tls]` section (None when disabled)
+    /// ALPN offers h2 only with `server.http2`
+    pub fn from_config(config: &Config) -> anyhow::Result<Option<Self>> {
+        let tls = &config.tls;
+        if !tls.enabled {
+            return Ok(None);
+        }
+        if tls.cert_path.is_empty() || tls.key_path.is_empty() {
+            anyhow::bail!("[tls] is enabled but cert_path or key_path is empty");
+        }
+
+        // Explicit provider: the S3 SDK may enable a second one, making the process default ambiguous
+        let provider = Arc::new(ring::default_provider());
+        let cert_path = PathBuf::from(&tls.cert_path);
+        let key_path = PathBuf::from(&tls.key_path);
+        let certified_key = load_certified_key(&provider, &cert_path, &key_path)?;
+
+        let certificate = Arc::new(ReloadableCertificate {
+            provider: Arc::clone(&provider),
+            cert_path,
+            key_path,
+            current: RwLock::new(Arc::new(certified_key)),
+        });
+
+        let mut server_config = ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .map_err(|e| anyhow::anyhow!("Failed to configure TLS: {}", e))?
+            .with_no_client_auth()
+            .with_cert_resolver(Arc::clone(&certificate) as Arc<dyn ResolvesServerCert>);
+        server_config.alpn_protocols = if config.server.http2 {
+            vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+        } else {
+            vec![b"http/1.1".to_vec()]
+        };
+
+        tracing::info!("TLS enabled with certificate {}", tls.cert_path);
+        Ok(Some(Self {
+            acceptor: TlsAcceptor::from(Arc::new(server_config)),
+            certificate,
+        }))
+    }
+
+    pub fn acceptor(&self) -> TlsAcceptor {
+        self.acceptor.clone()
+    }
+
+    /// Reloads the certificate when the certificate or key file changes
+    /// Their folders are watched rather than the files, so atomic replaces (certbot symlinks) are seen
+    pub fn watch(&self, config: &Config) -> anyhow::Result<tokio::task::JoinHandle<()>> {
+        let files = &config.hot_reload.files;
+        let debounce = Duration::from_millis(config.hot_reload.config.debounce_ms);
+        let certificate = Arc::clone(&self.certificate);
+        let watched_files: HashSet<PathBuf> = [&certificate.cert_path, &certificate.key_path]
+            .into_iter()
+            .map(|path| absolute(path))
+            .collect();
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(16);
+        let handler = {
+            let watched_files = watched_files.clone();
+            move |res: Result<Event, notify::Error>| {
+                if let Ok(event) = res {
+                    // Access events excluded: reloading reads the files
+                    let written = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_));
+                    if written && event.paths.iter().any(|path| watched_files.contains(path)) {
+                        let _ = tx.blocking_send(());
+                    }
+                }
+            }
+        };
+
+        let mut watcher: Box<dyn Watcher + Send> = match files.watcher {
+            WatcherBackend::Native => Box::new(notify::recommended_watcher(handler)?),
+            WatcherBackend::Poll => {
+                let poll_config = notify::Config::default()
+                    .with_poll_interval(Duration::from_millis(files.poll_interval_ms));
+                Box::new(notify::PollWatcher::new(handler, poll_config)?)
+            }
+        };
+
+        let folders: HashSet<_> = watched_files.iter().filter_map(|path| path.parent()).collect();
+        for folder in folders {
+            watcher.watch(folder, RecursiveMode::NonRecursive)?;
+        }
+
+        Ok(tokio::spawn(async move {
+            // Keep watcher alive for the entire loop
+            let _watcher = watcher;
+            while rx.recv().await.is_some() {
+                // Certificate and key are usually written together: reload once both are in place
+                tokio::time::sleep(debounce).await;
+                while rx.try_recv().is_ok() {}
+                certificate.reload();
+            }
+        }))
+    }
+}
+
+/// Event paths are absolute, relative `[tls]` paths are resolved against the working directory
+fn absolute(path: &Path) -> PathBuf {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
@@ -1,26 +1,33 @@
 mod bootstrap;
 
-use lighty_api::AppState;
+use lighty_api::{AppState, VersionInfo};
 use lighty_events::{AppEvent, EventBus};
 use lighty_cache::CacheManager;
 use lighty_watcher::ConfigWatcher;
-use lighty_storage::{LocalBackend, StorageBackend};
+use lighty_storage::{LocalBackend, StorageBackend, StorageError};
 #[cfg(feature = "s3")]
 use lighty_storage::S3Backend;
+#[cfg(feature = "gcs")]
+use lighty_storage::GcsBackend;
 use lighty_config::StorageBackend as StorageBackendType;
-use crate::bootstrap::{config, logging, router, server};
+use crate::bootstrap::{config, http, logging, router, scaffold, server, tls};
 use anyhow::Result;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     logging::initialize();
 
+    let config_path = std::env::var("LIGHTY_CONFIG").unwrap_or_else(|_| "config.toml".to_string());
+    if let Some(base_path) = scaffold::requested() {
+        return scaffold::scaffold_config(&config_path, base_path.as_deref()).await;
+    }
+
     let events = EventBus::new(true);
     events.emit(AppEvent::Starting);
 
-    let config_path = std::env::var("LIGHTY_CONFIG").unwrap_or_else(|_| "config.toml".to_string());
-    let config = config::load(&config_path, &events).await?;
+    let config = config::load(&config_path, config::migration_enabled(), &events).await?;
 
     server::initialize_folders(&config, &events).await?;
 
@@ -56,23 +63,33 @@ async fn main() -> Result<()> {
 
     cache_manager.start_auto_rescan().await;
 
-    let (app, addr, tcp_nodelay, base_url) = {
+    let (app, addr, connection_settings, base_url, tls) = {
         let config_read = config.read().await;
         let app_state = AppState::new(
             Arc::clone(&cache_manager),
-            config_read.server.base_url.to_string(),
-            config_read.server.base_path.to_string(),
-            config_read.server.streaming_threshold_mb,
+            &config_read,
+            VersionInfo {
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                git_commit: env!("LIGHTY_GIT_HASH").to_string(),
+                build_timestamp: env!("LIGHTY_BUILD_TIMESTAMP").to_string(),
+            },
         );
         let app = router::build(&config_read, app_state);
         let addr = format!("{}:{}", config_read.server.host, config_read.server.port);
+        let tls = tls::TlsTermination::from_config(&config_read)?;
+        let tls = match tls {
+            Some(tls) => Some((tls.acceptor(), tls.watch(&config_read)?)),
+            None => None,
+        };
         (
             app,
             addr,
-            config_read.server.tcp_nodelay,
+            http::ConnectionSettings::from(&config_read.server),
             config_read.server.base_url.to_string(),
+            tls,
         )
     };
+    let (tls_acceptor, tls_watcher_handle) = tls.unzip();
 
     let listener = bind_server(&addr).await?;
 
@@ -88,21 +105,49 @@ async fn main() -> Result<()> {
         tracing::info!("Shutdown signal received, initiating graceful shutdown...");
     };
 
-    axum::serve(listener, app.into_make_service())
-        .tcp_nodelay(tcp_nodelay)
-        .with_graceful_shutdown(shutdown_signal)
-        .await?;
+    http::serve(listener, app, connection_settings, tls_acceptor, shutdown_signal).await?;
 
     // Graceful shutdown: wait for config watcher to stop
     config_watcher_handle.abort();
     let _ = config_watcher_handle.await;
+    if let Some(handle) = tls_watcher_handle {
+        handle.abort();
+    }
 
     cache_manager.shutdown().await;
     events.emit(AppEvent::Shutdown);
     Ok(())
 }
 
+/// Creates the storage backend, retrying with exponential backoff while its endpoint is unreachable
+/// Other errors (bad configuration) fail at once
 async fn initialize_storage(config: &Arc<tokio::sync::RwLock<lighty_config::Config>>) -> Result<Arc<dyn StorageBackend>> {
+    const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+    const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+    let retries = config.read().await.storage.init_retries;
+    let mut attempt = 0;
+    loop {
+        match create_storage_backend(config).await {
+            Ok(backend) => return Ok(backend),
+            Err(e) if attempt < retries && e.downcast_ref::<StorageError>().is_some_and(StorageError::is_transient) => {
+                let backoff = INITIAL_BACKOFF.saturating_mul(2u32.saturating_pow(attempt)).min(MAX_BACKOFF);
+                attempt += 1;
+                tracing::warn!(
+                    "Storage initialization attempt {}/{} failed: {}. Retrying in {:?}...",
+                    attempt,
+                    retries + 1,
+                    e,
+                    backoff
+                );
+                tokio::time::sleep(backoff).await;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+async fn create_storage_backend(config: &Arc<tokio::sync::RwLock<lighty_config::Config>>) -> Result<Arc<dyn StorageBackend>> {
     let config_read = config.read().await;
 
     match config_read.storage.backend {
@@ -144,6 +189,30 @@ async fn initialize_storage(config: &Arc<tokio::sync::RwLock<lighty_config::Conf
                 Note: S3 support requires cmake to be installed on your system."
             )
         }
+        #[cfg(feature = "gcs")]
+        StorageBackendType::Gcs => {
+            if !config_read.storage.gcs.enabled {
+                anyhow::bail!("GCS backend selected but not enabled in configuration");
+            }
+
+            let backend = GcsBackend::new(
+                config_read.storage.gcs.bucket_name.to_string(),
+                std::path::Path::new(&config_read.storage.gcs.credentials_path),
+                config_read.storage.gcs.public_url.to_string(),
+                config_read.storage.gcs.bucket_prefix.to_string(),
+            ).await?;
+
+            tracing::info!(
+                "Initialized GCS storage backend: bucket={}",
+                config_read.storage.gcs.bucket_name
+            );
+
+            Ok(Arc::new(backend) as Arc<dyn StorageBackend>)
+        }
+        #[cfg(not(feature = "gcs"))]
+        StorageBackendType::Gcs => {
+            anyhow::bail!("GCS backend selected but not compiled. Rebuild with --features gcs to enable GCS support.")
+        }
     }
 }
 
@@ -153,14 +222,14 @@ async fn initialize_cdn(
     let config_read = config.read().await;
 
     if config_read.cdn.enabled {
-        let client = lighty_cache::CdnClient::new(
-            &config_read.cdn.provider,
-            config_read.cdn.zone_id.clone(),
-            config_read.cdn.api_token.clone(),
-        );
+        let client = lighty_cache::CdnClient::from_settings(&config_read.cdn);
+        if client.zone_count() == 0 {
+            tracing::warn!("CDN purging is enabled but no zone is configured");
+        }
         tracing::info!(
-            "Initialized CDN cache purge client (provider: {})",
-            config_read.cdn.provider
+            "Initialized CDN cache purge client (provider: {}, {} zones)",
+            config_read.cdn.provider,
+            client.zone_count()
         );
         Some(Arc::new(client))
     } else {
@@ -174,11 +243,11 @@ async fn initialize_cloudflare(
     let config_read = config.read().await;
 
     if config_read.cloudflare.enabled {
-        let client = lighty_cache::CloudflareClient::new(
-            config_read.cloudflare.zone_id.clone(),
-            config_read.cloudflare.api_token.clone(),
-        );
-        tracing::info!("Initialized Cloudflare API cache purge client");
+        let client = lighty_cache::CloudflareClient::from_settings(&config_read.cloudflare);
+        if client.zone_count() == 0 {
+            tracing::warn!("Cloudflare purging is enabled but no zone is configured");
+        }
+        tracing::info!("Initialized Cloudflare API cache purge client ({} zones)", client.zone_count());
         Some(Arc::new(client))
     } else {
         None
@@ -188,7 +257,7 @@ async fn initialize_cloudflare(
 async fn bind_server(addr: &str) -> Result<tokio::net::TcpListener> {
     tokio::net::TcpListener::bind(addr).await.map_err(|e| {
         if e.kind() == std::io::ErrorKind::AddrInUse {
-            let port = addr.split(':').last().unwrap_or("unknown");
+            let port = addr.split(':').next_back().unwrap_or("unknown");
             tracing::error!("❌ Port {} is already in use", port);
             tracing::error!("Another application is using this port");
             tracing::error!("Solutions:");